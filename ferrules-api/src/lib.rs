@@ -3,33 +3,76 @@ use opentelemetry_otlp::WithExportConfig;
 use opentelemetry_sdk::Resource;
 use opentelemetry_semantic_conventions::resource::SERVICE_NAME;
 
+pub mod metrics;
+pub mod parse_cache;
+pub mod store;
+pub mod transport;
+pub mod worker;
+
 use tracing_subscriber::fmt::format::FmtSpan;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
 use tracing_subscriber::EnvFilter;
+use tracing_subscriber::Layer;
 
+/// Sets up the global `tracing` subscriber for the API process.
+///
+/// `otlp_endpoint` is optional: when `None`, only the stdout/stderr fmt layer is installed and
+/// no OTLP exporter or tracer provider is created. `json_output` swaps the fmt layer to emit
+/// one JSON object per event (for ingestion into log pipelines) instead of pretty-printed text.
+///
+/// Returns the OTLP [`TracerProvider`], if one was installed, so the caller can flush it with
+/// [`global::shutdown_tracer_provider`] on shutdown.
 pub fn init_tracing(
-    // TODO Optional
-    otlp_endpoint: &str,
+    otlp_endpoint: Option<&str>,
     otlp_service_name: String,
     json_output: bool,
-) -> anyhow::Result<()> {
-    let provider = opentelemetry_sdk::trace::TracerProvider::builder()
-        .with_batch_exporter(
-            opentelemetry_otlp::SpanExporter::builder()
-                .with_tonic()
-                .with_endpoint(otlp_endpoint)
-                .build()?,
-            opentelemetry_sdk::runtime::Tokio,
+) -> anyhow::Result<Option<opentelemetry_sdk::trace::TracerProvider>> {
+    let mut layers = Vec::new();
+
+    // STDOUT/STDERR layer
+    let fmt_layer = tracing_subscriber::fmt::layer()
+        .with_line_number(true)
+        .with_thread_names(true)
+        .with_span_events(FmtSpan::NEW | FmtSpan::CLOSE)
+        .with_timer(tracing_subscriber::fmt::time::uptime());
+
+    let fmt_layer = if json_output {
+        tracing_subscriber::Layer::boxed(
+            fmt_layer.json().flatten_event(true).with_current_span(true),
         )
-        .with_resource(Resource::new(vec![KeyValue::new(
-            SERVICE_NAME,
-            otlp_service_name,
-        )]))
-        .build();
-    let tracer = provider.tracer("default_tracer_name");
-    global::set_tracer_provider(provider);
-    let otel_layer = tracing_opentelemetry::layer().with_tracer(tracer);
+    } else {
+        tracing_subscriber::Layer::boxed(fmt_layer.pretty())
+    };
+    layers.push(fmt_layer);
+
+    // OpenTelemetry tracing layer
+    let mut tracer_provider = None;
+    if let Some(otlp_endpoint) = otlp_endpoint {
+        let provider = opentelemetry_sdk::trace::TracerProvider::builder()
+            .with_batch_exporter(
+                opentelemetry_otlp::SpanExporter::builder()
+                    .with_tonic()
+                    .with_endpoint(otlp_endpoint)
+                    .build()?,
+                opentelemetry_sdk::runtime::Tokio,
+            )
+            .with_resource(Resource::new(vec![KeyValue::new(
+                SERVICE_NAME,
+                otlp_service_name,
+            )]))
+            .build();
+        let tracer = provider.tracer("default_tracer_name");
+        global::set_tracer_provider(provider.clone());
+        layers.push(tracing_opentelemetry::layer().with_tracer(tracer).boxed());
+        tracer_provider = Some(provider);
+
+        // So a `traceparent` header from an upstream caller (see `request_id_middleware`)
+        // stitches into the same trace instead of always starting a new one.
+        global::set_text_map_propagator(
+            opentelemetry_sdk::propagation::TraceContextPropagator::new(),
+        );
+    }
 
     let env_filter = EnvFilter::try_from_env("LOG_LEVEL").unwrap_or_else(|_| {
         EnvFilter::new(
@@ -37,76 +80,50 @@ pub fn init_tracing(
         )
     });
 
-    let fmt_layer = tracing_subscriber::fmt::layer()
-        .pretty()
-        .with_line_number(true)
-        .with_thread_names(true)
-        .with_span_events(FmtSpan::NEW | FmtSpan::CLOSE)
-        .with_timer(tracing_subscriber::fmt::time::uptime());
-
     tracing_subscriber::registry()
         .with(env_filter)
-        .with(fmt_layer)
-        .with(otel_layer)
+        .with(layers)
         .init();
-    // TODO: return guard to flush
-    Ok(())
-}
 
-// pub fn init_tracing(
-//     otlp_endpoint: Option<&str>,
-//     otlp_service_name: String,
-//     json_output: bool,
-// ) -> bool {
-//     let mut layers = Vec::new();
-
-//     // STDOUT/STDERR layer
-//     let fmt_layer = tracing_subscriber::fmt::layer()
-//         .with_file(true)
-//         .with_line_number(true);
-
-//     let fmt_layer = match json_output {
-//         true => tracing_subscriber::Layer::boxed(fmt_layer.json().flatten_event(true)),
-//         false => tracing_subscriber::Layer::boxed(fmt_layer),
-//     };
-//     layers.push(fmt_layer);
+    Ok(tracer_provider)
+}
 
-//     // OpenTelemetry tracing layer
-//     let mut global_tracer = false;
-//     if let Some(otlp_endpoint) = otlp_endpoint {
-//         global::set_text_map_propagator(
-//             opentelemetry_sdk::propagation::TraceContextPropagator::new(),
-//         );
+/// Response header carrying the id [`request_id_middleware`] assigns, so operators can grep
+/// one id across the fmt logs and the OTLP backend for a single request.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
 
-//         let tracer = opentelemetry_otlp::new_pipeline()
-//             .tracing()
-//             .with_exporter(
-//                 opentelemetry_otlp::new_exporter()
-//                     .tonic()
-//                     .with_endpoint(otlp_endpoint),
-//             )
-//             .with_trace_config(
-//                 Config::default()
-//                     .with_resource(Resource::new(vec![KeyValue::new(
-//                         SERVICE_NAME,
-//                         otlp_service_name,
-//                     )]))
-//                     .with_sampler(Sampler::AlwaysOn),
-//             )
-//             .install_batch(opentelemetry_sdk::runtime::Tokio);
+/// Assigns every inbound request a UUIDv4 id, records it as the `request.id` field on the span
+/// this middleware opens (present in both pretty and JSON fmt output), and echoes it back in
+/// the [`REQUEST_ID_HEADER`] response header. Also extracts a `traceparent` header from the
+/// request, if present, so the span becomes a child of the upstream caller's trace rather than
+/// starting a new one -- see [`TraceContextPropagator`] installed in [`init_tracing`].
+///
+/// [`TraceContextPropagator`]: opentelemetry_sdk::propagation::TraceContextPropagator
+pub async fn request_id_middleware(
+    req: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    use tracing::Instrument;
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
 
-//         let tracer = tracer.unwrap();
-//         layers.push(tracing_opentelemetry::layer().with_tracer(tracer).boxed());
-//         global_tracer = true;
-//     }
+    let request_id = uuid::Uuid::new_v4().to_string();
+    let span = tracing::info_span!("http_request", request.id = %request_id);
 
-//     // Filter events with LOG_LEVEL
-//     let env_filter = EnvFilter::try_from_env("LOG_LEVEL")
-//         .unwrap_or_else(|_| EnvFilter::new("ferrules_api=debug,ferrules_core=debug"));
+    let parent_cx = global::get_text_map_propagator(|propagator| {
+        propagator.extract(&opentelemetry_http::HeaderExtractor(req.headers()))
+    });
+    span.set_parent(parent_cx);
 
-//     tracing_subscriber::registry()
-//         .with(env_filter)
-//         .with(layers)
-//         .init();
-//     global_tracer
-// }
+    async move {
+        let mut response = next.run(req).await;
+        if let Ok(value) = axum::http::HeaderValue::from_str(&request_id) {
+            response.headers_mut().insert(
+                axum::http::HeaderName::from_static(REQUEST_ID_HEADER),
+                value,
+            );
+        }
+        response
+    }
+    .instrument(span)
+    .await
+}
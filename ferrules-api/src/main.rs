@@ -5,15 +5,22 @@ use axum::{
         HeaderMap, Response, StatusCode,
     },
     response::{IntoResponse, Sse},
-    routing::{get, post},
+    routing::{delete, get, post},
     Json, Router,
 };
 use axum_tracing_opentelemetry::middleware::OtelAxumLayer;
 use clap::Parser;
-use ferrules_api::init_tracing;
+use ferrules_api::{
+    init_tracing,
+    parse_cache::{ParseCache, ParseCacheConfig},
+    request_id_middleware,
+    store::{JobStore, QueuedJob},
+    worker,
+};
 use ferrules_core::{
-    layout::model::{ORTConfig, OrtExecutionProvider},
-    render::markdown::to_markdown,
+    layout::model::{ORTConfig, ORTLayoutParser, OrtExecutionProvider},
+    ocr::OcrConfig,
+    render::markdown::{to_markdown, MarkdownFlavor},
     FerrulesParseConfig, FerrulesParser,
 };
 use memmap2::Mmap;
@@ -42,9 +49,14 @@ const MAX_SIZE_LIMIT: usize = 250 * 1024 * 1024;
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// OpenTelemetry collector endpoint
-    #[arg(long, env = "OTLP_ENDPOINT", default_value = "http://localhost:4317")]
-    otlp_endpoint: String,
+    /// OpenTelemetry collector endpoint; when unset, traces aren't exported and only stdout/
+    /// stderr logging is configured
+    #[arg(long, env = "OTLP_ENDPOINT")]
+    otlp_endpoint: Option<String>,
+
+    /// Emit structured JSON logs instead of pretty-printed ones, for ingestion into log pipelines
+    #[arg(long, env = "JSON_LOGS", default_value_t = false)]
+    json_output: bool,
 
     /// Sentry DSN
     #[arg(long, env = "SENTRY_DSN")]
@@ -118,6 +130,70 @@ struct Args {
 
     #[arg(long, short = 'O', help = "Ort graph optimization level")]
     graph_opt_level: Option<usize>,
+
+    /// Path to the SQLite database backing the job store, so job status and results survive a
+    /// restart. Defaults to a file alongside the current working directory.
+    #[arg(long, env = "JOB_STORE_PATH", default_value = "ferrules-jobs.sqlite3")]
+    job_store_path: std::path::PathBuf,
+
+    /// Number of times a failed parse is retried (with exponential backoff) before the job is
+    /// reported as failed, for errors that aren't a cancellation.
+    #[arg(long, env = "PARSE_RETRY_COUNT", default_value_t = 2)]
+    retry_count: usize,
+
+    /// Base delay, in milliseconds, for the exponential backoff between retries. Attempt `n`
+    /// waits `retry_backoff_base_ms * 2^(n - 1)`.
+    #[arg(long, env = "PARSE_RETRY_BACKOFF_BASE_MS", default_value_t = 500)]
+    retry_backoff_base_ms: u64,
+
+    /// Max number of parsed documents kept in the in-memory, content-hash-keyed result cache.
+    #[arg(long, env = "PARSE_CACHE_MAX_ENTRIES", default_value_t = 64)]
+    parse_cache_max_entries: usize,
+
+    /// How long a cached parse result stays valid, in seconds, before it's treated as a miss.
+    #[arg(long, env = "PARSE_CACHE_TTL_SECS", default_value_t = 3600)]
+    parse_cache_ttl_secs: u64,
+
+    /// Directory where an uploaded document's bytes are durably persisted for the lifetime of
+    /// its job, so a crashed or restarted process can still resume it from the job queue instead
+    /// of losing it along with the request's `NamedTempFile`.
+    #[arg(long, env = "JOB_INPUTS_DIR", default_value = "ferrules-job-inputs")]
+    job_inputs_dir: std::path::PathBuf,
+
+    /// Number of background workers draining the durable job queue, recovering jobs orphaned by
+    /// a previous crash. The request that submitted a job still parses it inline; these workers
+    /// only pick up what's left behind.
+    #[arg(long, env = "JOB_WORKER_COUNT", default_value_t = 2)]
+    job_worker_count: usize,
+
+    /// How long a claimed job can go without a heartbeat before its worker is presumed dead and
+    /// the job is re-queued (or dead-lettered, past `job_max_retries`).
+    #[arg(long, env = "JOB_HEARTBEAT_TTL_SECS", default_value_t = 60)]
+    job_heartbeat_ttl_secs: u64,
+
+    /// How many times an orphaned job is re-queued and retried before it's moved to the
+    /// dead-letter state instead of being picked up again.
+    #[arg(long, env = "JOB_MAX_RETRIES", default_value_t = 3)]
+    job_max_retries: usize,
+
+    /// How long, in seconds, a `/parse/sse` job's buffered events stay available for
+    /// `GET /parse/:job_id/stream` to replay after the job reaches a terminal state.
+    #[arg(long, env = "SSE_EVENT_RETENTION_SECS", default_value_t = 3600)]
+    sse_event_retention_secs: u64,
+
+    /// Run the JSON-RPC 2.0 stdio transport instead of the HTTP server, for embedding ferrules
+    /// as a child process. See [`ferrules_api::transport`].
+    #[arg(long, env = "FERRULES_STDIO", default_value_t = false)]
+    stdio: bool,
+
+    /// How long, in seconds, a finished job's record, buffered SSE events, and any leftover
+    /// input file are kept around before the background sweeper reclaims them.
+    #[arg(long, env = "JOB_RETENTION_SECS", default_value_t = 86400)]
+    job_retention_secs: u64,
+
+    /// How often, in seconds, the background sweeper scans for expired jobs to reclaim.
+    #[arg(long, env = "JOB_SWEEP_INTERVAL_SECS", default_value_t = 300)]
+    job_sweep_interval_secs: u64,
 }
 
 fn parse_ep_args(args: &Args) -> Vec<OrtExecutionProvider> {
@@ -143,6 +219,95 @@ struct ApiResponse<T> {
     success: bool,
     data: Option<T>,
     error: Option<String>,
+    /// Stable, machine-readable identifier for `error`'s cause, so callers can branch on it
+    /// without parsing the human-readable message. `None` on a successful response.
+    code: Option<&'static str>,
+}
+
+impl<T> ApiResponse<T> {
+    fn ok(data: T) -> Self {
+        Self {
+            success: true,
+            data: Some(data),
+            error: None,
+            code: None,
+        }
+    }
+}
+
+/// Typed API errors, each mapped to a fixed [`StatusCode`] and a stable `code` so clients can
+/// branch on failure cause without string-matching `error`.
+#[derive(Debug, thiserror::Error)]
+enum ApiError {
+    #[error("failed to create temp file: {0}")]
+    TempFileCreate(std::io::Error),
+    #[error("failed to read multipart field: {0}")]
+    MultipartRead(#[from] axum::extract::multipart::MultipartError),
+    #[error("failed to write to temp file: {0}")]
+    TempFileWrite(std::io::Error),
+    #[error("failed to memory map file: {0}")]
+    MemoryMap(std::io::Error),
+    #[error("failed to parse options: {0}")]
+    InvalidOptions(serde_json::Error),
+    #[error("invalid page range: {0}")]
+    InvalidPageRange(anyhow::Error),
+    #[error("failed to render markdown: {0}")]
+    MarkdownRender(anyhow::Error),
+    #[error("failed to parse document: {0}")]
+    ParseFailed(anyhow::Error),
+    #[error("job {0} not found")]
+    JobNotFound(Uuid),
+    #[error("job {0} is still active and has a live SSE receiver attached")]
+    JobStillActive(Uuid),
+    #[error("job store error: {0}")]
+    JobStore(anyhow::Error),
+}
+
+impl ApiError {
+    fn code(&self) -> &'static str {
+        match self {
+            ApiError::TempFileCreate(_) => "temp_file_create_failed",
+            ApiError::MultipartRead(_) => "multipart_read_failed",
+            ApiError::TempFileWrite(_) => "temp_file_write_failed",
+            ApiError::MemoryMap(_) => "memory_map_failed",
+            ApiError::InvalidOptions(_) => "invalid_options",
+            ApiError::InvalidPageRange(_) => "invalid_page_range",
+            ApiError::MarkdownRender(_) => "markdown_render_failed",
+            ApiError::ParseFailed(_) => "parse_failed",
+            ApiError::JobNotFound(_) => "job_not_found",
+            ApiError::JobStillActive(_) => "job_still_active",
+            ApiError::JobStore(_) => "job_store_error",
+        }
+    }
+
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ApiError::MultipartRead(_)
+            | ApiError::InvalidOptions(_)
+            | ApiError::InvalidPageRange(_) => StatusCode::BAD_REQUEST,
+            ApiError::JobNotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::JobStillActive(_) => StatusCode::CONFLICT,
+            ApiError::TempFileCreate(_)
+            | ApiError::TempFileWrite(_)
+            | ApiError::MemoryMap(_)
+            | ApiError::MarkdownRender(_)
+            | ApiError::ParseFailed(_)
+            | ApiError::JobStore(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> axum::response::Response {
+        let status = self.status_code();
+        let body = Json(ApiResponse::<()> {
+            success: false,
+            data: None,
+            error: Some(self.to_string()),
+            code: Some(self.code()),
+        });
+        (status, body).into_response()
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -151,7 +316,7 @@ struct ParseOptions {
     _save_images: Option<bool>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 #[serde(tag = "type")]
 enum ParseEvent {
     #[serde(rename = "job_started")]
@@ -166,17 +331,237 @@ enum ParseEvent {
     Complete {
         document: serde_json::Value,
         total_pages: usize,
+        /// Whether `document` came from [`ParseCache`] instead of a fresh parse.
+        cache_hit: bool,
     },
     #[serde(rename = "cancelled")]
     Cancelled { message: String },
     #[serde(rename = "error")]
     Error { message: String },
+    #[serde(rename = "retry")]
+    Retry { attempt: usize, delay_ms: u64 },
+}
+
+/// Whether `event` is the last one a job will ever emit, and so the one worth retaining past the
+/// ring buffer's normal rotation.
+fn is_terminal_event(event: &ParseEvent) -> bool {
+    matches!(
+        event,
+        ParseEvent::Complete { .. } | ParseEvent::Cancelled { .. } | ParseEvent::Error { .. }
+    )
+}
+
+/// Bounded ring buffer of a `/parse/sse` job's recent [`ParseEvent`]s, keyed by a monotonically
+/// increasing sequence number. Backs `GET /parse/:job_id/stream`, which lets a client that lost
+/// its SSE connection catch up on everything it missed (via the standard `Last-Event-ID` header)
+/// instead of resubmitting the whole document.
+#[derive(Debug)]
+struct JobEventLog {
+    next_seq: u64,
+    ring: std::collections::VecDeque<(u64, ParseEvent)>,
+    /// The job's terminal event, kept even after it rotates out of `ring`, so a client
+    /// reconnecting long after the job finished still gets the final `complete`/`error`.
+    terminal: Option<(u64, ParseEvent)>,
+    /// When the terminal event was recorded, used by [`EventLogRegistry`] to expire this log
+    /// `retention` after the job actually finished rather than after it was created.
+    terminal_at: Option<std::time::Instant>,
+}
+
+/// Max number of non-terminal events retained per job before the oldest are dropped.
+const EVENT_LOG_CAPACITY: usize = 200;
+
+impl JobEventLog {
+    fn new() -> Self {
+        Self {
+            next_seq: 0,
+            ring: std::collections::VecDeque::new(),
+            terminal: None,
+            terminal_at: None,
+        }
+    }
+
+    /// Assigns the next sequence number to `event`, records it, and returns the stamped pair for
+    /// sending to the live SSE stream.
+    fn record(&mut self, event: ParseEvent) -> (u64, ParseEvent) {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+
+        if is_terminal_event(&event) {
+            self.terminal = Some((seq, event.clone()));
+            self.terminal_at = Some(std::time::Instant::now());
+        }
+        self.ring.push_back((seq, event.clone()));
+        while self.ring.len() > EVENT_LOG_CAPACITY {
+            self.ring.pop_front();
+        }
+
+        (seq, event)
+    }
+
+    /// Every recorded event with a sequence number greater than `last_seen`, oldest first. The
+    /// terminal event is included even if it has since rotated out of `ring`.
+    fn replay_after(&self, last_seen: Option<u64>) -> Vec<(u64, ParseEvent)> {
+        let threshold = last_seen.map_or(0, |seq| seq + 1);
+        let mut events: Vec<(u64, ParseEvent)> = self
+            .ring
+            .iter()
+            .filter(|(seq, _)| *seq >= threshold)
+            .cloned()
+            .collect();
+
+        if let Some((seq, event)) = &self.terminal {
+            if *seq >= threshold && !events.iter().any(|(s, _)| s == seq) {
+                events.push((*seq, event.clone()));
+                events.sort_by_key(|(seq, _)| *seq);
+            }
+        }
+
+        events
+    }
+}
+
+/// Registry of per-job [`JobEventLog`]s, kept independently of [`JobManager::active_jobs`] so a
+/// reconnecting client can still replay a job's events well after it finished and its
+/// `JobManager` entry was removed -- up to `retention`, mirroring the TTL the rest of a
+/// completed job's artifacts are kept for.
+#[derive(Debug, Clone)]
+struct EventLogRegistry {
+    logs: Arc<Mutex<HashMap<Uuid, Arc<Mutex<JobEventLog>>>>>,
+    retention: std::time::Duration,
+}
+
+impl EventLogRegistry {
+    fn new(retention: std::time::Duration) -> Self {
+        Self {
+            logs: Arc::new(Mutex::new(HashMap::new())),
+            retention,
+        }
+    }
+
+    async fn create(&self, job_id: Uuid) -> Arc<Mutex<JobEventLog>> {
+        let log = Arc::new(Mutex::new(JobEventLog::new()));
+        self.logs.lock().await.insert(job_id, log.clone());
+        log
+    }
+
+    /// Looks up `job_id`'s event log, evicting it (and returning `None`) if its terminal event
+    /// was recorded more than `retention` ago.
+    async fn get(&self, job_id: Uuid) -> Option<Arc<Mutex<JobEventLog>>> {
+        let mut logs = self.logs.lock().await;
+        let log = logs.get(&job_id)?.clone();
+
+        let expired = {
+            let log = log.lock().await;
+            log.terminal_at
+                .is_some_and(|at| at.elapsed() > self.retention)
+        };
+        if expired {
+            logs.remove(&job_id);
+            return None;
+        }
+
+        Some(log)
+    }
+
+    /// Drops `job_id`'s event log outright, regardless of `retention`. Used by the sweeper and
+    /// the manual `DELETE /jobs/{job_id}` endpoint once the underlying job record is gone too.
+    async fn remove(&self, job_id: Uuid) {
+        self.logs.lock().await.remove(&job_id);
+    }
+}
+
+/// A single file's outcome within a completed `POST /parse/batch` job.
+#[derive(Debug, Clone, Serialize)]
+struct BatchFileResult {
+    file_index: usize,
+    filename: String,
+    document: serde_json::Value,
+}
+
+/// A single file's failure within a completed `POST /parse/batch` job.
+#[derive(Debug, Clone, Serialize)]
+struct BatchFileFailure {
+    file_index: usize,
+    filename: String,
+    error: String,
+}
+
+/// SSE events for `POST /parse/batch`, modeled on LSP's `WorkDoneProgress` begin/report/end
+/// lifecycle: a single [`BatchParseEvent::BatchStarted`] announces how many documents to expect,
+/// per-file events mirror [`ParseEvent`] but are tagged with `file_index`/`filename` so a client
+/// can tell which upload they refer to, and the stream ends with a single aggregate
+/// [`BatchParseEvent::BatchComplete`] instead of one `complete` per file -- a failed file doesn't
+/// stop the rest from being reported.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type")]
+enum BatchParseEvent {
+    #[serde(rename = "batch_started")]
+    BatchStarted {
+        job_id: Uuid,
+        total_documents: usize,
+    },
+    #[serde(rename = "file_started")]
+    FileStarted { file_index: usize, filename: String },
+    #[serde(rename = "file_progress")]
+    FileProgress {
+        file_index: usize,
+        filename: String,
+        pages_completed: usize,
+        total_pages: usize,
+    },
+    #[serde(rename = "batch_complete")]
+    BatchComplete {
+        results: Vec<BatchFileResult>,
+        failed: Vec<BatchFileFailure>,
+    },
+    #[serde(rename = "cancelled")]
+    Cancelled { message: String },
+}
+
+/// The channel a [`JobHandle`] notifies on cancellation -- either a regular `/parse`/`/parse/sse`
+/// job or a `/parse/batch` job, whose events carry different shapes. The `Single` variant's
+/// events are tagged with their [`JobEventLog`] sequence number so a stamped `id` reaches the SSE
+/// wire event even on the cancellation path.
+#[derive(Debug)]
+enum JobSender {
+    Single(mpsc::Sender<(u64, ParseEvent)>),
+    Batch(mpsc::Sender<BatchParseEvent>),
+}
+
+impl JobSender {
+    /// `seq` is only meaningful for `Single` (see [`JobEventLog::record`]); `Batch` has no event
+    /// log yet and simply ignores it.
+    async fn send_cancelled(&self, seq: u64, message: String) {
+        match self {
+            JobSender::Single(tx) => {
+                let _ = tx.send((seq, ParseEvent::Cancelled { message })).await;
+            }
+            JobSender::Batch(tx) => {
+                let _ = tx.send(BatchParseEvent::Cancelled { message }).await;
+            }
+        }
+    }
+}
+
+impl From<mpsc::Sender<(u64, ParseEvent)>> for JobSender {
+    fn from(tx: mpsc::Sender<(u64, ParseEvent)>) -> Self {
+        JobSender::Single(tx)
+    }
+}
+
+impl From<mpsc::Sender<BatchParseEvent>> for JobSender {
+    fn from(tx: mpsc::Sender<BatchParseEvent>) -> Self {
+        JobSender::Batch(tx)
+    }
 }
 
 #[derive(Debug)]
 struct JobHandle {
     cancellation_token: CancellationToken,
-    tx: mpsc::Sender<ParseEvent>,
+    tx: JobSender,
+    /// Present for `/parse/sse` jobs only, so [`JobManager::cancel_job`] can stamp the
+    /// cancellation event with the next sequence number.
+    event_log: Option<Arc<Mutex<JobEventLog>>>,
 }
 
 #[derive(Debug, Clone)]
@@ -188,6 +573,26 @@ struct JobManager {
 struct AppState {
     parser: FerrulesParser,
     job_manager: JobManager,
+    job_store: JobStore,
+    parse_cache: ParseCache,
+    event_logs: EventLogRegistry,
+    retry_count: usize,
+    retry_backoff_base_ms: u64,
+    job_inputs_dir: std::path::PathBuf,
+    job_retention: std::time::Duration,
+    readiness: ReadinessState,
+}
+
+/// Whether the things a readiness probe cares about came up successfully at startup. The
+/// layout model is always `true` here -- [`FerrulesParser::new`] panics before `main` gets this
+/// far if it fails to load -- but the OTLP exporters are recorded so a probe (and an operator
+/// reading `/readyz`) can tell whether traces/metrics are actually flowing without digging
+/// through logs.
+#[derive(Debug, Clone, Copy, Serialize)]
+struct ReadinessState {
+    model_backend: bool,
+    otlp_tracing: bool,
+    otlp_metrics: bool,
 }
 
 impl JobManager {
@@ -197,11 +602,17 @@ impl JobManager {
         }
     }
 
-    async fn start_job(&self, job_id: Uuid, tx: mpsc::Sender<ParseEvent>) -> CancellationToken {
+    async fn start_job(
+        &self,
+        job_id: Uuid,
+        tx: impl Into<JobSender>,
+        event_log: Option<Arc<Mutex<JobEventLog>>>,
+    ) -> CancellationToken {
         let cancellation_token = CancellationToken::new();
         let job_handle = JobHandle {
             cancellation_token: cancellation_token.clone(),
-            tx,
+            tx: tx.into(),
+            event_log,
         };
 
         let mut jobs = self.active_jobs.lock().await;
@@ -217,13 +628,20 @@ impl JobManager {
         if let Some(job_handle) = jobs.get(&job_id) {
             job_handle.cancellation_token.cancel();
 
-            // Send cancellation event
-            let _ = job_handle
-                .tx
-                .send(ParseEvent::Cancelled {
-                    message: "Job was cancelled by user request".to_string(),
-                })
-                .await;
+            let message = "Job was cancelled by user request".to_string();
+            let seq = match &job_handle.event_log {
+                Some(event_log) => {
+                    event_log
+                        .lock()
+                        .await
+                        .record(ParseEvent::Cancelled {
+                            message: message.clone(),
+                        })
+                        .0
+                }
+                None => 0,
+            };
+            job_handle.tx.send_cancelled(seq, message).await;
 
             tracing::info!("Cancelled job {}", job_id);
             Ok(())
@@ -238,6 +656,12 @@ impl JobManager {
             tracing::info!("Completed job {}", job_id);
         }
     }
+
+    /// Whether `job_id` still has a live entry -- i.e. still has an SSE receiver attached. The
+    /// sweeper consults this so it never deletes a job out from under an open connection.
+    async fn is_active(&self, job_id: Uuid) -> bool {
+        self.active_jobs.lock().await.contains_key(&job_id)
+    }
 }
 
 #[tokio::main]
@@ -246,7 +670,6 @@ async fn main() {
     // Check providers
     let providers = parse_ep_args(&args);
     // Initialize Sentry if DSN is provided
-    let use_sentry = args.sentry_dsn.is_some();
     let _guard = if let Some(dsn) = args.sentry_dsn {
         Some(sentry::init((
             dsn,
@@ -262,37 +685,121 @@ async fn main() {
         None
     };
 
-    init_tracing(
-        Some(&args.otlp_endpoint),
+    let tracer_provider = init_tracing(
+        args.otlp_endpoint.as_deref(),
         "ferrules-api".into(),
-        false,
-        use_sentry,
+        args.json_output,
     )
     .expect("can't setup tracing for API");
 
+    let meter_provider =
+        ferrules_api::metrics::init_metrics(args.otlp_endpoint.as_deref(), "ferrules-api".into())
+            .expect("can't setup metrics for API");
+
+    let readiness = ReadinessState {
+        model_backend: true,
+        otlp_tracing: tracer_provider.is_some(),
+        otlp_metrics: meter_provider.is_some(),
+    };
+
     let ort_config = ORTConfig {
         execution_providers: providers,
         intra_threads: args.intra_threads,
         inter_threads: args.inter_threads,
         opt_level: args.graph_opt_level.map(|v| v.try_into().unwrap()),
+        nms_top_k: ORTLayoutParser::DEFAULT_NMS_TOP_K,
+        keep_top_k: None,
+        pixel_offset: 0.0,
+        precision: Default::default(),
+        conf_threshold: None,
+        nms_mode: Default::default(),
     };
     // Initialize the layout model and queues
     let parser = FerrulesParser::new(ort_config);
+
+    if args.stdio {
+        return ferrules_api::transport::run_stdio_server(parser)
+            .await
+            .expect("stdio transport failed");
+    }
+
     let job_manager = JobManager::new();
+    let job_store = JobStore::open(&args.job_store_path).expect("can't open job store database");
+    match job_store.reap_orphaned_jobs().await {
+        Ok(0) => {}
+        Ok(n) => tracing::warn!("Marked {n} job(s) left running by a previous instance as failed"),
+        Err(e) => tracing::error!("Failed to reap orphaned jobs from the job store: {e:?}"),
+    }
+
+    std::fs::create_dir_all(&args.job_inputs_dir).expect("can't create job inputs directory");
+
+    let job_heartbeat_ttl = std::time::Duration::from_secs(args.job_heartbeat_ttl_secs);
+    match job_store
+        .requeue_stale(job_heartbeat_ttl, args.job_max_retries)
+        .await
+    {
+        Ok(0) => {}
+        Ok(n) => tracing::warn!(
+            "Recovered {n} job queue entry(ies) left in-progress by a previous instance"
+        ),
+        Err(e) => tracing::error!("Failed to sweep the durable job queue at startup: {e:?}"),
+    }
+
+    for i in 0..args.job_worker_count {
+        tokio::spawn(worker::run_worker(
+            format!("worker-{i}"),
+            parser.clone(),
+            job_store.clone(),
+            job_heartbeat_ttl,
+            args.job_max_retries,
+        ));
+    }
+
+    let parse_cache = ParseCache::new(&ParseCacheConfig {
+        max_entries: args.parse_cache_max_entries,
+        ttl: std::time::Duration::from_secs(args.parse_cache_ttl_secs),
+        ..Default::default()
+    });
+
+    let event_logs = EventLogRegistry::new(std::time::Duration::from_secs(
+        args.sse_event_retention_secs,
+    ));
 
     let app_state = AppState {
         parser,
         job_manager,
+        job_store,
+        parse_cache,
+        event_logs,
+        retry_count: args.retry_count,
+        retry_backoff_base_ms: args.retry_backoff_base_ms,
+        job_inputs_dir: args.job_inputs_dir.clone(),
+        job_retention: std::time::Duration::from_secs(args.job_retention_secs),
+        readiness,
     };
 
+    let sweeper_shutdown = CancellationToken::new();
+    tokio::spawn(run_sweeper(
+        app_state.clone(),
+        std::time::Duration::from_secs(args.job_sweep_interval_secs),
+        sweeper_shutdown.clone(),
+    ));
+
     // Build our application with a route
     let app = Router::new()
         .route("/health", get(health_check))
+        .route("/readyz", get(readiness_check))
         .route("/parse", post(parse_document_handler))
         .route("/parse/sse", post(parse_document_sse_handler))
+        .route("/parse/:job_id/stream", get(job_stream_handler))
+        .route("/parse/batch", post(parse_batch_handler))
+        .route("/parse/:job_id", get(get_job_handler))
         .route("/parse/:job_id/cancel", post(cancel_job_handler))
+        .route("/jobs/:job_id", delete(delete_job_handler))
+        .route("/jobs/sweep", post(sweep_jobs_handler))
         .with_state(app_state)
         .layer(OtelAxumLayer::default())
+        .layer(axum::middleware::from_fn(request_id_middleware))
         .layer(DefaultBodyLimit::max(MAX_SIZE_LIMIT));
 
     // Run it
@@ -301,208 +808,202 @@ async fn main() {
         "Starting ferrules service listening on {}",
         listener.local_addr().unwrap()
     );
-    axum::serve(listener, app).await.unwrap();
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown_signal(sweeper_shutdown))
+        .await
+        .unwrap();
+
+    if tracer_provider.is_some() {
+        opentelemetry::global::shutdown_tracer_provider();
+    }
+    if let Some(meter_provider) = meter_provider {
+        if let Err(e) = meter_provider.shutdown() {
+            tracing::warn!("Failed to flush OTLP metrics on shutdown: {e:?}");
+        }
+    }
+}
+
+/// Waits for Ctrl+C, then cancels `sweeper_shutdown` so [`run_sweeper`] stops cleanly instead of
+/// being dropped mid-sweep.
+async fn shutdown_signal(sweeper_shutdown: CancellationToken) {
+    if let Err(e) = tokio::signal::ctrl_c().await {
+        tracing::error!("Failed to install Ctrl+C handler: {e:?}");
+        return;
+    }
+    tracing::info!("Shutdown signal received, stopping background sweeper");
+    sweeper_shutdown.cancel();
 }
 
 #[tracing::instrument(skip_all)]
 async fn health_check() -> impl IntoResponse {
-    Json(ApiResponse {
-        success: true,
-        data: Some("Service is healthy"),
-        error: None,
-    })
+    Json(ApiResponse::ok("Service is healthy"))
 }
 
-#[tracing::instrument(skip_all)]
-async fn parse_document_handler(
-    headers: HeaderMap,
-    state: State<AppState>,
-    mut multipart: Multipart,
-) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<()>>)> {
-    // Extract the file from multipart form
-
-    let mut temp_file = NamedTempFile::new().map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ApiResponse {
-                success: false,
-                data: None,
-                error: Some(format!("Failed to create temp file: {}", e)),
-            }),
-        )
-    })?;
+/// Readiness probe: reports whether the layout model and the configured OTLP exporters came up
+/// successfully at startup, for a container orchestrator to gate traffic on.
+async fn readiness_check(State(state): State<AppState>) -> impl IntoResponse {
+    Json(ApiResponse::ok(state.readiness))
+}
 
+/// Reads the `file` and `options` parts of a parse request's multipart body, streaming `file`
+/// into a fresh temp file and parsing `options` as [`ParseOptions`]. Shared by the regular and
+/// SSE parse handlers.
+async fn read_multipart_request(
+    multipart: &mut Multipart,
+) -> Result<(NamedTempFile, Option<ParseOptions>), ApiError> {
+    let mut temp_file = NamedTempFile::new().map_err(ApiError::TempFileCreate)?;
     let mut options = None;
 
-    while let Some(field) = multipart.next_field().await.map_err(|e| {
-        (
-            StatusCode::BAD_REQUEST,
-            Json(ApiResponse {
-                success: false,
-                data: None,
-                error: Some(format!("Failed to get next field: {}", e)),
-            }),
-        )
-    })? {
+    while let Some(field) = multipart.next_field().await? {
         let name = field.name().unwrap_or("").to_string();
 
         match name.as_str() {
             "file" => {
-                // Stream the field data to the temp file
                 let mut field_stream = field;
-                while let Some(chunk) = field_stream.chunk().await.map_err(|e| {
-                    (
-                        StatusCode::BAD_REQUEST,
-                        Json(ApiResponse {
-                            success: false,
-                            data: None,
-                            error: Some(format!("Failed to read chunk: {}", e)),
-                        }),
-                    )
-                })? {
-                    temp_file.write_all(&chunk).map_err(|e| {
-                        (
-                            StatusCode::INTERNAL_SERVER_ERROR,
-                            Json(ApiResponse {
-                                success: false,
-                                data: None,
-                                error: Some(format!("Failed to write to temp file: {}", e)),
-                            }),
-                        )
-                    })?;
+                while let Some(chunk) = field_stream.chunk().await? {
+                    temp_file
+                        .write_all(&chunk)
+                        .map_err(ApiError::TempFileWrite)?;
                 }
-                temp_file.flush().map_err(|e| {
-                    (
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        Json(ApiResponse {
-                            success: false,
-                            data: None,
-                            error: Some(format!("Failed to flush temp file: {}", e)),
-                        }),
-                    )
-                })?;
-                temp_file.seek(std::io::SeekFrom::Start(0)).map_err(|e| {
-                    (
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        Json(ApiResponse {
-                            success: false,
-                            data: None,
-                            error: Some(format!("Failed to seek temp file: {}", e)),
-                        }),
-                    )
-                })?;
+                temp_file.flush().map_err(ApiError::TempFileWrite)?;
+                temp_file
+                    .seek(std::io::SeekFrom::Start(0))
+                    .map_err(ApiError::TempFileWrite)?;
             }
             "options" => {
-                let options_str = field.text().await.map_err(|e| {
-                    (
-                        StatusCode::BAD_REQUEST,
-                        Json(ApiResponse {
-                            success: false,
-                            data: None,
-                            error: Some(format!("Failed to read options: {}", e)),
-                        }),
-                    )
-                })?;
-                options = Some(serde_json::from_str::<ParseOptions>(&options_str).map_err(
-                    |e| {
-                        (
-                            StatusCode::BAD_REQUEST,
-                            Json(ApiResponse {
-                                success: false,
-                                data: None,
-                                error: Some(format!("Failed to parse options: {}", e)),
-                            }),
-                        )
-                    },
-                )?);
+                let options_str = field.text().await?;
+                options = Some(
+                    serde_json::from_str::<ParseOptions>(&options_str)
+                        .map_err(ApiError::InvalidOptions)?,
+                );
             }
             _ => continue,
         }
     }
 
-    let file = File::open(temp_file.path()).await.map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ApiResponse {
-                success: false,
-                data: None,
-                error: Some(format!("Failed to open temp file: {}", e)),
-            }),
-        )
-    })?;
+    Ok((temp_file, options))
+}
 
-    let mmap = unsafe {
-        Mmap::map(&file).map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse {
-                    success: false,
-                    data: None,
-                    error: Some(format!("Failed to memory map file: {}", e)),
-                }),
-            )
-        })?
-    };
-    let page_range = if let Some(options) = options {
-        if let Some(range_str) = options.page_range {
-            Some(parse_page_range(&range_str).map_err(|e| {
-                (
-                    StatusCode::BAD_REQUEST,
-                    Json(ApiResponse {
-                        success: false,
-                        data: None,
-                        error: Some(e.to_string()),
-                    }),
-                )
-            })?)
-        } else {
-            None
+/// Reads a `POST /parse/batch` request's multipart body: every `file` part becomes its own
+/// temp file (named after the part's original filename, for tagging events), and a single
+/// shared `options` part applies to all of them.
+async fn read_batch_multipart_request(
+    multipart: &mut Multipart,
+) -> Result<(Vec<(String, NamedTempFile)>, Option<ParseOptions>), ApiError> {
+    let mut files = Vec::new();
+    let mut options = None;
+
+    while let Some(field) = multipart.next_field().await? {
+        let name = field.name().unwrap_or("").to_string();
+
+        match name.as_str() {
+            "file" => {
+                let filename = field
+                    .file_name()
+                    .map(ToString::to_string)
+                    .unwrap_or_else(|| format!("file-{}", files.len()));
+                let mut temp_file = NamedTempFile::new().map_err(ApiError::TempFileCreate)?;
+                let mut field_stream = field;
+                while let Some(chunk) = field_stream.chunk().await? {
+                    temp_file
+                        .write_all(&chunk)
+                        .map_err(ApiError::TempFileWrite)?;
+                }
+                temp_file.flush().map_err(ApiError::TempFileWrite)?;
+                temp_file
+                    .seek(std::io::SeekFrom::Start(0))
+                    .map_err(ApiError::TempFileWrite)?;
+                files.push((filename, temp_file));
+            }
+            "options" => {
+                let options_str = field.text().await?;
+                options = Some(
+                    serde_json::from_str::<ParseOptions>(&options_str)
+                        .map_err(ApiError::InvalidOptions)?,
+                );
+            }
+            _ => continue,
         }
-    } else {
-        None
-    };
+    }
+
+    Ok((files, options))
+}
 
-    let config = FerrulesParseConfig {
-        password: None,
-        flatten_pdf: true,
-        page_range,
-        debug_dir: None,
+fn page_range_from_options(
+    options: Option<ParseOptions>,
+) -> Result<Option<std::ops::Range<usize>>, ApiError> {
+    let Some(range_str) = options.and_then(|o| o.page_range) else {
+        return Ok(None);
     };
-    let doc = state
-        .parser
-        .parse_document(
-            &mmap,
-            Uuid::new_v4().to_string(),
-            config,
-            Some(|_| {}),
-            None::<fn() -> bool>,
-        )
+    Ok(Some(
+        parse_page_range(&range_str).map_err(ApiError::InvalidPageRange)?,
+    ))
+}
+
+#[tracing::instrument(skip_all)]
+async fn parse_document_handler(
+    headers: HeaderMap,
+    state: State<AppState>,
+    mut multipart: Multipart,
+) -> Result<impl IntoResponse, ApiError> {
+    let (temp_file, options) = read_multipart_request(&mut multipart).await?;
+    let page_range = page_range_from_options(options)?;
+
+    let file = File::open(temp_file.path())
         .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse {
-                    success: false,
-                    data: None,
-                    error: Some(e.to_string()),
-                }),
-            )
-        })?;
+        .map_err(ApiError::TempFileCreate)?;
+    let mmap = unsafe { Mmap::map(&file).map_err(ApiError::MemoryMap)? };
+
+    const FLATTEN_PDF: bool = true;
+    let cache_key = ferrules_api::parse_cache::digest(&mmap, page_range.as_ref(), FLATTEN_PDF);
+    // A cached entry that fails to deserialize is treated the same as a miss rather than as a
+    // request error -- the cache is an optimization, not a source of truth.
+    let cached = state
+        .parse_cache
+        .get(&cache_key)
+        .and_then(|document| serde_json::from_value(document).ok());
+
+    let doc = match cached {
+        Some(doc) => doc,
+        None => {
+            let config = FerrulesParseConfig {
+                password: None,
+                flatten_pdf: FLATTEN_PDF,
+                page_range,
+                debug_dir: None,
+                ocr: OcrConfig::default(),
+                max_image_pixels: ferrules_core::DEFAULT_MAX_IMAGE_PIXELS,
+                cache: Default::default(),
+                merge: Default::default(),
+                reading_order: Default::default(),
+                ligatures: Default::default(),
+                resume_from: None,
+            };
+            let doc = state
+                .parser
+                .parse_document(
+                    &mmap,
+                    Uuid::new_v4().to_string(),
+                    config,
+                    Some(|_| {}),
+                    None::<fn() -> bool>,
+                )
+                .await
+                .map_err(ApiError::ParseFailed)?;
+
+            if let Ok(document) = serde_json::to_value(&doc) {
+                state.parse_cache.insert(cache_key, document);
+            }
+            doc
+        }
+    };
 
     let accept_header = headers.get(ACCEPT).and_then(|h| h.to_str().ok());
 
     match accept_header {
         Some("text/markdown") => {
-            let markdown = to_markdown(&doc, &doc.doc_name, None).map_err(|e| {
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ApiResponse {
-                        success: false,
-                        data: None,
-                        error: Some(format!("Failed to convert to markdown: {}", e)),
-                    }),
-                )
-            })?;
+            let markdown = to_markdown(&doc, &doc.doc_name, None, MarkdownFlavor::ViaHtml)
+                .map_err(ApiError::MarkdownRender)?;
 
             Ok(Response::builder()
                 .status(StatusCode::OK)
@@ -517,21 +1018,63 @@ async fn parse_document_handler(
                 .header(CONTENT_TYPE, "application/json")
                 .body(
                     // Use to_vec + from_utf8 to preserve Unicode characters instead of escaping them
-                    String::from_utf8(
-                        serde_json::to_vec(&ApiResponse {
-                            success: true,
-                            data: Some(doc),
-                            error: None,
-                        })
-                        .unwrap(),
-                    )
-                    .unwrap(),
+                    String::from_utf8(serde_json::to_vec(&ApiResponse::ok(doc)).unwrap()).unwrap(),
                 )
                 .unwrap())
         }
     }
 }
 
+/// Wraps a future and logs a warning whenever more than `threshold` elapses between two polls
+/// of it, which for a tokio task means the executor went that long without scheduling it --
+/// useful for spotting a page-inference future that's silently stuck instead of just slow.
+struct PollTimer<'a, T> {
+    inner: std::pin::Pin<Box<dyn std::future::Future<Output = T> + Send + 'a>>,
+    label: String,
+    threshold: std::time::Duration,
+    last_poll: std::time::Instant,
+}
+
+impl<T> std::future::Future for PollTimer<'_, T> {
+    type Output = T;
+
+    fn poll(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<T> {
+        let elapsed = self.last_poll.elapsed();
+        if elapsed > self.threshold {
+            tracing::warn!(
+                label = %self.label,
+                elapsed_secs = elapsed.as_secs_f64(),
+                "task went longer than expected between polls"
+            );
+        }
+        let poll = self.inner.as_mut().poll(cx);
+        self.last_poll = std::time::Instant::now();
+        poll
+    }
+}
+
+fn with_poll_timer<'a, T>(
+    label: impl Into<String>,
+    threshold: std::time::Duration,
+    fut: impl std::future::Future<Output = T> + Send + 'a,
+) -> PollTimer<'a, T> {
+    PollTimer {
+        inner: Box::pin(fut),
+        label: label.into(),
+        threshold,
+        last_poll: std::time::Instant::now(),
+    }
+}
+
+/// Whether a parse failure is worth retrying. Cancellation is a deliberate, user-initiated
+/// outcome rather than a transient failure, so it's the one error we never retry.
+fn is_retryable(err: &anyhow::Error) -> bool {
+    !err.to_string().contains("cancelled")
+}
+
 fn parse_page_range(range_str: &str) -> anyhow::Result<std::ops::Range<usize>> {
     if let Some((start, end)) = range_str.split_once('-') {
         let start: usize = start.trim().parse()?;
@@ -558,185 +1101,204 @@ fn parse_page_range(range_str: &str) -> anyhow::Result<std::ops::Range<usize>> {
     }
 }
 
+/// Converts a stamped `(seq, event)` pair into the SSE wire event, tagging it with its `type` so
+/// clients can dispatch on `event.event` without peeking into the JSON payload, and with its
+/// [`JobEventLog`] sequence number as the standard `id` field so a dropped connection can resume
+/// via `Last-Event-ID` against `GET /parse/:job_id/stream`. Shared by the regular parse flow and
+/// the content-hash-cache shortcut.
+fn sse_event(
+    (seq, event): (u64, ParseEvent),
+) -> Result<axum::response::sse::Event, std::convert::Infallible> {
+    // Use to_vec + from_utf8 to preserve Unicode characters instead of escaping them
+    let data =
+        String::from_utf8(serde_json::to_vec(&event).unwrap_or_default()).unwrap_or_default();
+    Ok(axum::response::sse::Event::default()
+        .id(seq.to_string())
+        .event(match &event {
+            ParseEvent::JobStarted { .. } => "job_started",
+            ParseEvent::Progress { .. } => "progress",
+            ParseEvent::Complete { .. } => "complete",
+            ParseEvent::Cancelled { .. } => "cancelled",
+            ParseEvent::Error { .. } => "error",
+            ParseEvent::Retry { .. } => "retry",
+        })
+        .data(data))
+}
+
+/// The [`sse_event`] equivalent for [`BatchParseEvent`].
+fn batch_sse_event(
+    event: BatchParseEvent,
+) -> Result<axum::response::sse::Event, std::convert::Infallible> {
+    // Use to_vec + from_utf8 to preserve Unicode characters instead of escaping them
+    let data =
+        String::from_utf8(serde_json::to_vec(&event).unwrap_or_default()).unwrap_or_default();
+    Ok(axum::response::sse::Event::default()
+        .event(match &event {
+            BatchParseEvent::BatchStarted { .. } => "batch_started",
+            BatchParseEvent::FileStarted { .. } => "file_started",
+            BatchParseEvent::FileProgress { .. } => "file_progress",
+            BatchParseEvent::BatchComplete { .. } => "batch_complete",
+            BatchParseEvent::Cancelled { .. } => "cancelled",
+        })
+        .data(data))
+}
+
+/// Records `event` into `event_log` to obtain its sequence number, then sends the stamped pair
+/// to the live SSE stream. Used by every non-`Progress` event in [`parse_document_sse_handler`];
+/// `Progress` is emitted from a synchronous callback and goes through [`JobEventLog::record`] via
+/// `try_lock` instead, since it can't `.await` the registry's async lock.
+async fn emit_event(
+    event_log: &Arc<Mutex<JobEventLog>>,
+    tx: &mpsc::Sender<(u64, ParseEvent)>,
+    event: ParseEvent,
+) {
+    let stamped = event_log.lock().await.record(event);
+    let _ = tx.send(stamped).await;
+}
+
 #[tracing::instrument(skip_all)]
 async fn parse_document_sse_handler(
     _headers: HeaderMap,
     state: State<AppState>,
     mut multipart: Multipart,
-) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<()>>)> {
+) -> Result<impl IntoResponse, ApiError> {
     // Create a channel for sending events
-    let (tx, rx) = mpsc::channel::<ParseEvent>(32);
+    let (tx, rx) = mpsc::channel::<(u64, ParseEvent)>(32);
 
     // Generate job ID and start job tracking
     let job_id = Uuid::new_v4();
-    let cancellation_token = state.job_manager.start_job(job_id, tx.clone()).await;
-
-    // Extract the file from multipart form (same as regular handler)
-    let mut temp_file = NamedTempFile::new().map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ApiResponse {
-                success: false,
-                data: None,
-                error: Some(format!("Failed to create temp file: {}", e)),
-            }),
-        )
-    })?;
-
-    let mut options = None;
+    let event_log = state.event_logs.create(job_id).await;
+    let cancellation_token = state
+        .job_manager
+        .start_job(job_id, tx.clone(), Some(event_log.clone()))
+        .await;
+
+    let (temp_file, options) = read_multipart_request(&mut multipart).await?;
+    let page_range = page_range_from_options(options)?;
+
+    // Persist the upload to a stable path outside of the request's own temp-file lifetime, so a
+    // crash or restart mid-parse leaves something a worker can still resume (see `worker`).
+    let input_path = state.job_inputs_dir.join(format!("{job_id}.bin"));
+    temp_file
+        .persist(&input_path)
+        .map_err(|e| ApiError::TempFileWrite(e.error))?;
+
+    let file = File::open(&input_path)
+        .await
+        .map_err(ApiError::TempFileCreate)?;
+    let mmap = unsafe { Mmap::map(&file).map_err(ApiError::MemoryMap)? };
 
-    while let Some(field) = multipart.next_field().await.map_err(|e| {
-        (
-            StatusCode::BAD_REQUEST,
-            Json(ApiResponse {
-                success: false,
-                data: None,
-                error: Some(format!("Failed to get next field: {}", e)),
-            }),
-        )
-    })? {
-        let name = field.name().unwrap_or("").to_string();
+    const FLATTEN_PDF: bool = true;
+    let cache_key = ferrules_api::parse_cache::digest(&mmap, page_range.as_ref(), FLATTEN_PDF);
+    let cached = state.parse_cache.get(&cache_key);
 
-        match name.as_str() {
-            "file" => {
-                let mut field_stream = field;
-                while let Some(chunk) = field_stream.chunk().await.map_err(|e| {
-                    (
-                        StatusCode::BAD_REQUEST,
-                        Json(ApiResponse {
-                            success: false,
-                            data: None,
-                            error: Some(format!("Failed to read chunk: {}", e)),
-                        }),
-                    )
-                })? {
-                    temp_file.write_all(&chunk).map_err(|e| {
-                        (
-                            StatusCode::INTERNAL_SERVER_ERROR,
-                            Json(ApiResponse {
-                                success: false,
-                                data: None,
-                                error: Some(format!("Failed to write to temp file: {}", e)),
-                            }),
-                        )
-                    })?;
-                }
-                temp_file.flush().map_err(|e| {
-                    (
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        Json(ApiResponse {
-                            success: false,
-                            data: None,
-                            error: Some(format!("Failed to flush temp file: {}", e)),
-                        }),
-                    )
-                })?;
-                temp_file.seek(std::io::SeekFrom::Start(0)).map_err(|e| {
-                    (
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        Json(ApiResponse {
-                            success: false,
-                            data: None,
-                            error: Some(format!("Failed to seek temp file: {}", e)),
-                        }),
-                    )
-                })?;
-            }
-            "options" => {
-                let options_str = field.text().await.map_err(|e| {
-                    (
-                        StatusCode::BAD_REQUEST,
-                        Json(ApiResponse {
-                            success: false,
-                            data: None,
-                            error: Some(format!("Failed to read options: {}", e)),
-                        }),
-                    )
-                })?;
-                options = Some(serde_json::from_str::<ParseOptions>(&options_str).map_err(
-                    |e| {
-                        (
-                            StatusCode::BAD_REQUEST,
-                            Json(ApiResponse {
-                                success: false,
-                                data: None,
-                                error: Some(format!("Failed to parse options: {}", e)),
-                            }),
-                        )
-                    },
-                )?);
-            }
-            _ => continue,
-        }
+    // Send job started event
+    emit_event(&event_log, &tx, ParseEvent::JobStarted { job_id }).await;
+
+    let submitted_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+        .to_string();
+    if let Err(e) = state.job_store.create(job_id, submitted_at).await {
+        tracing::error!("Failed to record job {job_id} in the job store: {e:?}");
     }
 
-    // Parse page range
-    let page_range = if let Some(options) = options {
-        if let Some(range_str) = options.page_range {
-            Some(parse_page_range(&range_str).map_err(|e| {
-                (
-                    StatusCode::BAD_REQUEST,
-                    Json(ApiResponse {
-                        success: false,
-                        data: None,
-                        error: Some(e.to_string()),
-                    }),
-                )
-            })?)
-        } else {
-            None
+    if let Some(document) = cached {
+        // Content-hash cache hit: skip the layout pipeline entirely and report completion
+        // immediately, same as a freshly-parsed job would once it finished. Nothing to resume,
+        // so the durable input file this job would otherwise occupy is dropped right away.
+        if let Err(e) = tokio::fs::remove_file(&input_path).await {
+            tracing::warn!("Failed to remove durable input file for job {job_id}: {e:?}");
         }
-    } else {
-        None
-    };
-
-    // Create memory map before spawning task
-    let file = File::open(temp_file.path()).await.map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ApiResponse {
-                success: false,
-                data: None,
-                error: Some(format!("Failed to open temp file: {}", e)),
-            }),
+        let total_pages = document
+            .get("pages")
+            .and_then(|pages| pages.as_array())
+            .map_or(0, |pages| pages.len());
+        if let Err(e) = state
+            .job_store
+            .set_completed(job_id, document.clone())
+            .await
+        {
+            tracing::error!("Failed to update job store for {job_id}: {e:?}");
+        }
+        emit_event(
+            &event_log,
+            &tx,
+            ParseEvent::Complete {
+                document,
+                total_pages,
+                cache_hit: true,
+            },
         )
-    })?;
+        .await;
+        state.job_manager.complete_job(job_id).await;
+
+        let stream = ReceiverStream::new(rx).map(sse_event);
+        return Ok(Sse::new(stream).keep_alive(
+            axum::response::sse::KeepAlive::new()
+                .interval(std::time::Duration::from_secs(30))
+                .text("keep-alive-text"),
+        ));
+    }
 
-    let mmap = unsafe {
-        Mmap::map(&file).map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse {
-                    success: false,
-                    data: None,
-                    error: Some(format!("Failed to memory map file: {}", e)),
-                }),
-            )
-        })?
+    // Push the job onto the durable queue, then immediately claim it for inline processing --
+    // the request handler parses it itself for low latency; the row only matters if this
+    // process dies before finishing, in which case a `worker` picks it back up.
+    let queued_job = QueuedJob {
+        job_id,
+        input_path: input_path.clone(),
+        page_range: page_range.clone(),
+        flatten_pdf: FLATTEN_PDF,
     };
-
-    // Send job started event
-    let _ = tx.send(ParseEvent::JobStarted { job_id }).await;
+    if let Err(e) = state.job_store.enqueue(queued_job).await {
+        tracing::error!("Failed to enqueue job {job_id} in the durable queue: {e:?}");
+    }
+    let inline_worker_id = format!("inline-{job_id}");
+    if let Err(e) = state.job_store.claim(job_id, &inline_worker_id).await {
+        tracing::error!("Failed to claim job {job_id} for inline processing: {e:?}");
+    }
 
     // Spawn parsing task
     let tx_clone = tx.clone();
+    let event_log_clone = event_log.clone();
     let parser = state.parser.clone();
     let job_manager = state.job_manager.clone();
+    let job_store = state.job_store.clone();
+    let parse_cache = state.parse_cache.clone();
+    let retry_count = state.retry_count;
+    let retry_backoff_base_ms = state.retry_backoff_base_ms;
     let cancellation_token_clone = cancellation_token.clone();
 
     tokio::spawn(async move {
-        // Keep the temp file alive by moving it into the task
-        let _temp_file = temp_file; // Keep alive until end of task
+        // Best-effort cleanup of this job's durable queue row and input file, run on every exit
+        // path below since the job has either finished or been fully handed off elsewhere.
+        async fn finish_durably(job_store: &JobStore, job_id: Uuid, input_path: &std::path::Path) {
+            if let Err(e) = job_store.finish_queue_entry(job_id).await {
+                tracing::error!("Failed to remove queue entry for job {job_id}: {e:?}");
+            }
+            if let Err(e) = tokio::fs::remove_file(input_path).await {
+                tracing::warn!("Failed to remove durable input file for job {job_id}: {e:?}");
+            }
+        }
 
         let config = FerrulesParseConfig {
             password: None,
-            flatten_pdf: true,
+            flatten_pdf: FLATTEN_PDF,
             page_range,
             debug_dir: None,
+            ocr: OcrConfig::default(),
+            max_image_pixels: ferrules_core::DEFAULT_MAX_IMAGE_PIXELS,
+            cache: Default::default(),
+            merge: Default::default(),
+            reading_order: Default::default(),
+            ligatures: Default::default(),
+            resume_from: None,
         };
 
         // Check for cancellation before starting
         if cancellation_token_clone.is_cancelled() {
+            finish_durably(&job_store, job_id, &input_path).await;
             job_manager.complete_job(job_id).await;
             return;
         }
@@ -745,16 +1307,27 @@ async fn parse_document_sse_handler(
         let total_pages = match parser.get_page_count(&mmap, config.password).await {
             Ok(count) => count,
             Err(e) => {
-                let _ = tx_clone
-                    .send(ParseEvent::Error {
+                emit_event(
+                    &event_log_clone,
+                    &tx_clone,
+                    ParseEvent::Error {
                         message: format!("Failed to get page count: {}", e),
-                    })
-                    .await;
+                    },
+                )
+                .await;
+                if let Err(e) = job_store.set_failed(job_id, e.to_string()).await {
+                    tracing::error!("Failed to update job store for {job_id}: {e:?}");
+                }
+                finish_durably(&job_store, job_id, &input_path).await;
                 job_manager.complete_job(job_id).await;
                 return;
             }
         };
 
+        if let Err(e) = job_store.set_running(job_id, total_pages).await {
+            tracing::error!("Failed to update job store for {job_id}: {e:?}");
+        }
+
         let pages_completed = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
         let tx_progress = tx_clone.clone();
         let pages_completed_clone = pages_completed.clone();
@@ -762,6 +1335,8 @@ async fn parse_document_sse_handler(
         // Create progress callback
         let progress_callback = {
             let tx_progress = tx_progress.clone();
+            let event_log_progress = event_log_clone.clone();
+            let job_store = job_store.clone();
             move |page_id| {
                 let completed =
                     pages_completed_clone.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
@@ -771,10 +1346,26 @@ async fn parse_document_sse_handler(
                     completed,
                     total_pages
                 );
-                let _ = tx_progress.try_send(ParseEvent::Progress {
+                let progress_event = ParseEvent::Progress {
                     pages_completed: completed,
                     total_pages,
                     page_id,
+                };
+                // This closure isn't async, so it can't `.await` the registry's lock like
+                // `emit_event` does; a `try_lock` miss just drops this progress tick; the client
+                // still catches up to the latest count on the next one (or via reconnect replay).
+                if let Ok(mut log) = event_log_progress.try_lock() {
+                    let stamped = log.record(progress_event);
+                    let _ = tx_progress.try_send(stamped);
+                }
+                let job_store = job_store.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = job_store.set_progress(job_id, completed).await {
+                        tracing::error!("Failed to update job store progress for {job_id}: {e:?}");
+                    }
+                    if let Err(e) = job_store.heartbeat(job_id).await {
+                        tracing::error!("Failed to heartbeat job {job_id}: {e:?}");
+                    }
                 });
             }
         };
@@ -791,69 +1382,209 @@ async fn parse_document_sse_handler(
             }
         };
 
-        // Parse document with cancellation callback - much simpler!
-        let result = parser
-            .parse_document(
+        // Parse document with cancellation callback, retrying transient failures with
+        // exponential backoff before giving up.
+        let mut attempt = 0usize;
+        let result = loop {
+            let parse_fut = parser.parse_document(
                 &mmap,
                 job_id.to_string(),
-                config,
-                Some(progress_callback),
-                Some(cancellation_callback),
+                config.clone(),
+                Some(progress_callback.clone()),
+                Some(cancellation_callback.clone()),
+            );
+            let attempt_result = with_poll_timer(
+                format!("parse_document[{job_id}]"),
+                std::time::Duration::from_secs(10),
+                parse_fut,
             )
             .await;
 
+            match attempt_result {
+                Err(e)
+                    if is_retryable(&e)
+                        && attempt < retry_count
+                        && !cancellation_token_clone.is_cancelled() =>
+                {
+                    let delay_ms = retry_backoff_base_ms * (1u64 << attempt);
+                    attempt += 1;
+                    tracing::warn!(
+                        "Parse attempt {attempt} for job {job_id} failed, retrying in {delay_ms}ms: {e:?}"
+                    );
+                    emit_event(
+                        &event_log_clone,
+                        &tx_clone,
+                        ParseEvent::Retry { attempt, delay_ms },
+                    )
+                    .await;
+                    tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
+                }
+                other => break other,
+            }
+        };
+
         match result {
             Ok(doc) => {
                 if !cancellation_token_clone.is_cancelled() {
-                    let _ = tx_clone
-                        .send(ParseEvent::Complete {
-                            document: serde_json::to_value(&doc).unwrap_or_default(),
+                    let document = serde_json::to_value(&doc).unwrap_or_default();
+                    if let Err(e) = job_store.set_completed(job_id, document.clone()).await {
+                        tracing::error!("Failed to update job store for {job_id}: {e:?}");
+                    }
+                    parse_cache.insert(cache_key, document.clone());
+                    emit_event(
+                        &event_log_clone,
+                        &tx_clone,
+                        ParseEvent::Complete {
+                            document,
                             total_pages: doc.pages.len(),
-                        })
-                        .await;
+                            cache_hit: false,
+                        },
+                    )
+                    .await;
                 }
             }
             Err(e) => {
                 // Check if the error is due to cancellation
                 if e.to_string().contains("cancelled") {
                     tracing::info!("Document processing was cancelled: {}", e);
-                    let _ = tx_clone
-                        .send(ParseEvent::Cancelled {
+                    if let Err(e) = job_store.set_cancelled(job_id).await {
+                        tracing::error!("Failed to update job store for {job_id}: {e:?}");
+                    }
+                    emit_event(
+                        &event_log_clone,
+                        &tx_clone,
+                        ParseEvent::Cancelled {
                             message: "Processing was cancelled".to_string(),
-                        })
-                        .await;
+                        },
+                    )
+                    .await;
                 } else if !cancellation_token_clone.is_cancelled() {
-                    let _ = tx_clone
-                        .send(ParseEvent::Error {
+                    if let Err(e) = job_store.set_failed(job_id, e.to_string()).await {
+                        tracing::error!("Failed to update job store for {job_id}: {e:?}");
+                    }
+                    emit_event(
+                        &event_log_clone,
+                        &tx_clone,
+                        ParseEvent::Error {
                             message: e.to_string(),
-                        })
-                        .await;
+                        },
+                    )
+                    .await;
                 }
             }
         }
 
         // Clean up job when done
+        finish_durably(&job_store, job_id, &input_path).await;
         job_manager.complete_job(job_id).await;
     });
 
     // Create SSE stream
-    let stream = ReceiverStream::new(rx).map(|event| {
-        // Use to_vec + from_utf8 to preserve Unicode characters instead of escaping them
-        let data =
-            String::from_utf8(serde_json::to_vec(&event).unwrap_or_default()).unwrap_or_default();
-        Ok::<_, std::convert::Infallible>(
-            axum::response::sse::Event::default()
-                .event(match &event {
-                    ParseEvent::JobStarted { .. } => "job_started",
-                    ParseEvent::Progress { .. } => "progress",
-                    ParseEvent::Complete { .. } => "complete",
-                    ParseEvent::Cancelled { .. } => "cancelled",
-                    ParseEvent::Error { .. } => "error",
-                })
-                .data(data),
-        )
+    let stream = ReceiverStream::new(rx).map(sse_event);
+
+    Ok(Sse::new(stream).keep_alive(
+        axum::response::sse::KeepAlive::new()
+            .interval(std::time::Duration::from_secs(30))
+            .text("keep-alive-text"),
+    ))
+}
+
+/// Parses every `file` part of a multipart request under one job, each against the shared
+/// `options`. Child files run concurrently; one failing doesn't stop the others from being
+/// reported in the final [`BatchParseEvent::BatchComplete`].
+#[tracing::instrument(skip_all)]
+async fn parse_batch_handler(
+    _headers: HeaderMap,
+    state: State<AppState>,
+    mut multipart: Multipart,
+) -> Result<impl IntoResponse, ApiError> {
+    let (tx, rx) = mpsc::channel::<BatchParseEvent>(32);
+
+    let job_id = Uuid::new_v4();
+    let cancellation_token = state.job_manager.start_job(job_id, tx.clone(), None).await;
+
+    let (files, options) = read_batch_multipart_request(&mut multipart).await?;
+    let page_range = page_range_from_options(options)?;
+
+    let _ = tx
+        .send(BatchParseEvent::BatchStarted {
+            job_id,
+            total_documents: files.len(),
+        })
+        .await;
+
+    let submitted_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+        .to_string();
+    if let Err(e) = state.job_store.create(job_id, submitted_at).await {
+        tracing::error!("Failed to record job {job_id} in the job store: {e:?}");
+    }
+
+    let parser = state.parser.clone();
+    let job_manager = state.job_manager.clone();
+    let job_store = state.job_store.clone();
+    let parse_cache = state.parse_cache.clone();
+    let cancellation_token_clone = cancellation_token.clone();
+
+    tokio::spawn(async move {
+        let child_tasks: Vec<_> = files
+            .into_iter()
+            .enumerate()
+            .map(|(file_index, (filename, temp_file))| {
+                let parser = parser.clone();
+                let parse_cache = parse_cache.clone();
+                let tx = tx.clone();
+                // A genuine child of the batch's token, not a clone of it: cancelling the batch
+                // still cascades to every in-flight document (tokio_util propagates parent ->
+                // child), but this leaves room for a document to be cancelled on its own later
+                // without taking down its siblings.
+                let cancellation_token = cancellation_token_clone.child_token();
+                let page_range = page_range.clone();
+
+                tokio::spawn(parse_batch_file(
+                    file_index,
+                    filename,
+                    temp_file,
+                    parser,
+                    parse_cache,
+                    page_range,
+                    cancellation_token,
+                    tx,
+                ))
+            })
+            .collect();
+
+        let mut results = Vec::new();
+        let mut failed = Vec::new();
+        for task in child_tasks {
+            match task.await {
+                Ok(Ok(result)) => results.push(result),
+                Ok(Err(failure)) => failed.push(failure),
+                Err(join_err) => {
+                    tracing::error!("Batch child task for job {job_id} panicked: {join_err:?}");
+                }
+            }
+        }
+        results.sort_by_key(|r: &BatchFileResult| r.file_index);
+        failed.sort_by_key(|f: &BatchFileFailure| f.file_index);
+
+        if !cancellation_token_clone.is_cancelled() {
+            let batch_document = serde_json::to_value(&results).unwrap_or_default();
+            if let Err(e) = job_store.set_completed(job_id, batch_document).await {
+                tracing::error!("Failed to update job store for {job_id}: {e:?}");
+            }
+            let _ = tx
+                .send(BatchParseEvent::BatchComplete { results, failed })
+                .await;
+        }
+
+        job_manager.complete_job(job_id).await;
     });
 
+    let stream = ReceiverStream::new(rx).map(batch_sse_event);
+
     Ok(Sse::new(stream).keep_alive(
         axum::response::sse::KeepAlive::new()
             .interval(std::time::Duration::from_secs(30))
@@ -861,24 +1592,308 @@ async fn parse_document_sse_handler(
     ))
 }
 
+/// Parses a single file within a `/parse/batch` job: consults the content-hash cache, then
+/// falls back to a fresh parse, emitting `file_started`/`file_progress` events tagged with
+/// `file_index`/`filename` along the way.
+#[allow(clippy::too_many_arguments)]
+async fn parse_batch_file(
+    file_index: usize,
+    filename: String,
+    temp_file: NamedTempFile,
+    parser: FerrulesParser,
+    parse_cache: ParseCache,
+    page_range: Option<std::ops::Range<usize>>,
+    cancellation_token: CancellationToken,
+    tx: mpsc::Sender<BatchParseEvent>,
+) -> Result<BatchFileResult, BatchFileFailure> {
+    let fail = |error: String| BatchFileFailure {
+        file_index,
+        filename: filename.clone(),
+        error,
+    };
+
+    let _ = tx
+        .send(BatchParseEvent::FileStarted {
+            file_index,
+            filename: filename.clone(),
+        })
+        .await;
+
+    if cancellation_token.is_cancelled() {
+        return Err(fail("cancelled".to_string()));
+    }
+
+    let file = File::open(temp_file.path())
+        .await
+        .map_err(|e| fail(e.to_string()))?;
+    let mmap = unsafe { Mmap::map(&file).map_err(|e| fail(e.to_string()))? };
+
+    const FLATTEN_PDF: bool = true;
+    let cache_key = ferrules_api::parse_cache::digest(&mmap, page_range.as_ref(), FLATTEN_PDF);
+
+    let document = match parse_cache.get(&cache_key) {
+        Some(document) => document,
+        None => {
+            let config = FerrulesParseConfig {
+                password: None,
+                flatten_pdf: FLATTEN_PDF,
+                page_range,
+                debug_dir: None,
+                ocr: OcrConfig::default(),
+                max_image_pixels: ferrules_core::DEFAULT_MAX_IMAGE_PIXELS,
+                cache: Default::default(),
+                merge: Default::default(),
+                reading_order: Default::default(),
+                ligatures: Default::default(),
+                resume_from: None,
+            };
+
+            let total_pages = parser
+                .get_page_count(&mmap, config.password)
+                .await
+                .map_err(|e| fail(e.to_string()))?;
+            let pages_completed = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+            let progress_callback = {
+                let tx = tx.clone();
+                let filename = filename.clone();
+                let pages_completed = pages_completed.clone();
+                move |_page_id| {
+                    let completed =
+                        pages_completed.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+                    let _ = tx.try_send(BatchParseEvent::FileProgress {
+                        file_index,
+                        filename: filename.clone(),
+                        pages_completed: completed,
+                        total_pages,
+                    });
+                }
+            };
+            let cancellation_callback = {
+                let token = cancellation_token.clone();
+                move || token.is_cancelled()
+            };
+
+            let doc = parser
+                .parse_document(
+                    &mmap,
+                    Uuid::new_v4().to_string(),
+                    config,
+                    Some(progress_callback),
+                    Some(cancellation_callback),
+                )
+                .await
+                .map_err(|e| fail(e.to_string()))?;
+
+            let document = serde_json::to_value(&doc).unwrap_or_default();
+            parse_cache.insert(cache_key, document.clone());
+            document
+        }
+    };
+
+    Ok(BatchFileResult {
+        file_index,
+        filename,
+        document,
+    })
+}
+
+#[tracing::instrument(skip_all)]
+async fn get_job_handler(
+    Path(job_id): Path<Uuid>,
+    State(app_state): State<AppState>,
+) -> Result<impl IntoResponse, ApiError> {
+    let record = app_state
+        .job_store
+        .get(job_id)
+        .await
+        .map_err(ApiError::JobStore)?;
+
+    match record {
+        Some(record) => Ok(Json(ApiResponse::ok(record))),
+        None => Err(ApiError::JobNotFound(job_id)),
+    }
+}
+
 #[tracing::instrument(skip_all)]
 async fn cancel_job_handler(
     Path(job_id): Path<Uuid>,
     State(app_state): State<AppState>,
-) -> Result<impl IntoResponse, (StatusCode, Json<ApiResponse<()>>)> {
-    match app_state.job_manager.cancel_job(job_id).await {
-        Ok(()) => Ok(Json(ApiResponse {
-            success: true,
-            data: Some("Job cancelled successfully"),
-            error: None,
-        })),
-        Err(error_msg) => Err((
-            StatusCode::NOT_FOUND,
-            Json(ApiResponse {
-                success: false,
-                data: None,
-                error: Some(error_msg),
-            }),
-        )),
+) -> Result<impl IntoResponse, ApiError> {
+    app_state
+        .job_manager
+        .cancel_job(job_id)
+        .await
+        .map_err(|_| ApiError::JobNotFound(job_id))?;
+    Ok(Json(ApiResponse::ok("Job cancelled successfully")))
+}
+
+/// How often [`replay_and_follow`] re-checks a job's [`JobEventLog`] for new events once it has
+/// caught a reconnecting client up to the live edge.
+const STREAM_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(250);
+
+/// `GET /parse/:job_id/stream` -- lets a client that lost its `/parse/sse` connection resume
+/// without re-submitting the document, by replaying everything recorded in the job's
+/// [`JobEventLog`] after the `Last-Event-ID` it last saw (or from the beginning, if absent or
+/// unparsable). Returns [`ApiError::JobNotFound`] once the job's log has expired, same as
+/// [`get_job_handler`] does for the underlying job record.
+#[tracing::instrument(skip_all)]
+async fn job_stream_handler(
+    Path(job_id): Path<Uuid>,
+    headers: HeaderMap,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, ApiError> {
+    let event_log = state
+        .event_logs
+        .get(job_id)
+        .await
+        .ok_or(ApiError::JobNotFound(job_id))?;
+
+    let last_seen = headers
+        .get("last-event-id")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok());
+
+    let (tx, rx) = mpsc::channel::<(u64, ParseEvent)>(32);
+    tokio::spawn(replay_and_follow(event_log, last_seen, tx));
+
+    let stream = ReceiverStream::new(rx).map(sse_event);
+    Ok(Sse::new(stream).keep_alive(
+        axum::response::sse::KeepAlive::new()
+            .interval(std::time::Duration::from_secs(30))
+            .text("keep-alive-text"),
+    ))
+}
+
+/// Feeds `tx` everything in `event_log` after `last_seen`, then keeps polling for newly recorded
+/// events until the job's terminal event has been delivered or `tx` is dropped (the reconnected
+/// client disconnected again). The original per-job channel is single-consumer, so a reconnect
+/// can't subscribe to it directly -- this polls the shared log instead, which is cheap at
+/// [`STREAM_POLL_INTERVAL`] and bounded in duration by the job actually finishing.
+async fn replay_and_follow(
+    event_log: Arc<Mutex<JobEventLog>>,
+    mut last_seen: Option<u64>,
+    tx: mpsc::Sender<(u64, ParseEvent)>,
+) {
+    loop {
+        let (batch, terminal_seq) = {
+            let log = event_log.lock().await;
+            (
+                log.replay_after(last_seen),
+                log.terminal.as_ref().map(|(seq, _)| *seq),
+            )
+        };
+
+        for stamped in batch {
+            last_seen = Some(stamped.0);
+            if tx.send(stamped).await.is_err() {
+                return;
+            }
+        }
+
+        if terminal_seq.is_some_and(|seq| last_seen.is_some_and(|seen| seen >= seq)) {
+            return;
+        }
+
+        tokio::time::sleep(STREAM_POLL_INTERVAL).await;
+    }
+}
+
+/// Periodically reclaims finished jobs older than `retention`: their `jobs` row, buffered SSE
+/// event log, and any leftover durable input file. Runs until `shutdown` is cancelled, so the
+/// server can stop it cleanly instead of leaving a sweep half-done across a restart.
+async fn run_sweeper(state: AppState, interval: std::time::Duration, shutdown: CancellationToken) {
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        tokio::select! {
+            _ = shutdown.cancelled() => {
+                tracing::info!("Job sweeper stopped");
+                return;
+            }
+            _ = ticker.tick() => {
+                let removed = sweep_expired_jobs(&state).await;
+                if removed > 0 {
+                    tracing::info!("Swept {removed} expired job(s)");
+                }
+            }
+        }
+    }
+}
+
+/// Deletes every job whose terminal state is older than `state.job_retention`, skipping any that
+/// [`JobManager::is_active`] still reports live -- e.g. a job whose `jobs` row was marked
+/// complete a moment before its SSE connection actually closed. Returns the number removed.
+async fn sweep_expired_jobs(state: &AppState) -> usize {
+    let expired = match state.job_store.expired_job_ids(state.job_retention).await {
+        Ok(ids) => ids,
+        Err(e) => {
+            tracing::error!("Failed to list expired jobs: {e:?}");
+            return 0;
+        }
+    };
+
+    let mut removed = 0;
+    for job_id in expired {
+        if state.job_manager.is_active(job_id).await {
+            continue;
+        }
+        if let Err(e) = state.job_store.delete(job_id).await {
+            tracing::error!("Failed to delete expired job {job_id}: {e:?}");
+            continue;
+        }
+        state.event_logs.remove(job_id).await;
+        remove_leftover_input(&state.job_inputs_dir, job_id).await;
+        removed += 1;
+    }
+    removed
+}
+
+/// Best-effort removal of a job's durable input file, in case it somehow outlived the job
+/// itself (the normal completion paths already clean this up as they finish).
+async fn remove_leftover_input(job_inputs_dir: &std::path::Path, job_id: Uuid) {
+    let input_path = job_inputs_dir.join(format!("{job_id}.bin"));
+    match tokio::fs::remove_file(&input_path).await {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => tracing::warn!("Failed to remove leftover input file for job {job_id}: {e:?}"),
+    }
+}
+
+/// `DELETE /jobs/{job_id}` -- manually reclaims a finished job ahead of the periodic sweep.
+/// Refuses to touch one that's still active (see [`JobManager::is_active`]).
+#[tracing::instrument(skip_all)]
+async fn delete_job_handler(
+    Path(job_id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, ApiError> {
+    if state.job_manager.is_active(job_id).await {
+        return Err(ApiError::JobStillActive(job_id));
+    }
+
+    let exists = state
+        .job_store
+        .get(job_id)
+        .await
+        .map_err(ApiError::JobStore)?
+        .is_some();
+    if !exists {
+        return Err(ApiError::JobNotFound(job_id));
     }
+
+    state
+        .job_store
+        .delete(job_id)
+        .await
+        .map_err(ApiError::JobStore)?;
+    state.event_logs.remove(job_id).await;
+    remove_leftover_input(&state.job_inputs_dir, job_id).await;
+
+    Ok(Json(ApiResponse::ok("Job deleted")))
+}
+
+/// `POST /jobs/sweep` -- admin call to run the sweep immediately instead of waiting for the next
+/// tick, returning how many jobs it removed.
+#[tracing::instrument(skip_all)]
+async fn sweep_jobs_handler(State(state): State<AppState>) -> impl IntoResponse {
+    let removed = sweep_expired_jobs(&state).await;
+    Json(ApiResponse::ok(removed))
 }
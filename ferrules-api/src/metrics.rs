@@ -0,0 +1,41 @@
+//! OpenTelemetry metrics pipeline for the API process, parallel to [`crate::init_tracing`]'s
+//! span pipeline. `ferrules_core` records pipeline metrics (pages processed, blocks rendered,
+//! per-stage latency, bytes in/out) through `opentelemetry::global::meter` regardless of
+//! whether this has run -- installing a real `MeterProvider` here is what turns those calls
+//! from no-ops into an actual OTLP export.
+
+use opentelemetry::global;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{
+    metrics::{PeriodicReader, SdkMeterProvider},
+    runtime::Tokio,
+    Resource,
+};
+use opentelemetry_semantic_conventions::resource::SERVICE_NAME;
+
+/// Installs an OTLP metrics pipeline (a periodic reader running on the Tokio runtime) and sets
+/// it as the global meter provider. Returns `None` when `otlp_endpoint` is `None`, leaving the
+/// no-op global meter provider in place.
+pub fn init_metrics(
+    otlp_endpoint: Option<&str>,
+    service_name: String,
+) -> anyhow::Result<Option<SdkMeterProvider>> {
+    let Some(otlp_endpoint) = otlp_endpoint else {
+        return Ok(None);
+    };
+
+    let exporter = opentelemetry_otlp::MetricExporter::builder()
+        .with_tonic()
+        .with_endpoint(otlp_endpoint)
+        .build()?;
+    let reader = PeriodicReader::builder(exporter, Tokio).build();
+    let provider = SdkMeterProvider::builder()
+        .with_reader(reader)
+        .with_resource(Resource::new(vec![opentelemetry::KeyValue::new(
+            SERVICE_NAME,
+            service_name,
+        )]))
+        .build();
+    global::set_meter_provider(provider.clone());
+    Ok(Some(provider))
+}
@@ -0,0 +1,110 @@
+//! In-memory cache of fully-parsed documents, keyed by a content digest of the uploaded bytes
+//! combined with the parts of [`FerrulesParseConfig`][ferrules_core::FerrulesParseConfig] that
+//! affect the output (page range, flatten flag).
+//!
+//! Re-uploading the same file with the same options -- a common pattern when a client retries
+//! or re-submits the same corpus -- hits this cache instead of re-running the layout pipeline.
+
+use std::{
+    num::NonZeroUsize,
+    ops::Range,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use lru::LruCache;
+
+/// Configuration for [`ParseCache`], threaded down from `Args`.
+#[derive(Debug, Clone)]
+pub struct ParseCacheConfig {
+    /// Whether the cache is consulted and populated at all.
+    pub enabled: bool,
+    /// Max number of parsed documents kept resident.
+    pub max_entries: usize,
+    /// How long an entry remains valid after being inserted. A stale entry is treated as a
+    /// miss and evicted on next lookup rather than returned.
+    pub ttl: Duration,
+}
+
+impl Default for ParseCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_entries: 64,
+            ttl: Duration::from_secs(3600),
+        }
+    }
+}
+
+/// Digests `bytes` together with the parse options that would change the resulting document,
+/// so two uploads only collide in the cache if they'd have produced the same output.
+pub fn digest(bytes: &[u8], page_range: Option<&Range<usize>>, flatten_pdf: bool) -> String {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(bytes);
+    match page_range {
+        Some(range) => {
+            hasher.update(b"range");
+            hasher.update(&range.start.to_le_bytes());
+            hasher.update(&range.end.to_le_bytes());
+        }
+        None => hasher.update(b"no-range"),
+    };
+    hasher.update(&[flatten_pdf as u8]);
+    hasher.finalize().to_hex().to_string()
+}
+
+struct Entry {
+    document: serde_json::Value,
+    inserted_at: Instant,
+}
+
+/// LRU-bounded, TTL-expiring cache of serialized [`ferrules_core::entities::ParsedDocument`]s.
+#[derive(Clone)]
+pub struct ParseCache {
+    entries: Arc<Mutex<LruCache<String, Entry>>>,
+    enabled: bool,
+    ttl: Duration,
+}
+
+impl ParseCache {
+    pub fn new(config: &ParseCacheConfig) -> Self {
+        let capacity = NonZeroUsize::new(config.max_entries.max(1)).unwrap();
+        Self {
+            entries: Arc::new(Mutex::new(LruCache::new(capacity))),
+            enabled: config.enabled,
+            ttl: config.ttl,
+        }
+    }
+
+    /// Looks up `key`, returning the cached document unless the cache is disabled, the key is
+    /// absent, or the entry has outlived its TTL (in which case it's evicted).
+    pub fn get(&self, key: &str) -> Option<serde_json::Value> {
+        if !self.enabled {
+            return None;
+        }
+
+        let mut entries = self.entries.lock().unwrap();
+        let expired = entries
+            .get(key)
+            .is_some_and(|entry| entry.inserted_at.elapsed() > self.ttl);
+        if expired {
+            entries.pop(key);
+            return None;
+        }
+
+        entries.get(key).map(|entry| entry.document.clone())
+    }
+
+    pub fn insert(&self, key: String, document: serde_json::Value) {
+        if !self.enabled {
+            return;
+        }
+        self.entries.lock().unwrap().put(
+            key,
+            Entry {
+                document,
+                inserted_at: Instant::now(),
+            },
+        );
+    }
+}
@@ -0,0 +1,454 @@
+//! Persistent job store backing the `/parse/sse` and `/parse/:job_id` endpoints.
+//!
+//! [`JobManager`] (in `main.rs`) only tracks jobs that are currently running in this process --
+//! a restart loses every in-flight job. [`JobStore`] mirrors job state to a SQLite database so
+//! that `GET /parse/:job_id` keeps working across restarts and a completed job's document can be
+//! fetched well after the SSE stream that produced it has closed.
+//!
+//! The `job_queue` table (see [`QueuedJob`]) goes a step further: it durably records a job's
+//! *input*, not just its status, so a job that was still parsing when the process died can
+//! actually be re-run rather than merely reported as failed. See `worker` for the pool that
+//! drains it.
+//!
+//! `jobs` rows otherwise accumulate forever once a job finishes; [`JobStore::expired_job_ids`]
+//! and [`JobStore::delete`] back the periodic sweeper (in `main.rs`) that reclaims them past a
+//! configurable retention TTL.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+fn now_unix() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64
+}
+
+/// Lifecycle state of a job, persisted alongside its progress counters and (once available)
+/// its final document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobState {
+    Queued,
+    Running,
+    Completed,
+    Cancelled,
+    Failed,
+}
+
+impl JobState {
+    fn as_str(self) -> &'static str {
+        match self {
+            JobState::Queued => "queued",
+            JobState::Running => "running",
+            JobState::Completed => "completed",
+            JobState::Cancelled => "cancelled",
+            JobState::Failed => "failed",
+        }
+    }
+
+    fn parse(s: &str) -> Self {
+        match s {
+            "queued" => JobState::Queued,
+            "running" => JobState::Running,
+            "completed" => JobState::Completed,
+            "cancelled" => JobState::Cancelled,
+            _ => JobState::Failed,
+        }
+    }
+}
+
+/// A job's full persisted state, as returned by `GET /parse/:job_id`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobRecord {
+    pub id: Uuid,
+    pub submitted_at: String,
+    pub state: JobState,
+    pub pages_completed: usize,
+    pub total_pages: usize,
+    pub document: Option<serde_json::Value>,
+    pub error: Option<String>,
+}
+
+/// A job queued for durable, restart-surviving processing: its input has already been persisted
+/// to disk at `input_path` (rather than a `NamedTempFile` that would vanish with the process), so
+/// any worker -- not just the one that accepted the request -- can pick it up and run it.
+#[derive(Debug, Clone)]
+pub struct QueuedJob {
+    pub job_id: Uuid,
+    pub input_path: PathBuf,
+    pub page_range: Option<std::ops::Range<usize>>,
+    pub flatten_pdf: bool,
+}
+
+/// SQLite-backed [`JobRecord`] store. `rusqlite`'s `Connection` isn't `Send`-friendly under
+/// concurrent use, so every call is wrapped in a blocking task and the connection itself is
+/// guarded by a plain [`Mutex`] rather than a tokio one (no `.await` ever happens while held).
+#[derive(Clone)]
+pub struct JobStore {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl JobStore {
+    /// Opens (creating if necessary) the SQLite database at `path` and runs the store's schema
+    /// migration. Pass `:memory:` in tests or when no persistence across restarts is needed.
+    pub fn open(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS jobs (
+                id              TEXT PRIMARY KEY,
+                submitted_at    TEXT NOT NULL,
+                state           TEXT NOT NULL,
+                pages_completed INTEGER NOT NULL DEFAULT 0,
+                total_pages     INTEGER NOT NULL DEFAULT 0,
+                document        TEXT,
+                error           TEXT,
+                finished_at     INTEGER
+            );
+            CREATE TABLE IF NOT EXISTS job_queue (
+                job_id           TEXT PRIMARY KEY,
+                input_path       TEXT NOT NULL,
+                page_range_start INTEGER,
+                page_range_end   INTEGER,
+                flatten_pdf      INTEGER NOT NULL,
+                state            TEXT NOT NULL,
+                worker_id        TEXT,
+                heartbeat_at     INTEGER,
+                retry_count      INTEGER NOT NULL DEFAULT 0
+            );",
+        )?;
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    /// Re-hydrates the store after a restart: any job still marked `running` was orphaned by
+    /// the process that was tracking it, since `JobManager` (in `main.rs`) is purely in-memory and
+    /// lost it. Those jobs are marked `failed` so `GET /parse/:job_id` doesn't hang forever
+    /// waiting on a task that no longer exists.
+    pub async fn reap_orphaned_jobs(&self) -> anyhow::Result<usize> {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap();
+            let updated = conn.execute(
+                "UPDATE jobs SET state = ?1, error = ?2 WHERE state = ?3",
+                params![
+                    JobState::Failed.as_str(),
+                    "Job was still running when the service restarted",
+                    JobState::Running.as_str(),
+                ],
+            )?;
+            Ok::<_, anyhow::Error>(updated)
+        })
+        .await?
+    }
+
+    pub async fn create(&self, job_id: Uuid, submitted_at: String) -> anyhow::Result<()> {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            conn.lock().unwrap().execute(
+                "INSERT INTO jobs (id, submitted_at, state, pages_completed, total_pages)
+                 VALUES (?1, ?2, ?3, 0, 0)",
+                params![job_id.to_string(), submitted_at, JobState::Queued.as_str()],
+            )?;
+            Ok(())
+        })
+        .await?
+    }
+
+    pub async fn set_running(&self, job_id: Uuid, total_pages: usize) -> anyhow::Result<()> {
+        self.update(job_id, move |conn| {
+            conn.execute(
+                "UPDATE jobs SET state = ?1, total_pages = ?2 WHERE id = ?3",
+                params![
+                    JobState::Running.as_str(),
+                    total_pages as i64,
+                    job_id.to_string()
+                ],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn set_progress(&self, job_id: Uuid, pages_completed: usize) -> anyhow::Result<()> {
+        self.update(job_id, move |conn| {
+            conn.execute(
+                "UPDATE jobs SET pages_completed = ?1 WHERE id = ?2",
+                params![pages_completed as i64, job_id.to_string()],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn set_completed(
+        &self,
+        job_id: Uuid,
+        document: serde_json::Value,
+    ) -> anyhow::Result<()> {
+        self.update(job_id, move |conn| {
+            conn.execute(
+                "UPDATE jobs SET state = ?1, document = ?2, finished_at = ?3 WHERE id = ?4",
+                params![
+                    JobState::Completed.as_str(),
+                    document.to_string(),
+                    now_unix(),
+                    job_id.to_string()
+                ],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn set_cancelled(&self, job_id: Uuid) -> anyhow::Result<()> {
+        self.update(job_id, move |conn| {
+            conn.execute(
+                "UPDATE jobs SET state = ?1, finished_at = ?2 WHERE id = ?3",
+                params![JobState::Cancelled.as_str(), now_unix(), job_id.to_string()],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn set_failed(&self, job_id: Uuid, error: String) -> anyhow::Result<()> {
+        self.update(job_id, move |conn| {
+            conn.execute(
+                "UPDATE jobs SET state = ?1, error = ?2, finished_at = ?3 WHERE id = ?4",
+                params![
+                    JobState::Failed.as_str(),
+                    error,
+                    now_unix(),
+                    job_id.to_string()
+                ],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn get(&self, job_id: Uuid) -> anyhow::Result<Option<JobRecord>> {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap();
+            let record = conn
+                .query_row(
+                    "SELECT id, submitted_at, state, pages_completed, total_pages, document, error
+                     FROM jobs WHERE id = ?1",
+                    params![job_id.to_string()],
+                    |row| {
+                        let document: Option<String> = row.get(5)?;
+                        Ok(JobRecord {
+                            id: job_id,
+                            submitted_at: row.get(1)?,
+                            state: JobState::parse(&row.get::<_, String>(2)?),
+                            pages_completed: row.get::<_, i64>(3)? as usize,
+                            total_pages: row.get::<_, i64>(4)? as usize,
+                            document: document.and_then(|d| serde_json::from_str(&d).ok()),
+                            error: row.get(6)?,
+                        })
+                    },
+                )
+                .optional()?;
+            Ok(record)
+        })
+        .await?
+    }
+
+    /// Ids of every job whose terminal state (`completed`, `cancelled`, or `failed`) was reached
+    /// more than `retention` ago. Used by the periodic sweeper to find candidates for deletion --
+    /// the caller is still responsible for skipping any that still have a live SSE receiver.
+    pub async fn expired_job_ids(
+        &self,
+        retention: std::time::Duration,
+    ) -> anyhow::Result<Vec<Uuid>> {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap();
+            let cutoff = now_unix() - retention.as_secs() as i64;
+            let mut stmt = conn.prepare(
+                "SELECT id FROM jobs
+                 WHERE state IN ('completed', 'cancelled', 'failed')
+                   AND finished_at IS NOT NULL AND finished_at <= ?1",
+            )?;
+            let ids = stmt
+                .query_map(params![cutoff], |row| row.get::<_, String>(0))?
+                .filter_map(|id| id.ok())
+                .filter_map(|id| Uuid::parse_str(&id).ok())
+                .collect();
+            Ok(ids)
+        })
+        .await?
+    }
+
+    /// Deletes a job's record outright, including its serialized document. Used by the periodic
+    /// sweeper and the manual `DELETE /jobs/{job_id}` endpoint.
+    pub async fn delete(&self, job_id: Uuid) -> anyhow::Result<()> {
+        self.update(job_id, move |conn| {
+            conn.execute(
+                "DELETE FROM jobs WHERE id = ?1",
+                params![job_id.to_string()],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Pushes a job onto the durable queue in the `queued` state. Its input has already been
+    /// written to `job.input_path`, so the row alone is enough for any worker to resume it.
+    pub async fn enqueue(&self, job: QueuedJob) -> anyhow::Result<()> {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            conn.lock().unwrap().execute(
+                "INSERT OR REPLACE INTO job_queue
+                    (job_id, input_path, page_range_start, page_range_end, flatten_pdf, state, retry_count)
+                 VALUES (?1, ?2, ?3, ?4, ?5, 'queued', 0)",
+                params![
+                    job.job_id.to_string(),
+                    job.input_path.to_string_lossy().to_string(),
+                    job.page_range.as_ref().map(|r| r.start as i64),
+                    job.page_range.as_ref().map(|r| r.end as i64),
+                    job.flatten_pdf as i64,
+                ],
+            )?;
+            Ok(())
+        })
+        .await?
+    }
+
+    /// Claims a specific, already-known job for `worker_id`. Used by the request handler that
+    /// just enqueued the job to start processing it immediately, without waiting for a worker to
+    /// pick it up through [`JobStore::claim_next`].
+    pub async fn claim(&self, job_id: Uuid, worker_id: &str) -> anyhow::Result<()> {
+        let conn = self.conn.clone();
+        let worker_id = worker_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            conn.lock().unwrap().execute(
+                "UPDATE job_queue SET state = 'in_progress', worker_id = ?1, heartbeat_at = ?2
+                 WHERE job_id = ?3 AND state = 'queued'",
+                params![worker_id, now_unix(), job_id.to_string()],
+            )?;
+            Ok(())
+        })
+        .await?
+    }
+
+    /// Claims and returns the oldest `queued` row for `worker_id`, atomically transitioning it
+    /// to `in_progress`. Returns `None` when the queue is empty.
+    pub async fn claim_next(&self, worker_id: &str) -> anyhow::Result<Option<QueuedJob>> {
+        let conn = self.conn.clone();
+        let worker_id = worker_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap();
+            let row = conn
+                .query_row(
+                    "SELECT job_id, input_path, page_range_start, page_range_end, flatten_pdf
+                     FROM job_queue WHERE state = 'queued' ORDER BY rowid ASC LIMIT 1",
+                    [],
+                    |row| {
+                        Ok((
+                            row.get::<_, String>(0)?,
+                            row.get::<_, String>(1)?,
+                            row.get::<_, Option<i64>>(2)?,
+                            row.get::<_, Option<i64>>(3)?,
+                            row.get::<_, i64>(4)?,
+                        ))
+                    },
+                )
+                .optional()?;
+
+            let Some((job_id, input_path, start, end, flatten_pdf)) = row else {
+                return Ok(None);
+            };
+
+            conn.execute(
+                "UPDATE job_queue SET state = 'in_progress', worker_id = ?1, heartbeat_at = ?2
+                 WHERE job_id = ?3",
+                params![worker_id, now_unix(), job_id],
+            )?;
+
+            Ok(Some(QueuedJob {
+                job_id: Uuid::parse_str(&job_id)?,
+                input_path: PathBuf::from(input_path),
+                page_range: match (start, end) {
+                    (Some(start), Some(end)) => Some(start as usize..end as usize),
+                    _ => None,
+                },
+                flatten_pdf: flatten_pdf != 0,
+            }))
+        })
+        .await?
+    }
+
+    /// Touches a claimed job's heartbeat so [`JobStore::requeue_stale`] doesn't mistake a worker
+    /// that's still making progress for a dead one.
+    pub async fn heartbeat(&self, job_id: Uuid) -> anyhow::Result<()> {
+        self.update(job_id, move |conn| {
+            conn.execute(
+                "UPDATE job_queue SET heartbeat_at = ?1 WHERE job_id = ?2",
+                params![now_unix(), job_id.to_string()],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Removes a job's durable-queue row once it has reached a terminal state (completed,
+    /// cancelled, or failed past its retry budget) -- the `jobs` table already has the final
+    /// record, so the row no longer serves a purpose.
+    pub async fn finish_queue_entry(&self, job_id: Uuid) -> anyhow::Result<()> {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            conn.lock().unwrap().execute(
+                "DELETE FROM job_queue WHERE job_id = ?1",
+                params![job_id.to_string()],
+            )?;
+            Ok(())
+        })
+        .await?
+    }
+
+    /// Re-queues `in_progress` rows whose heartbeat is older than `heartbeat_ttl` -- the worker
+    /// that claimed them is presumed dead -- up to `max_retries` attempts; beyond that the row is
+    /// moved to `dead_letter` instead of being retried forever. Returns the number of rows moved,
+    /// either way. Run at startup to recover from a crash, and periodically by idle workers.
+    pub async fn requeue_stale(
+        &self,
+        heartbeat_ttl: std::time::Duration,
+        max_retries: usize,
+    ) -> anyhow::Result<usize> {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap();
+            let cutoff = now_unix() - heartbeat_ttl.as_secs() as i64;
+
+            let dead_lettered = conn.execute(
+                "UPDATE job_queue SET state = 'dead_letter'
+                 WHERE state = 'in_progress' AND heartbeat_at <= ?1 AND retry_count >= ?2",
+                params![cutoff, max_retries as i64],
+            )?;
+            let requeued = conn.execute(
+                "UPDATE job_queue SET state = 'queued', worker_id = NULL, heartbeat_at = NULL,
+                    retry_count = retry_count + 1
+                 WHERE state = 'in_progress' AND heartbeat_at <= ?1 AND retry_count < ?2",
+                params![cutoff, max_retries as i64],
+            )?;
+            Ok(dead_lettered + requeued)
+        })
+        .await?
+    }
+
+    async fn update<F>(&self, _job_id: Uuid, f: F) -> anyhow::Result<()>
+    where
+        F: FnOnce(&Connection) -> anyhow::Result<()> + Send + 'static,
+    {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || f(&conn.lock().unwrap())).await?
+    }
+}
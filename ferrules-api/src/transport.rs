@@ -0,0 +1,416 @@
+//! JSON-RPC 2.0 server over stdio, framed with `Content-Length` headers exactly like LSP.
+//!
+//! This exposes the same `parse`/progress/cancel capability as `/parse/sse`, for an editor, CLI,
+//! or orchestration tool that would rather embed ferrules as a long-running child process than
+//! run an HTTP port. `run_parse_job` below uses the same `token.is_cancelled()` pattern for
+//! cancellation as `parse_document_sse_handler` in the `ferrules-api` binary; its synchronous
+//! progress callback spawns a task per page that writes the `parse/progress` notification.
+
+use std::{collections::HashMap, ops::Range, sync::Arc};
+
+use ferrules_core::{ocr::OcrConfig, FerrulesParseConfig, FerrulesParser};
+use memmap2::Mmap;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::{
+    fs::File,
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    sync::Mutex,
+};
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize)]
+struct RpcRequest {
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Debug, Serialize)]
+struct RpcResponse {
+    jsonrpc: &'static str,
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<RpcError>,
+}
+
+#[derive(Debug, Serialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcResponse {
+    fn ok(id: Value, result: Value) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            id,
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    fn err(id: Value, code: i64, message: impl Into<String>) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            id,
+            result: None,
+            error: Some(RpcError {
+                code,
+                message: message.into(),
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct RpcNotification {
+    jsonrpc: &'static str,
+    method: &'static str,
+    params: Value,
+}
+
+/// Params for a `parse/start` request.
+#[derive(Debug, Deserialize)]
+struct ParseStartParams {
+    /// Path to a file on disk readable by this process -- there is no multipart upload over
+    /// stdio, so the caller is expected to have already placed the document where ferrules can
+    /// see it.
+    path: String,
+    page_range: Option<String>,
+    #[serde(default = "default_flatten_pdf")]
+    flatten_pdf: bool,
+}
+
+fn default_flatten_pdf() -> bool {
+    true
+}
+
+/// Params for a `parse/cancel` request, naming the `job_id` a prior `parse/start` response
+/// returned -- mirroring `cancel_job_handler`'s `Path<Uuid>` rather than the request's own id.
+#[derive(Debug, Deserialize)]
+struct ParseCancelParams {
+    job_id: Uuid,
+}
+
+fn parse_page_range(range_str: &str) -> anyhow::Result<Range<usize>> {
+    if let Some((start, end)) = range_str.split_once('-') {
+        let start: usize = start.trim().parse()?;
+        let end: usize = end.trim().parse()?;
+        if start > 0 && end >= start {
+            Ok(Range {
+                start: start - 1,
+                end,
+            })
+        } else {
+            anyhow::bail!("Invalid page range: start must be > 0 and end must be >= start")
+        }
+    } else {
+        let page: usize = range_str.trim().parse()?;
+        if page > 0 {
+            Ok(Range {
+                start: page - 1,
+                end: page,
+            })
+        } else {
+            anyhow::bail!("Invalid page range: page must be > 0")
+        }
+    }
+}
+
+/// Tracks the [`CancellationToken`] for every `parse/start` job still in flight on this
+/// connection, so a later `parse/cancel` naming the same `job_id` can reach it.
+#[derive(Clone, Default)]
+struct ActiveJobs {
+    tokens: Arc<Mutex<HashMap<Uuid, CancellationToken>>>,
+}
+
+impl ActiveJobs {
+    async fn insert(&self, job_id: Uuid, token: CancellationToken) {
+        self.tokens.lock().await.insert(job_id, token);
+    }
+
+    async fn remove(&self, job_id: Uuid) {
+        self.tokens.lock().await.remove(&job_id);
+    }
+
+    /// Cancels `job_id`'s token if it's still tracked, returning whether it was found.
+    async fn cancel(&self, job_id: Uuid) -> bool {
+        match self.tokens.lock().await.get(&job_id) {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Serializes `value` and writes it to `out` under `Content-Length` framing. Held behind a lock
+/// since concurrent jobs' `parse/progress` notifications would otherwise interleave mid-frame.
+async fn write_message(
+    out: &Mutex<tokio::io::Stdout>,
+    value: &impl Serialize,
+) -> anyhow::Result<()> {
+    let body = serde_json::to_vec(value)?;
+    let mut out = out.lock().await;
+    out.write_all(format!("Content-Length: {}\r\n\r\n", body.len()).as_bytes())
+        .await?;
+    out.write_all(&body).await?;
+    out.flush().await?;
+    Ok(())
+}
+
+/// Reads one `Content-Length: N\r\n\r\n<body>` framed message, or `Ok(None)` on a clean EOF
+/// before any header bytes arrive (the client closed stdin).
+async fn read_message(reader: &mut BufReader<tokio::io::Stdin>) -> anyhow::Result<Option<Value>> {
+    let mut content_length = None;
+    let mut header = String::new();
+    loop {
+        header.clear();
+        if reader.read_line(&mut header).await? == 0 {
+            return Ok(None);
+        }
+        let trimmed = header.trim_end();
+        if trimmed.is_empty() {
+            break;
+        }
+        if let Some(value) = trimmed.strip_prefix("Content-Length:") {
+            content_length = Some(value.trim().parse::<usize>()?);
+        }
+    }
+
+    let content_length = content_length
+        .ok_or_else(|| anyhow::anyhow!("message is missing Content-Length header"))?;
+    let mut body = vec![0u8; content_length];
+    reader.read_exact(&mut body).await?;
+    Ok(Some(serde_json::from_slice(&body)?))
+}
+
+/// Runs the JSON-RPC server on stdin/stdout until `shutdown` is received or stdin closes.
+/// `parser` may be shared with an HTTP server running alongside this transport in the same
+/// process; starting this loop costs nothing beyond the stdio plumbing itself.
+pub async fn run_stdio_server(parser: FerrulesParser) -> anyhow::Result<()> {
+    let mut reader = BufReader::new(tokio::io::stdin());
+    let stdout = Arc::new(Mutex::new(tokio::io::stdout()));
+    let active_jobs = ActiveJobs::default();
+
+    loop {
+        let message = match read_message(&mut reader).await {
+            Ok(Some(message)) => message,
+            Ok(None) => break,
+            Err(e) => {
+                tracing::error!("Failed to read a JSON-RPC message: {e:?}");
+                break;
+            }
+        };
+
+        let request: RpcRequest = match serde_json::from_value(message) {
+            Ok(request) => request,
+            Err(e) => {
+                tracing::error!("Malformed JSON-RPC request: {e:?}");
+                continue;
+            }
+        };
+
+        match request.method.as_str() {
+            "parse/start" => match serde_json::from_value::<ParseStartParams>(request.params) {
+                Ok(params) => {
+                    let job_id = Uuid::new_v4();
+                    write_message(
+                        &stdout,
+                        &RpcResponse::ok(request.id, json!({ "job_id": job_id })),
+                    )
+                    .await?;
+                    spawn_parse_job(
+                        parser.clone(),
+                        stdout.clone(),
+                        active_jobs.clone(),
+                        job_id,
+                        params,
+                    );
+                }
+                Err(e) => {
+                    write_message(
+                        &stdout,
+                        &RpcResponse::err(request.id, -32602, format!("invalid params: {e}")),
+                    )
+                    .await?;
+                }
+            },
+            "parse/cancel" => match serde_json::from_value::<ParseCancelParams>(request.params) {
+                Ok(params) => {
+                    let result = if active_jobs.cancel(params.job_id).await {
+                        RpcResponse::ok(request.id, json!({ "cancelled": true }))
+                    } else {
+                        RpcResponse::err(
+                            request.id,
+                            -32000,
+                            format!("job {} not found or already finished", params.job_id),
+                        )
+                    };
+                    write_message(&stdout, &result).await?;
+                }
+                Err(e) => {
+                    write_message(
+                        &stdout,
+                        &RpcResponse::err(request.id, -32602, format!("invalid params: {e}")),
+                    )
+                    .await?;
+                }
+            },
+            "shutdown" => {
+                write_message(&stdout, &RpcResponse::ok(request.id, Value::Null)).await?;
+                break;
+            }
+            other => {
+                write_message(
+                    &stdout,
+                    &RpcResponse::err(request.id, -32601, format!("unknown method: {other}")),
+                )
+                .await?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Spawns `parse/start`'s job as its own task so the request loop above stays free to accept a
+/// `parse/cancel` for it (or start another job) while it runs.
+fn spawn_parse_job(
+    parser: FerrulesParser,
+    stdout: Arc<Mutex<tokio::io::Stdout>>,
+    active_jobs: ActiveJobs,
+    job_id: Uuid,
+    params: ParseStartParams,
+) {
+    tokio::spawn(async move {
+        let token = CancellationToken::new();
+        active_jobs.insert(job_id, token.clone()).await;
+
+        if let Err(e) = run_parse_job(&parser, &stdout, job_id, params, token).await {
+            let notification = RpcNotification {
+                jsonrpc: "2.0",
+                method: "parse/complete",
+                params: json!({ "job_id": job_id, "error": e.to_string() }),
+            };
+            if let Err(write_err) = write_message(&stdout, &notification).await {
+                tracing::error!(
+                    "Failed to write parse/complete notification for job {job_id}: {write_err:?}"
+                );
+            }
+        }
+
+        active_jobs.remove(job_id).await;
+    });
+}
+
+/// Parses `params.path`, emitting a `parse/progress` notification per page and, on success or
+/// cancellation, a single terminal `parse/complete` notification. Any other failure, whether
+/// before the parse starts (e.g. the input file doesn't exist) or during it, is returned as
+/// `Err`; [`spawn_parse_job`] then reports it as `parse/complete` with an `error` field.
+async fn run_parse_job(
+    parser: &FerrulesParser,
+    stdout: &Arc<Mutex<tokio::io::Stdout>>,
+    job_id: Uuid,
+    params: ParseStartParams,
+    token: CancellationToken,
+) -> anyhow::Result<()> {
+    let page_range = params
+        .page_range
+        .as_deref()
+        .map(parse_page_range)
+        .transpose()?;
+
+    let file = File::open(&params.path).await?;
+    let mmap = unsafe { Mmap::map(&file)? };
+
+    let config = FerrulesParseConfig {
+        password: None,
+        flatten_pdf: params.flatten_pdf,
+        page_range,
+        debug_dir: None,
+        ocr: OcrConfig::default(),
+        max_image_pixels: ferrules_core::DEFAULT_MAX_IMAGE_PIXELS,
+        cache: Default::default(),
+        merge: Default::default(),
+        reading_order: Default::default(),
+        ligatures: Default::default(),
+        resume_from: None,
+    };
+
+    let total_pages = parser.get_page_count(&mmap, config.password).await?;
+    let pages_completed = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+
+    let progress_callback = {
+        let stdout = stdout.clone();
+        let pages_completed = pages_completed.clone();
+        move |page_id| {
+            let completed = pages_completed.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+            let stdout = stdout.clone();
+            tokio::spawn(async move {
+                let notification = RpcNotification {
+                    jsonrpc: "2.0",
+                    method: "parse/progress",
+                    params: json!({
+                        "job_id": job_id,
+                        "pages_completed": completed,
+                        "total_pages": total_pages,
+                        "page_id": page_id,
+                    }),
+                };
+                if let Err(e) = write_message(&stdout, &notification).await {
+                    tracing::error!(
+                        "Failed to write parse/progress notification for job {job_id}: {e:?}"
+                    );
+                }
+            });
+        }
+    };
+
+    let cancellation_callback = {
+        let token = token.clone();
+        move || token.is_cancelled()
+    };
+
+    let result = parser
+        .parse_document(
+            &mmap,
+            job_id.to_string(),
+            config,
+            Some(progress_callback),
+            Some(cancellation_callback),
+        )
+        .await;
+
+    let params = match result {
+        Ok(doc) if !token.is_cancelled() => {
+            let total_pages = doc.pages.len();
+            json!({
+                "job_id": job_id,
+                "document": serde_json::to_value(&doc).unwrap_or_default(),
+                "total_pages": total_pages,
+            })
+        }
+        Ok(_) => json!({ "job_id": job_id, "cancelled": true }),
+        Err(e) if e.to_string().contains("cancelled") => {
+            json!({ "job_id": job_id, "cancelled": true })
+        }
+        Err(e) => return Err(e),
+    };
+
+    write_message(
+        stdout,
+        &RpcNotification {
+            jsonrpc: "2.0",
+            method: "parse/complete",
+            params,
+        },
+    )
+    .await
+}
@@ -0,0 +1,145 @@
+//! Background worker pool that drains the durable `job_queue` table (see [`crate::store`]).
+//!
+//! The hot path for `/parse/sse` still parses inline, in the request's own spawned task, for low
+//! latency -- this pool exists purely for recovery. A job's row only survives in `queued` state
+//! if the process died before the inline task could claim it, or flips back from `in_progress`
+//! to `queued` via [`JobStore::requeue_stale`] if the process died mid-parse. Either way, a
+//! worker here will eventually pick it up and finish it headlessly, so the job's final result is
+//! still available from `GET /parse/:job_id` even though the original SSE connection is long gone.
+
+use std::time::Duration;
+
+use ferrules_core::{ocr::OcrConfig, FerrulesParseConfig, FerrulesParser};
+use memmap2::Mmap;
+use tokio::fs::File;
+use uuid::Uuid;
+
+use crate::store::{JobStore, QueuedJob};
+
+/// How long a worker sleeps between queue polls when it finds nothing to claim.
+const IDLE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Runs one worker's claim loop forever: pop the next queued job, parse it, record the result in
+/// `job_store`, and clean up its durable input file. Intended to be spawned once per
+/// `--job-worker-count` at startup and left running for the life of the process.
+///
+/// Each time the queue is found empty, the worker also sweeps for `in_progress` rows whose
+/// heartbeat has gone stale (`heartbeat_ttl`) and re-queues them (up to `max_retries`) or
+/// dead-letters them -- so recovery keeps happening even once the startup sweep has passed.
+pub async fn run_worker(
+    worker_id: String,
+    parser: FerrulesParser,
+    job_store: JobStore,
+    heartbeat_ttl: Duration,
+    max_retries: usize,
+) {
+    loop {
+        match job_store.claim_next(&worker_id).await {
+            Ok(Some(job)) => recover_job(&worker_id, &parser, &job_store, job).await,
+            Ok(None) => {
+                if let Err(e) = job_store.requeue_stale(heartbeat_ttl, max_retries).await {
+                    tracing::error!(
+                        "Worker {worker_id} failed to sweep stale queue entries: {e:?}"
+                    );
+                }
+                tokio::time::sleep(IDLE_POLL_INTERVAL).await;
+            }
+            Err(e) => {
+                tracing::error!("Worker {worker_id} failed to claim a job from the queue: {e:?}");
+                tokio::time::sleep(IDLE_POLL_INTERVAL).await;
+            }
+        }
+    }
+}
+
+/// Re-runs a job that was orphaned by a previous process (or a previous worker in this one): no
+/// live SSE receiver exists for it anymore, so progress and completion are only ever recorded to
+/// `job_store`, never sent over a channel.
+async fn recover_job(
+    worker_id: &str,
+    parser: &FerrulesParser,
+    job_store: &JobStore,
+    job: QueuedJob,
+) {
+    tracing::info!("Worker {worker_id} recovering orphaned job {}", job.job_id);
+
+    let result = parse_queued_job(parser, job_store, &job).await;
+    if let Err(e) = job_store.finish_queue_entry(job.job_id).await {
+        tracing::error!(
+            "Failed to remove queue entry for recovered job {}: {e:?}",
+            job.job_id
+        );
+    }
+    if let Err(e) = tokio::fs::remove_file(&job.input_path).await {
+        tracing::warn!(
+            "Failed to remove durable input file for job {}: {e:?}",
+            job.job_id
+        );
+    }
+
+    match result {
+        Ok(()) => tracing::info!("Worker {worker_id} finished recovered job {}", job.job_id),
+        Err(e) => tracing::error!(
+            "Worker {worker_id} failed recovered job {}: {e:?}",
+            job.job_id
+        ),
+    }
+}
+
+async fn parse_queued_job(
+    parser: &FerrulesParser,
+    job_store: &JobStore,
+    job: &QueuedJob,
+) -> anyhow::Result<()> {
+    let file = File::open(&job.input_path).await?;
+    let mmap = unsafe { Mmap::map(&file)? };
+
+    let config = FerrulesParseConfig {
+        password: None,
+        flatten_pdf: job.flatten_pdf,
+        page_range: job.page_range.clone(),
+        debug_dir: None,
+        ocr: OcrConfig::default(),
+        max_image_pixels: ferrules_core::DEFAULT_MAX_IMAGE_PIXELS,
+        cache: Default::default(),
+        merge: Default::default(),
+        reading_order: Default::default(),
+        ligatures: Default::default(),
+        resume_from: None,
+    };
+
+    let total_pages = parser.get_page_count(&mmap, config.password).await?;
+    job_store.set_running(job.job_id, total_pages).await?;
+
+    let job_id = job.job_id;
+    let job_store_progress = job_store.clone();
+    let progress_callback = move |_page_id| {
+        let job_store = job_store_progress.clone();
+        tokio::spawn(async move {
+            if let Err(e) = job_store.heartbeat(job_id).await {
+                tracing::error!("Failed to heartbeat recovered job {job_id}: {e:?}");
+            }
+        });
+    };
+
+    match parser
+        .parse_document(
+            &mmap,
+            Uuid::new_v4().to_string(),
+            config,
+            Some(progress_callback),
+            None::<fn() -> bool>,
+        )
+        .await
+    {
+        Ok(doc) => {
+            let document = serde_json::to_value(&doc).unwrap_or_default();
+            job_store.set_completed(job.job_id, document).await?;
+            Ok(())
+        }
+        Err(e) => {
+            job_store.set_failed(job.job_id, e.to_string()).await?;
+            Err(e)
+        }
+    }
+}
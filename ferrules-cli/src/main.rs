@@ -1,17 +1,13 @@
 use clap::Parser;
 
 use ferrules_core::{
-    layout::{
-        model::{ORTConfig, ORTLayoutParser, OrtExecutionProvider},
-        ParseLayoutQueue,
-    },
-    parse::{
-        document::{get_doc_length, parse_document},
-        native::ParseNativeQueue,
-    },
-    save_parsed_document,
+    cache::PageCacheConfig,
+    layout::model::{ORTConfig, ORTLayoutParser, OrtExecutionProvider},
+    ocr::{OcrBatchMode, OcrConfig, OcrRecognitionLevel},
+    utils::{create_dirs, save_parsed_document},
+    FerrulesParseConfig, FerrulesParser, MergeConfig,
 };
-use indicatif::{ProgressBar, ProgressState, ProgressStyle};
+use indicatif::{MultiProgress, ProgressBar, ProgressState, ProgressStyle};
 use memmap2::Mmap;
 use std::{
     fmt::Write,
@@ -19,7 +15,7 @@ use std::{
     path::{Path, PathBuf},
     sync::Arc,
 };
-use tokio::fs::File;
+use tokio::{fs::File, sync::Semaphore, task::JoinSet};
 use uuid::Uuid;
 
 #[derive(Parser, Debug)]
@@ -29,16 +25,22 @@ use uuid::Uuid;
     long_about = "Ferrules is an opinionated high-performance document parsing library designed to generate LLM-ready documents efficiently. Built with Rust for seamless deployment across various platforms."
 )]
 struct Args {
-    /// Path to the PDF file to be parsed
+    /// Path to a PDF file, or to a directory to process every PDF found recursively under it
     file_path: PathBuf,
 
-    // /// Process directory instead of single file
-    // #[arg(
-    //     long,
-    //     default_value_t = false,
-    //     help = "Process all PDF files in the specified directory"
-    // )]
-    // directory: bool,
+    /// Glob pattern selecting input PDFs (e.g. "corpus/**/*.pdf"), used instead of
+    /// `file_path` when given
+    #[arg(long, help = "Process every PDF matching this glob pattern")]
+    glob: Option<String>,
+
+    /// Number of PDFs to process concurrently in directory/glob mode
+    #[arg(
+        long,
+        short = 'j',
+        help = "Max number of documents to process concurrently in batch mode"
+    )]
+    jobs: Option<usize>,
+
     #[arg(
         long,
         short('r'),
@@ -127,6 +129,30 @@ struct Args {
     #[arg(long, short = 'O', help = "Ort graph optimization level")]
     graph_opt_level: Option<usize>,
 
+    /// Enable OCR fallback for pages with insufficient native text coverage
+    #[arg(
+        long,
+        default_value_t = cfg!(target_os = "macos"),
+        help = "Enable or disable OCR fallback (Apple Vision on macOS, ONNX Runtime elsewhere)"
+    )]
+    ocr: bool,
+
+    /// Comma-separated BCP-47 language hints for the OCR backend (e.g. "en-US,fr-FR")
+    #[arg(
+        long,
+        value_delimiter = ',',
+        help = "Languages to hint the OCR backend with"
+    )]
+    ocr_languages: Vec<String>,
+
+    /// OCR recognition level: "fast" or "accurate" (default)
+    #[arg(
+        long,
+        default_value = "accurate",
+        help = "OCR recognition level: fast or accurate"
+    )]
+    ocr_level: String,
+
     /// Enable debug mode to output additional information
     #[arg(
         long,
@@ -143,6 +169,61 @@ struct Args {
         help = "Specify the directory to store debug output files"
     )]
     debug_dir: Option<PathBuf>,
+
+    /// Ceiling on rasterized page pixel count (width * height); pages with a larger MediaBox
+    /// are rejected instead of rasterized, guarding against decompression-bomb PDFs
+    #[arg(
+        long,
+        env = "FERRULES_MAX_IMAGE_PIXELS",
+        default_value_t = ferrules_core::DEFAULT_MAX_IMAGE_PIXELS,
+        help = "Max allowed page pixel count (width * height) before rasterization is refused"
+    )]
+    max_image_pixels: u64,
+
+    /// Skip re-running layout inference on pages whose content (text + rendered image) was
+    /// already seen, such as repeated letterheads or re-parses of the same document
+    #[arg(
+        long,
+        default_value_t = false,
+        help = "Enable the content-addressed page cache"
+    )]
+    page_cache: bool,
+
+    #[arg(
+        long,
+        default_value_t = 256,
+        help = "Max pages kept resident in the in-memory page cache"
+    )]
+    page_cache_entries: usize,
+
+    #[arg(
+        long,
+        help = "Directory for the on-disk page cache tier (entries evicted from memory are demoted here)"
+    )]
+    page_cache_dir: Option<PathBuf>,
+
+    /// Directory for checkpoint snapshots, letting a cancelled parse resume from whatever
+    /// pages it already completed instead of starting over. A subdirectory per document name
+    /// is used, in single-file and batch/glob mode alike.
+    #[arg(
+        long,
+        help = "Directory to checkpoint completed pages to, for resuming a cancelled parse"
+    )]
+    resume_dir: Option<PathBuf>,
+
+    #[arg(
+        long,
+        default_value_t = MergeConfig::default().caption_min_overlap,
+        help = "Minimum horizontal overlap ratio for a caption/footnote to be attached to an image/table"
+    )]
+    caption_min_overlap: f32,
+
+    #[arg(
+        long,
+        default_value_t = MergeConfig::default().caption_max_gap,
+        help = "Maximum vertical gap (in points) for a caption/footnote to be attached to an image/table"
+    )]
+    caption_max_gap: f32,
 }
 
 fn parse_page_range(range_str: &str) -> anyhow::Result<Range<usize>> {
@@ -171,24 +252,17 @@ fn parse_page_range(range_str: &str) -> anyhow::Result<Range<usize>> {
     }
 }
 
-fn setup_progress_bar(
-    file_path: &Path,
-    password: Option<&str>,
-    page_range: Option<Range<usize>>,
-) -> ProgressBar {
-    let length_pages = get_doc_length(file_path, password, page_range.clone()).unwrap();
-    let pb = ProgressBar::new(length_pages as u64);
-    pb.set_style(
-        ProgressStyle::with_template(
-            "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {msg}",
-        )
+fn page_progress_style() -> ProgressStyle {
+    ProgressStyle::with_template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {msg}")
         .unwrap()
         .with_key("eta", |state: &ProgressState, w: &mut dyn Write| {
             write!(w, "{:.1}s", state.eta().as_secs_f64()).unwrap()
         })
-        .progress_chars("#>-"),
-    );
-    pb
+        .progress_chars("#>-")
+}
+
+fn file_progress_style() -> ProgressStyle {
+    ProgressStyle::with_template("{spinner:.green} {msg}").unwrap()
 }
 
 fn parse_ep_args(args: &Args) -> Vec<OrtExecutionProvider> {
@@ -209,62 +283,292 @@ fn parse_ep_args(args: &Args) -> Vec<OrtExecutionProvider> {
     providers
 }
 
-#[tokio::main(flavor = "multi_thread")]
-async fn main() {
-    let args = Args::parse();
+fn page_cache_config_from_args(args: &Args) -> PageCacheConfig {
+    PageCacheConfig {
+        enabled: args.page_cache,
+        max_resident_entries: args.page_cache_entries,
+        disk_cache_dir: args.page_cache_dir.clone(),
+    }
+}
 
-    // Check providers
-    let providers = parse_ep_args(&args);
+fn merge_config_from_args(args: &Args) -> MergeConfig {
+    MergeConfig {
+        caption_min_overlap: args.caption_min_overlap,
+        caption_max_gap: args.caption_max_gap,
+    }
+}
 
-    let ort_config = ORTConfig {
-        execution_providers: providers,
-        intra_threads: args.intra_threads,
-        inter_threads: args.inter_threads,
-        opt_level: args.graph_opt_level.map(|v| v.try_into().unwrap()),
+fn ocr_config_from_args(args: &Args) -> OcrConfig {
+    let level = match args.ocr_level.to_lowercase().as_str() {
+        "fast" => OcrRecognitionLevel::Fast,
+        _ => OcrRecognitionLevel::Accurate,
     };
-    // Global tasks
-    let layout_model = Arc::new(ORTLayoutParser::new(ort_config).expect("can't load layout model"));
-    let layout_queue = ParseLayoutQueue::new(layout_model);
-    let native_queue = ParseNativeQueue::new();
+    OcrConfig {
+        enabled: args.ocr,
+        languages: args.ocr_languages.clone(),
+        level,
+        batch_mode: OcrBatchMode::default(),
+        backend: Default::default(),
+    }
+}
 
-    let page_range = args
-        .page_range
-        .map(|page_range_str| parse_page_range(&page_range_str).unwrap());
+/// Recursively finds every `*.pdf` file under `dir`.
+fn collect_pdfs_in_dir(dir: &Path, out: &mut Vec<PathBuf>) -> anyhow::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_pdfs_in_dir(&path, out)?;
+        } else if path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("pdf"))
+        {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
 
-    let pb = setup_progress_bar(&args.file_path, None, page_range.clone());
-    let pbc = pb.clone();
+/// Resolves the set of PDFs this invocation should process: a `--glob` pattern, every
+/// `*.pdf` under `file_path` if it's a directory, or just `file_path` itself.
+fn discover_inputs(args: &Args) -> anyhow::Result<Vec<PathBuf>> {
+    if let Some(pattern) = &args.glob {
+        let mut files = glob::glob(pattern)?
+            .filter_map(Result::ok)
+            .filter(|p| p.is_file())
+            .collect::<Vec<_>>();
+        files.sort();
+        return Ok(files);
+    }
+
+    if args.file_path.is_dir() {
+        let mut files = Vec::new();
+        collect_pdfs_in_dir(&args.file_path, &mut files)?;
+        files.sort();
+        return Ok(files);
+    }
 
-    let doc_name = args
-        .file_path
+    Ok(vec![args.file_path.clone()])
+}
+
+fn doc_name_for(file_path: &Path) -> String {
+    file_path
         .file_name()
         .and_then(|name| name.to_str())
         .and_then(|name| name.split('.').next().map(|s| s.to_owned()))
-        .unwrap_or(Uuid::new_v4().to_string());
+        .unwrap_or(Uuid::new_v4().to_string())
+}
+
+/// Output directory for a given input file, mirroring its path relative to `input_root`
+/// underneath `output_dir` so batch runs don't collide on same-named files from
+/// different subdirectories.
+fn mirrored_output_dir(
+    output_dir: Option<&PathBuf>,
+    input_root: &Path,
+    file_path: &Path,
+) -> Option<PathBuf> {
+    let base = output_dir.cloned().unwrap_or_else(|| PathBuf::from("."));
+    let relative_dir = file_path
+        .parent()
+        .and_then(|parent| parent.strip_prefix(input_root).ok());
+    match relative_dir {
+        Some(relative_dir) if !relative_dir.as_os_str().is_empty() => Some(base.join(relative_dir)),
+        _ => Some(base),
+    }
+}
+
+async fn parse_and_save(
+    parser: FerrulesParser,
+    file_path: PathBuf,
+    output_dir: Option<PathBuf>,
+    page_range: Option<Range<usize>>,
+    ocr_config: OcrConfig,
+    max_image_pixels: u64,
+    page_cache_config: PageCacheConfig,
+    merge_config: MergeConfig,
+    resume_dir: Option<PathBuf>,
+    debug: bool,
+    debug_dir: Option<PathBuf>,
+    save_images: bool,
+    page_callback: Option<impl FnOnce(u32) + Send + 'static + Clone>,
+) -> anyhow::Result<()> {
+    let doc_name = doc_name_for(&file_path);
+    let file = File::open(&file_path).await?;
+    let mmap = unsafe { Mmap::map(&file)? };
+    let resume_from = resume_dir.map(|dir| dir.join(&doc_name));
 
-    // TODO : refac memap
-    let file = File::open(&args.file_path).await.unwrap();
-    let mmap = unsafe { Mmap::map(&file).unwrap() };
+    let config = FerrulesParseConfig {
+        password: None,
+        flatten_pdf: true,
+        page_range,
+        debug_dir,
+        ocr: ocr_config,
+        max_image_pixels,
+        cache: page_cache_config,
+        merge: merge_config,
+        reading_order: Default::default(),
+        ligatures: Default::default(),
+        resume_from,
+    };
 
-    let doc = parse_document(
-        &mmap,
-        doc_name,
-        None,
-        true,
+    let doc = parser
+        .parse_document(&mmap, doc_name, config, page_callback, None::<fn() -> bool>)
+        .await?;
+
+    let (res_dir_path, _debug_path, dir_lock) =
+        create_dirs(output_dir, &doc.doc_name, debug, save_images)?;
+    save_parsed_document(&doc, res_dir_path, save_images, false, false, dir_lock)
+}
+
+async fn run_single(args: Args, parser: FerrulesParser, page_range: Option<Range<usize>>) {
+    let pb = ProgressBar::new_spinner();
+    pb.set_style(page_progress_style());
+    let pbc = pb.clone();
+
+    parse_and_save(
+        parser,
+        args.file_path.clone(),
+        args.output_dir.clone(),
         page_range,
-        layout_queue,
-        native_queue,
+        ocr_config_from_args(&args),
+        args.max_image_pixels,
+        page_cache_config_from_args(&args),
+        merge_config_from_args(&args),
+        args.resume_dir.clone(),
         args.debug,
-        Some(move |page_id| {
+        args.debug_dir.clone(),
+        args.save_images,
+        Some(move |page_id: u32| {
             pbc.set_message(format!("Page #{}", page_id + 1));
             pbc.inc(1u64);
         }),
     )
     .await
-    .unwrap();
+    .expect("failed to parse document");
+
+    pb.finish_with_message("Parsed document");
+}
+
+async fn run_batch(
+    args: Args,
+    parser: FerrulesParser,
+    page_range: Option<Range<usize>>,
+    files: Vec<PathBuf>,
+) {
+    let jobs = args
+        .jobs
+        .unwrap_or_else(|| {
+            std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1)
+        })
+        .max(1);
+    let semaphore = Arc::new(Semaphore::new(jobs));
 
-    pb.finish_with_message(format!(
-        "Parsed document in {}ms",
-        doc.metadata.parsing_duration.as_millis()
-    ));
-    save_parsed_document(&doc, args.output_dir.as_ref(), args.save_images).unwrap();
+    let input_root = if args.file_path.is_dir() {
+        args.file_path.clone()
+    } else {
+        args.file_path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default()
+    };
+
+    let multi = MultiProgress::new();
+    let aggregate = multi.add(ProgressBar::new(files.len() as u64));
+    aggregate.set_style(page_progress_style());
+    aggregate.set_message("documents processed");
+
+    let mut set = JoinSet::new();
+    for file_path in files {
+        let permit = Arc::clone(&semaphore);
+        let parser = parser.clone();
+        let output_dir = mirrored_output_dir(args.output_dir.as_ref(), &input_root, &file_path);
+        let ocr_config = ocr_config_from_args(&args);
+        let max_image_pixels = args.max_image_pixels;
+        let page_cache_config = page_cache_config_from_args(&args);
+        let merge_config = merge_config_from_args(&args);
+        let resume_dir = args.resume_dir.clone();
+        let page_range = page_range.clone();
+        let debug = args.debug;
+        let debug_dir = args.debug_dir.clone();
+        let save_images = args.save_images;
+        let aggregate = aggregate.clone();
+
+        let file_bar = multi.add(ProgressBar::new_spinner());
+        file_bar.set_style(file_progress_style());
+        file_bar.enable_steady_tick(std::time::Duration::from_millis(100));
+        file_bar.set_message(file_path.display().to_string());
+
+        set.spawn(async move {
+            let _permit = permit.acquire_owned().await.unwrap();
+            let result = parse_and_save(
+                parser,
+                file_path.clone(),
+                output_dir,
+                page_range,
+                ocr_config,
+                max_image_pixels,
+                page_cache_config,
+                merge_config,
+                resume_dir,
+                debug,
+                debug_dir,
+                save_images,
+                None::<fn(u32)>,
+            )
+            .await;
+
+            match &result {
+                Ok(()) => file_bar.finish_with_message(format!("done: {}", file_path.display())),
+                Err(e) => {
+                    file_bar.finish_with_message(format!("failed: {} ({e})", file_path.display()))
+                }
+            }
+            aggregate.inc(1);
+            result
+        });
+    }
+
+    let mut failures = 0usize;
+    while let Some(result) = set.join_next().await {
+        if !matches!(result, Ok(Ok(()))) {
+            failures += 1;
+        }
+    }
+    aggregate.finish_with_message(format!("{failures} document(s) failed"));
+}
+
+#[tokio::main(flavor = "multi_thread")]
+async fn main() {
+    let args = Args::parse();
+
+    let providers = parse_ep_args(&args);
+    let ort_config = ORTConfig {
+        execution_providers: providers,
+        intra_threads: args.intra_threads,
+        inter_threads: args.inter_threads,
+        nms_top_k: ORTLayoutParser::DEFAULT_NMS_TOP_K,
+        keep_top_k: None,
+        pixel_offset: 0.0,
+        precision: Default::default(),
+        conf_threshold: None,
+        nms_mode: Default::default(),
+    };
+
+    // One shared layout model (and its internal queues) for every document this
+    // invocation processes, whether that's a single file or an entire batch.
+    let parser = FerrulesParser::new(ort_config);
+
+    let page_range = args
+        .page_range
+        .clone()
+        .map(|page_range_str| parse_page_range(&page_range_str).unwrap());
+
+    let files = discover_inputs(&args).expect("failed to discover input PDFs");
+
+    if files.len() == 1 && args.glob.is_none() && !args.file_path.is_dir() {
+        run_single(args, parser, page_range).await;
+    } else {
+        run_batch(args, parser, page_range, files).await;
+    }
 }
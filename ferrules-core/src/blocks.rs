@@ -0,0 +1,265 @@
+use anyhow::bail;
+use image::DynamicImage;
+use serde::{Deserialize, Serialize};
+
+use crate::entities::{
+    append_collapsing_spans, AnnotationKind, BBox, Element, ElementType, PageID, StyledSpan,
+};
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct ImageBlock {
+    pub(crate) id: usize,
+    /// Short, single-line description for accessibility (`alt=` in HTML), derived from the
+    /// figure's caption/footnote text when one is available.
+    pub(crate) alt_text: Option<String>,
+    /// Full figure/footnote prose associated with the image, as opposed to `alt_text`'s
+    /// accessibility-oriented summary.
+    pub(crate) caption: Option<String>,
+    /// Native-resolution bitmap decoded from the PDF's own image XObject, when
+    /// [`crate::parse::page::build_page_elements`] found one overlapping this figure. `None`
+    /// means there was no matching XObject (e.g. a vector figure), so `save_doc_images` falls
+    /// back to cropping the page raster.
+    #[serde(skip_serializing, skip_deserializing)]
+    pub(crate) native_image: Option<DynamicImage>,
+    /// This figure's 1-based position among all images in the document, assigned by
+    /// [`crate::parse::figures::number_figures`] in document order. `None` until that pass has
+    /// run (e.g. for a lone page parsed outside the full document pipeline).
+    pub(crate) number: Option<usize>,
+}
+
+impl ImageBlock {
+    pub(crate) fn path(&self) -> String {
+        format!("image_{}.png", self.id)
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct TextBlock {
+    pub(crate) text: String,
+    /// Styled runs backing `text`, in the same order, for renderers that want bold/italic or
+    /// font-size information instead of the flattened string.
+    pub(crate) spans: Vec<StyledSpan>,
+    /// Ids of the `Image` blocks this text's "Figure N"/"Fig. N" mentions resolve to, found by
+    /// [`crate::parse::figures::link_figure_references`]. Empty until that pass has run.
+    pub(crate) references: Vec<usize>,
+}
+
+/// Appends `new_spans` onto `spans`, inserting `separator` at the join point so two merged
+/// text blocks don't run together, then collapsing the boundary run if both sides share a style.
+fn push_spans_with_separator(
+    spans: &mut Vec<StyledSpan>,
+    mut new_spans: Vec<StyledSpan>,
+    separator: char,
+) {
+    if let Some(first) = new_spans.first_mut() {
+        first.text.insert(0, separator);
+    }
+    append_collapsing_spans(spans, new_spans);
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct List {
+    pub(crate) items: Vec<String>,
+}
+
+/// A footnote reconciled across the document by [`crate::parse::footnotes::link_footnotes`]: its
+/// in-text reference marker(s) and, when one was found, the block that defines it. Unmatched
+/// references or definitions are kept with the other side empty rather than dropped, so callers
+/// can surface them as diagnostics instead of silently losing footnote content.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct Footnote {
+    /// The marker text shared by the reference(s) and definition, e.g. `"1"`.
+    pub label: String,
+    /// Ids of every block whose text contains an in-text marker for this label.
+    pub reference_block_ids: Vec<usize>,
+    /// Id of the block that defines this footnote, if one was found.
+    pub definition_block_id: Option<usize>,
+}
+
+/// Heading rank assigned to a `Title` block, derived by clustering title font sizes.
+pub type TitleLevel = u8;
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Title {
+    pub(crate) level: TitleLevel,
+    pub(crate) text: String,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Table {
+    pub(crate) rows: Vec<Vec<String>>,
+    /// Caption/footnote text geometrically associated with this table, if one was found.
+    /// See [`crate::parse::merge::MergeConfig`].
+    pub(crate) caption: Option<String>,
+}
+
+/// A single PDF annotation surfaced as its own block, so exporters can emit `<mark>`/`<a>`
+/// (or Markdown's equivalents) instead of discarding the annotation entirely. See
+/// [`crate::entities::Annotation`].
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AnnotationBlock {
+    pub(crate) kind: AnnotationKind,
+    /// Text the annotation covers (markup) or carries (a note). `None` for an unmatched link.
+    pub(crate) text: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(tag = "block_type")]
+pub enum BlockType {
+    Header(TextBlock),
+    Footer(TextBlock),
+    Title(Title),
+    ListBlock(List),
+    TextBlock(TextBlock),
+    Image(ImageBlock),
+    Table(Table),
+    Annotation(AnnotationBlock),
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Block {
+    pub id: usize,
+    pub kind: BlockType,
+    pub pages_id: Vec<PageID>,
+    pub bbox: BBox,
+}
+
+impl Block {
+    pub(crate) fn merge(&mut self, element: Element) -> anyhow::Result<()> {
+        match &mut self.kind {
+            BlockType::TextBlock(text) => {
+                if let ElementType::Text = &element.kind {
+                    self.bbox.merge(&element.bbox);
+                    text.text.push('\n');
+                    text.text.push_str(&element.text_block.text);
+                    push_spans_with_separator(&mut text.spans, element.spans, '\n');
+
+                    Ok(())
+                } else {
+                    bail!("can't merge element in textblock")
+                }
+            }
+            BlockType::ListBlock(list) => {
+                if let ElementType::ListItem = &element.kind {
+                    self.bbox.merge(&element.bbox);
+                    list.items.push(element.text_block.text);
+                    Ok(())
+                } else {
+                    bail!("can't merge element in Listblock")
+                }
+            }
+            BlockType::Header(text) => {
+                if let ElementType::Header = &element.kind {
+                    self.bbox.merge(&element.bbox);
+                    text.text.push('\n');
+                    text.text.push_str(&element.text_block.text);
+                    push_spans_with_separator(&mut text.spans, element.spans, '\n');
+                    Ok(())
+                } else {
+                    bail!("can't merge element in header block")
+                }
+            }
+            BlockType::Footer(text) => {
+                if let ElementType::Footer = &element.kind {
+                    self.bbox.merge(&element.bbox);
+                    text.text.push('\n');
+                    text.text.push_str(&element.text_block.text);
+                    push_spans_with_separator(&mut text.spans, element.spans, '\n');
+                    Ok(())
+                } else {
+                    bail!("can't merge element in footer block")
+                }
+            }
+            BlockType::Title(_title) => todo!(),
+            BlockType::Image(_image_block) => todo!(),
+            BlockType::Annotation(_annotation_block) => {
+                bail!("can't merge element in annotation block")
+            }
+            BlockType::Table(table) => {
+                if let ElementType::Table = &element.kind {
+                    self.bbox.merge(&element.bbox);
+                    if let Some(rows) = element.table_rows {
+                        table.rows.extend(rows);
+                    }
+                    Ok(())
+                } else {
+                    bail!("can't merge element in table block")
+                }
+            }
+        }
+    }
+
+    pub(crate) fn label(&self) -> &str {
+        match self.kind {
+            BlockType::Header(_) => "HEADER",
+            BlockType::Footer(_) => "FOOTER",
+            BlockType::TextBlock(_) => "TEXT",
+            BlockType::Title(_) => "TITLE",
+            BlockType::ListBlock(_) => "LIST",
+            BlockType::Image(_) => "IMAGE",
+            BlockType::Table(_) => "TABLE",
+            BlockType::Annotation(_) => "ANNOTATION",
+        }
+    }
+}
+
+/// Which structural constructs are present in a set of blocks, computed by [`Features::scan`].
+///
+/// Renderers and export pipelines use this to decide which packages/headers they need to emit
+/// (e.g. only load table CSS when `tables` is set) and callers can cheaply query "does this PDF
+/// contain footnotes?" without walking the block tree themselves. `BitOr`-composable so a
+/// document-level summary can be folded from per-page (or otherwise partial) feature sets.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Features {
+    pub images: bool,
+    pub tables: bool,
+    pub captions: bool,
+    pub footnotes: bool,
+}
+
+impl Features {
+    /// Walks `blocks` and reports which constructs appear among them. Footnotes are linked
+    /// separately (see [`crate::parse::footnotes::link_footnotes`]), so this only ever sets
+    /// `images`, `tables` and `captions`; fold in a footnote presence check with `|`.
+    pub fn scan(blocks: &[Block]) -> Self {
+        let mut features = Self::default();
+        for block in blocks {
+            match &block.kind {
+                BlockType::Image(image) => {
+                    features.images = true;
+                    features.captions |= image.caption.is_some();
+                }
+                BlockType::Table(table) => {
+                    features.tables = true;
+                    features.captions |= table.caption.is_some();
+                }
+                BlockType::Header(_)
+                | BlockType::Footer(_)
+                | BlockType::Title(_)
+                | BlockType::ListBlock(_)
+                | BlockType::TextBlock(_)
+                | BlockType::Annotation(_) => {}
+            }
+        }
+        features
+    }
+}
+
+impl std::ops::BitOr for Features {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self {
+            images: self.images || rhs.images,
+            tables: self.tables || rhs.tables,
+            captions: self.captions || rhs.captions,
+            footnotes: self.footnotes || rhs.footnotes,
+        }
+    }
+}
+
+impl std::ops::BitOrAssign for Features {
+    fn bitor_assign(&mut self, rhs: Self) {
+        *self = *self | rhs;
+    }
+}
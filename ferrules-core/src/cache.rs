@@ -0,0 +1,122 @@
+//! Content-addressed cache for fully-parsed pages.
+//!
+//! Lets [`crate::FerrulesParser`] skip the layout-model pass on pages it has already fully
+//! parsed -- repeated letterheads, boilerplate pages, or re-parses of the same document --
+//! by keying on a stable hash of the page's content rather than its position in a document.
+
+use std::{
+    num::NonZeroUsize,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use lru::LruCache;
+use xxhash_rust::xxh3::Xxh3;
+
+use crate::entities::{Line, StructuredPage};
+
+/// Configuration for the page cache, threaded down from
+/// [`crate::parse::document::FerrulesParseConfig`].
+#[derive(Debug, Clone)]
+pub struct PageCacheConfig {
+    /// Whether the cache is consulted and populated for this parse.
+    pub enabled: bool,
+    /// Max number of fully-parsed pages kept resident in memory (the "hot" tier). Pages
+    /// evicted from here are demoted to the on-disk tier when one is configured.
+    pub max_resident_entries: usize,
+    /// Optional directory backing the "on-disk" tier. When `None`, eviction from memory
+    /// simply drops the entry.
+    pub disk_cache_dir: Option<PathBuf>,
+}
+
+impl Default for PageCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_resident_entries: 256,
+            disk_cache_dir: None,
+        }
+    }
+}
+
+/// Hit/miss counts accumulated over a single `parse_document` call.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PageCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+/// Stable content hash for a native-parsed page: its text spans plus the rendered page
+/// image bytes. Two pages hashing identically are treated as interchangeable for caching
+/// purposes (their `id` is the only thing that differs once restored from cache).
+pub(crate) fn page_content_hash(text_lines: &[Line], image_bytes: &[u8]) -> u64 {
+    let mut hasher = Xxh3::new();
+    for line in text_lines {
+        hasher.update(line.text.as_bytes());
+    }
+    hasher.update(image_bytes);
+    hasher.digest()
+}
+
+/// Tiered cache of fully-parsed pages, shared across every document a single
+/// [`crate::FerrulesParser`] processes. A resident in-memory LRU backs an optional on-disk
+/// tier: entries evicted from memory are demoted to disk instead of dropped, and a disk hit
+/// is promoted back into memory.
+///
+/// The on-disk tier does not persist the rendered page image, mirroring how
+/// [`crate::entities::Page::image`] is already excluded from a document's own JSON output --
+/// only a resident-tier hit returns a page with its original image intact.
+pub(crate) struct PageCache {
+    resident: Mutex<LruCache<u64, Arc<StructuredPage>>>,
+    disk_dir: Option<PathBuf>,
+}
+
+impl PageCache {
+    pub(crate) fn new(config: &PageCacheConfig) -> Self {
+        let capacity = NonZeroUsize::new(config.max_resident_entries.max(1)).unwrap();
+        if let Some(dir) = &config.disk_cache_dir {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        Self {
+            resident: Mutex::new(LruCache::new(capacity)),
+            disk_dir: config.disk_cache_dir.clone(),
+        }
+    }
+
+    fn disk_path(&self, hash: u64) -> Option<PathBuf> {
+        self.disk_dir
+            .as_ref()
+            .map(|dir| dir.join(format!("{hash:016x}.page.json")))
+    }
+
+    /// Looks up `hash`, checking the resident tier first and falling back to the on-disk
+    /// tier. A disk hit is promoted back into the resident tier.
+    pub(crate) fn get(&self, hash: u64) -> Option<Arc<StructuredPage>> {
+        if let Some(page) = self.resident.lock().unwrap().get(&hash).cloned() {
+            return Some(page);
+        }
+
+        let path = self.disk_path(hash)?;
+        let bytes = std::fs::read(path).ok()?;
+        let page = Arc::new(serde_json::from_slice::<StructuredPage>(&bytes).ok()?);
+        self.resident.lock().unwrap().put(hash, Arc::clone(&page));
+        Some(page)
+    }
+
+    /// Inserts a freshly-parsed page into the resident tier, demoting whatever it evicts to
+    /// the on-disk tier (if configured).
+    pub(crate) fn insert(&self, hash: u64, page: Arc<StructuredPage>) {
+        let evicted = self.resident.lock().unwrap().push(hash, page);
+        let Some((evicted_hash, evicted_page)) = evicted else {
+            return;
+        };
+        if evicted_hash == hash {
+            return; // replaced the same key in place, nothing to demote
+        }
+        if let Some(path) = self.disk_path(evicted_hash) {
+            if let Ok(bytes) = serde_json::to_vec(evicted_page.as_ref()) {
+                let _ = std::fs::write(path, bytes);
+            }
+        }
+    }
+}
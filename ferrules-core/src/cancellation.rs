@@ -0,0 +1,30 @@
+//! Structured, per-request cancellation for the native and layout queues (see
+//! [`crate::parse::native::ParseNativeQueue`] and [`crate::layout::ParseLayoutQueue`]), as an
+//! alternative to [`crate::layout::ParseLayoutQueue::flush`]'s coarse "cancel everything
+//! pending" behavior.
+
+use tokio_util::sync::CancellationToken;
+
+/// Returned by a queue's `push`, this owns the [`CancellationToken`] the pushed request was
+/// given, so the caller can cancel that one document without touching any other in-flight
+/// work.
+#[derive(Debug, Clone)]
+pub struct ParseHandle {
+    token: CancellationToken,
+}
+
+impl ParseHandle {
+    pub(crate) fn new(token: CancellationToken) -> Self {
+        Self { token }
+    }
+
+    /// Cancels the request this handle was issued for.
+    pub fn cancel(&self) {
+        self.token.cancel();
+    }
+
+    /// Whether the request this handle was issued for has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+}
@@ -0,0 +1,104 @@
+//! On-disk checkpoint snapshots for resumable document parsing.
+//!
+//! As pages finish in `parse_doc_pages`'s join loop they're written here, keyed by `PageID`
+//! within a single document. A parse cancelled partway through a large PDF leaves the
+//! snapshot directory intact, so a later call against the same document and page range can
+//! skip straight to dispatching work for whatever pages are still missing instead of starting
+//! over from page one.
+
+use std::{
+    collections::{HashMap, HashSet},
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::entities::{PageID, StructuredPage};
+
+/// Records which pages of a document a snapshot directory already has, plus enough context
+/// (document fingerprint, page range) to refuse resuming it against a different document.
+#[derive(Debug, Deserialize, Serialize)]
+struct CheckpointManifest {
+    fingerprint: u64,
+    page_range: Option<Range<usize>>,
+    completed_pages: HashSet<PageID>,
+}
+
+fn manifest_path(dir: &Path) -> PathBuf {
+    dir.join("manifest.json")
+}
+
+fn page_path(dir: &Path, page_id: PageID) -> PathBuf {
+    dir.join(format!("page_{page_id:06}.zst"))
+}
+
+/// Stable fingerprint for a document's bytes, used to make sure a snapshot directory is only
+/// ever resumed against the document it was created for.
+pub(crate) fn document_fingerprint(data: &[u8]) -> u64 {
+    xxhash_rust::xxh3::xxh3_64(data)
+}
+
+/// A snapshot directory tracking per-page parse progress for a single document.
+pub(crate) struct Checkpoint {
+    dir: PathBuf,
+    manifest: CheckpointManifest,
+}
+
+impl Checkpoint {
+    /// Opens (or initializes) a checkpoint directory for `fingerprint`/`page_range`. A
+    /// manifest left over from a different document or page range is discarded rather than
+    /// silently mixed in with this parse.
+    pub(crate) fn open(
+        dir: PathBuf,
+        fingerprint: u64,
+        page_range: Option<Range<usize>>,
+    ) -> anyhow::Result<Self> {
+        std::fs::create_dir_all(&dir)?;
+        let manifest = std::fs::read(manifest_path(&dir))
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<CheckpointManifest>(&bytes).ok())
+            .filter(|m: &CheckpointManifest| {
+                m.fingerprint == fingerprint && m.page_range == page_range
+            })
+            .unwrap_or(CheckpointManifest {
+                fingerprint,
+                page_range,
+                completed_pages: HashSet::new(),
+            });
+        Ok(Self { dir, manifest })
+    }
+
+    pub(crate) fn is_complete(&self, page_id: PageID) -> bool {
+        self.manifest.completed_pages.contains(&page_id)
+    }
+
+    /// Loads every page this checkpoint already has recorded as complete.
+    pub(crate) fn load_completed(&self) -> HashMap<PageID, StructuredPage> {
+        self.manifest
+            .completed_pages
+            .iter()
+            .filter_map(|&page_id| {
+                let compressed = std::fs::read(page_path(&self.dir, page_id)).ok()?;
+                let bytes = zstd::decode_all(compressed.as_slice()).ok()?;
+                let page = serde_json::from_slice::<StructuredPage>(&bytes).ok()?;
+                Some((page_id, page))
+            })
+            .collect()
+    }
+
+    /// Persists a freshly-parsed page to disk and records it as done, so a later resume skips
+    /// it. Errors are the caller's to decide whether to treat as fatal; a failed checkpoint
+    /// write doesn't invalidate the page that was already successfully parsed.
+    pub(crate) fn save_page(&mut self, page: &StructuredPage) -> anyhow::Result<()> {
+        let json = serde_json::to_vec(page)?;
+        let compressed = zstd::encode_all(json.as_slice(), 0)?;
+        crate::utils::atomic_write(&page_path(&self.dir, page.id), &compressed)?;
+        self.manifest.completed_pages.insert(page.id);
+        crate::utils::atomic_write(
+            &manifest_path(&self.dir),
+            &serde_json::to_vec(&self.manifest)?,
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,235 @@
+//! Token-budgeted chunking of a parsed document's merged blocks, for feeding extracted content
+//! into embedding/retrieval pipelines.
+//!
+//! [`chunk_document`] walks [`ParsedDocument::blocks`] in reading order, greedily accumulating
+//! block text until the next block would push a chunk past its token budget, then starts the
+//! next chunk with a trailing overlap window for context continuity. A single block is never
+//! split unless it alone exceeds the budget, in which case it's sentence-split.
+
+use crate::{
+    blocks::{Block, BlockType, TitleLevel},
+    entities::{BBox, PageID, ParsedDocument},
+};
+
+/// Counts (and truncates) tokens for a piece of text. The default [`WhitespaceTokenizer`]
+/// approximates tokens as whitespace-separated words; plug in a real tokenizer -- e.g. one
+/// backed by a HuggingFace `tokenizers::Tokenizer` -- by implementing this trait for more
+/// accurate budgets.
+pub trait Tokenizer {
+    /// Number of tokens `text` would consume.
+    fn count(&self, text: &str) -> usize;
+
+    /// The trailing `n_tokens` tokens of `text`, used to seed the next chunk's overlap window.
+    fn last_n_tokens(&self, text: &str, n_tokens: usize) -> String;
+}
+
+/// Whitespace-based fallback tokenizer: each run of non-whitespace characters is one token.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct WhitespaceTokenizer;
+
+impl Tokenizer for WhitespaceTokenizer {
+    fn count(&self, text: &str) -> usize {
+        text.split_whitespace().count()
+    }
+
+    fn last_n_tokens(&self, text: &str, n_tokens: usize) -> String {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        let start = words.len().saturating_sub(n_tokens);
+        words[start..].join(" ")
+    }
+}
+
+/// Configuration for [`chunk_document`].
+pub struct ChunkConfig<T: Tokenizer = WhitespaceTokenizer> {
+    /// Target token count per chunk. A chunk is closed as soon as the next block would push it
+    /// past this.
+    pub target_tokens: usize,
+    /// Number of trailing tokens from the previous chunk repeated at the start of the next, for
+    /// context continuity across the boundary.
+    pub overlap_tokens: usize,
+    pub tokenizer: T,
+}
+
+impl Default for ChunkConfig<WhitespaceTokenizer> {
+    fn default() -> Self {
+        Self {
+            target_tokens: 512,
+            overlap_tokens: 64,
+            tokenizer: WhitespaceTokenizer,
+        }
+    }
+}
+
+/// A retrieval-ready slice of a parsed document's blocks, sized to a token budget.
+#[derive(Debug, Clone)]
+pub struct Chunk {
+    pub text: String,
+    pub token_count: usize,
+    /// Pages this chunk's text was drawn from, in first-seen order.
+    pub page_ids: Vec<PageID>,
+    /// Bounding boxes of every block (or block fragment) folded into this chunk.
+    pub bboxes: Vec<BBox>,
+    /// Section heading path in force when this chunk started, outermost first (e.g.
+    /// `["Chapter 1", "Section 1.2"]`), derived from the document's title hierarchy.
+    pub heading_path: Vec<String>,
+}
+
+struct ChunkBuilder {
+    text: String,
+    page_ids: Vec<PageID>,
+    bboxes: Vec<BBox>,
+    heading_path: Vec<String>,
+}
+
+impl ChunkBuilder {
+    fn new(heading_path: Vec<String>) -> Self {
+        Self {
+            text: String::new(),
+            page_ids: Vec::new(),
+            bboxes: Vec::new(),
+            heading_path,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    fn push(&mut self, text: &str, block: &Block) {
+        if !self.text.is_empty() {
+            self.text.push_str("\n\n");
+        }
+        self.text.push_str(text);
+        for &page_id in &block.pages_id {
+            if !self.page_ids.contains(&page_id) {
+                self.page_ids.push(page_id);
+            }
+        }
+        self.bboxes.push(block.bbox.clone());
+    }
+
+    fn finish<T: Tokenizer>(self, tokenizer: &T) -> Chunk {
+        let token_count = tokenizer.count(&self.text);
+        Chunk {
+            text: self.text,
+            token_count,
+            page_ids: self.page_ids,
+            bboxes: self.bboxes,
+            heading_path: self.heading_path,
+        }
+    }
+}
+
+fn block_text(block: &Block) -> String {
+    match &block.kind {
+        BlockType::Header(t) | BlockType::Footer(t) | BlockType::TextBlock(t) => {
+            t.text.trim().to_string()
+        }
+        BlockType::Title(title) => title.text.trim().to_string(),
+        BlockType::ListBlock(list) => list.items.join("\n"),
+        BlockType::Image(image) => image.caption.clone().unwrap_or_default(),
+        BlockType::Table(table) => table
+            .rows
+            .iter()
+            .map(|row| row.join(" | "))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        BlockType::Annotation(annotation) => annotation.text.clone().unwrap_or_default(),
+    }
+}
+
+/// Splits `text` on sentence-ending punctuation followed by whitespace (or end of string).
+/// Only used as a last resort for a single block that alone exceeds the token budget.
+fn split_sentences(text: &str) -> Vec<String> {
+    let bytes = text.as_bytes();
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        if matches!(c, '.' | '!' | '?') {
+            let end = i + c.len_utf8();
+            if end >= bytes.len() || bytes[end].is_ascii_whitespace() {
+                let sentence = text[start..end].trim();
+                if !sentence.is_empty() {
+                    sentences.push(sentence.to_string());
+                }
+                start = end;
+            }
+        }
+    }
+    let tail = text[start..].trim();
+    if !tail.is_empty() {
+        sentences.push(tail.to_string());
+    }
+    sentences
+}
+
+/// Splits a parsed document's merged blocks into retrieval-ready chunks sized to
+/// `config.target_tokens`, with `config.overlap_tokens` of trailing context repeated across
+/// chunk boundaries.
+pub fn chunk_document<T: Tokenizer>(doc: &ParsedDocument, config: &ChunkConfig<T>) -> Vec<Chunk> {
+    let mut chunks = Vec::new();
+    let mut heading_stack: Vec<(TitleLevel, String)> = Vec::new();
+    let mut current = ChunkBuilder::new(Vec::new());
+
+    let start_overlap = |tokenizer: &T, prev: &ChunkBuilder, heading_path: Vec<String>| {
+        let mut builder = ChunkBuilder::new(heading_path);
+        let overlap = tokenizer.last_n_tokens(&prev.text, config.overlap_tokens);
+        if !overlap.is_empty() {
+            builder.text = overlap;
+            builder.page_ids = prev.page_ids.clone();
+            builder.bboxes = prev.bboxes.clone();
+        }
+        builder
+    };
+
+    for block in &doc.blocks {
+        if let BlockType::Title(title) = &block.kind {
+            heading_stack.retain(|(level, _)| *level < title.level);
+            heading_stack.push((title.level, title.text.trim().to_string()));
+        }
+
+        let text = block_text(block);
+        if text.is_empty() {
+            continue;
+        }
+
+        let heading_path: Vec<String> = heading_stack.iter().map(|(_, t)| t.clone()).collect();
+        let block_tokens = config.tokenizer.count(&text);
+
+        if block_tokens > config.target_tokens {
+            if !current.is_empty() {
+                chunks.push(current.finish(&config.tokenizer));
+            }
+            current = ChunkBuilder::new(heading_path.clone());
+            for sentence in split_sentences(&text) {
+                let sentence_tokens = config.tokenizer.count(&sentence);
+                if !current.is_empty()
+                    && config.tokenizer.count(&current.text) + sentence_tokens
+                        > config.target_tokens
+                {
+                    let finished =
+                        std::mem::replace(&mut current, ChunkBuilder::new(heading_path.clone()));
+                    current = start_overlap(&config.tokenizer, &finished, heading_path.clone());
+                    chunks.push(finished.finish(&config.tokenizer));
+                }
+                current.push(&sentence, block);
+            }
+            continue;
+        }
+
+        if !current.is_empty()
+            && config.tokenizer.count(&current.text) + block_tokens > config.target_tokens
+        {
+            let finished = std::mem::replace(&mut current, ChunkBuilder::new(heading_path.clone()));
+            current = start_overlap(&config.tokenizer, &finished, heading_path.clone());
+            chunks.push(finished.finish(&config.tokenizer));
+        }
+        current.push(&text, block);
+    }
+
+    if !current.is_empty() {
+        chunks.push(current.finish(&config.tokenizer));
+    }
+
+    chunks
+}
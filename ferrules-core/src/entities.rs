@@ -6,18 +6,91 @@ use std::{path::PathBuf, time::Duration};
 
 use pdfium_render::prelude::{PdfFontWeight, PdfPageTextChar, PdfRect};
 
-use crate::{blocks::Block, layout::model::LayoutBBox};
+use crate::{
+    agl,
+    blocks::{Block, Features, Footnote},
+    layout::model::LayoutBBox,
+};
 
 pub type PageID = usize;
 pub type ElementID = usize;
 
 const FERRULES_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Configuration for the ligature/glyph-recovery pass applied by [`fix_utf8_corruption`] to
+/// native-extracted text. Layered on top of [`decode_char`]'s per-glyph `/ToUnicode`/AGL
+/// priority chain, which only ever catches corruption on ingestion; this pass additionally
+/// normalizes whole ligature codepoints the font reported correctly, so "e\u{FB00}ective" (a
+/// correctly-decoded U+FB00 `ff` glyph) round-trips to searchable "effective" the same as a
+/// corrupted one does.
+#[derive(Debug, Clone)]
+pub struct LigatureConfig {
+    /// Turns the whole pass off, for documents already known to decode correctly where blind
+    /// substitution could only ever introduce false positives (see [`fix_ligature_corruption`]'s
+    /// "long-context" example).
+    pub enabled: bool,
+    /// Extra single-codepoint -> decomposed-letters mappings, layered on top of the built-in
+    /// [`UNICODE_LIGATURES`] table. For a font that reuses a Private Use Area codepoint as its
+    /// own ligature glyph -- something no standard table can anticipate -- a caller who's
+    /// identified the mapping can teach this pass about it without waiting on a crate release.
+    pub extra_mappings: std::collections::HashMap<char, String>,
+}
+
+impl Default for LigatureConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            extra_mappings: std::collections::HashMap::new(),
+        }
+    }
+}
+
+/// The Unicode Alphabetic Presentation Forms ligatures PDF fonts most commonly embed as a
+/// single glyph, and the ASCII letters they decompose to. A font's `/ToUnicode` CMap (or its
+/// glyph name, resolved via [`agl`]) can correctly report one of these and still leave behind a
+/// single opaque character that most text processing -- search, highlighting, footnote marker
+/// matching -- doesn't expect.
+const UNICODE_LIGATURES: &[(char, &str)] = &[
+    ('\u{FB00}', "ff"),
+    ('\u{FB01}', "fi"),
+    ('\u{FB02}', "fl"),
+    ('\u{FB03}', "ffi"),
+    ('\u{FB04}', "ffl"),
+    ('\u{FB06}', "st"),
+];
+
+/// Replaces known Unicode ligature codepoints (see [`UNICODE_LIGATURES`]) and any
+/// caller-supplied [`LigatureConfig::extra_mappings`] with their decomposed letters.
+fn decompose_ligature_codepoints(text: &str, config: &LigatureConfig) -> String {
+    text.chars()
+        .map(|c| {
+            if let Some(mapped) = config.extra_mappings.get(&c) {
+                mapped.clone()
+            } else if let Some((_, letters)) = UNICODE_LIGATURES.iter().find(|(lig, _)| *lig == c) {
+                letters.to_string()
+            } else {
+                c.to_string()
+            }
+        })
+        .collect()
+}
+
+/// Whether `text` contains a codepoint from one of the three Unicode Private Use Areas (BMP
+/// U+E000-F8FF, and the two supplementary-plane PUAs). A PUA hit almost always means a
+/// subset-embedded font assigned one of its own glyphs a codepoint with no standard meaning;
+/// there's no generic mapping for these (unlike [`UNICODE_LIGATURES`]), so this is only ever a
+/// diagnostic signal -- a caller who identifies what a specific document's PUA codepoints mean
+/// can resolve them via [`LigatureConfig::extra_mappings`].
+fn contains_pua(text: &str) -> bool {
+    text.chars()
+        .any(|c| matches!(c as u32, 0xE000..=0xF8FF | 0xF0000..=0xFFFFD | 0x100000..=0x10FFFD))
+}
+
 /// UTF-8 reconstruction function to fix corrupted mathematical symbols
 ///
 /// PDFium sometimes returns UTF-8 bytes as individual Latin-1 characters.
 /// This function detects and reconstructs proper Unicode from corrupted sequences.
-pub fn fix_utf8_corruption(text: &str) -> String {
+pub fn fix_utf8_corruption(text: &str, ligatures: &LigatureConfig) -> String {
     if text.is_empty() {
         return text.to_string();
     }
@@ -79,8 +152,17 @@ pub fn fix_utf8_corruption(text: &str) -> String {
         i += 1;
     }
 
-    // Fix common ligature corruption patterns, then remove control characters
-    let ligature_fixed = fix_ligature_corruption(&result);
+    if !ligatures.enabled {
+        return remove_control_characters(&result);
+    }
+
+    // Decompose correctly-decoded ligature codepoints first, then fall back to the contextual
+    // garbage-ASCII heuristic, then remove control characters.
+    let decomposed = decompose_ligature_codepoints(&result, ligatures);
+    if contains_pua(&decomposed) {
+        tracing::debug!("text contains unmapped Private Use Area codepoint(s) after ligature recovery; consider LigatureConfig::extra_mappings");
+    }
+    let ligature_fixed = fix_ligature_corruption(&decomposed);
     remove_control_characters(&ligature_fixed)
 }
 
@@ -240,6 +322,104 @@ fn determine_ligature_from_context(prefix: &str, suffix: &str, symbol: &str) ->
     }
 }
 
+/// Codepoints PDFium has been observed emitting in place of a ligature glyph when a
+/// subset-embedded font's `/ToUnicode` CMap is missing or wrong (see [`fix_ligature_corruption`]).
+/// Seeing one of these is itself the corruption signal that triggers glyph-name resolution.
+const SUSPECTED_LIGATURE_CORRUPTION_SYMBOLS: &[char] =
+    &['!', '@', '#', '$', '%', '^', '&', '*', '\'', '"'];
+
+/// Which step in the decode-priority chain ultimately produced a [`CharSpan`]'s text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum UnicodeSource {
+    /// PDFium decoded the glyph through the font's own `/ToUnicode` CMap; the normal, trusted
+    /// case.
+    ToUnicode,
+    /// `/ToUnicode` was missing or landed on a [`SUSPECTED_LIGATURE_CORRUPTION_SYMBOLS`] symbol,
+    /// and the font's own glyph name resolved unambiguously via the Adobe Glyph List.
+    GlyphName,
+    /// Neither of the above was available; [`fix_ligature_corruption`]'s contextual regex guess
+    /// was used as a last resort. Least confident of the three.
+    Heuristic,
+}
+
+impl UnicodeSource {
+    /// Distrust ranking (0 is the most confident source, higher is less confident), for demoting
+    /// a [`CharSpan`]'s source as less-confident glyphs are appended to it.
+    fn rank(self) -> u8 {
+        match self {
+            UnicodeSource::ToUnicode => 0,
+            UnicodeSource::GlyphName => 1,
+            UnicodeSource::Heuristic => 2,
+        }
+    }
+}
+
+/// The font's own account of which glyph a [`CharSpan`] is, independent of whatever text ended
+/// up being used.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct GlyphInfo {
+    /// Glyph name from the font's `/Encoding /Differences` array or embedded `post` table (e.g.
+    /// `"fi"`, `"uni2014"`), when one was available and consulted.
+    pub name: Option<String>,
+    pub source: UnicodeSource,
+}
+
+/// Best-effort accessor for the PDF glyph name backing `char`, for [`GlyphInfo`]/AGL resolution.
+///
+/// `pdfium_render`'s safe API surface currently exposes the decoded `/ToUnicode` codepoint
+/// (`unicode_char`) and font metadata (name, weight, size), but not the font's `/Differences`
+/// array or embedded `post`-table glyph names — that requires raw `FPDFFont_GetGlyphName`-style
+/// access this crate doesn't bind yet. This always returns `None` for now; once that accessor
+/// exists, wiring it in here is the only change needed to light up glyph-name resolution in
+/// [`decode_char`].
+fn pdfium_glyph_name(_char: &PdfPageTextChar) -> Option<String> {
+    None
+}
+
+/// Decodes one glyph following the priority chain: trust `/ToUnicode` unless it looks like
+/// ligature corruption, then try the font's own glyph name via the Adobe Glyph List, and only
+/// fall back to the contextual regex heuristic (applied later, over the accumulated line text by
+/// [`fix_utf8_corruption`]) once both of those are unavailable.
+fn decode_char(char: &PdfPageTextChar, ligatures: &LigatureConfig) -> (String, GlyphInfo) {
+    let raw = char.unicode_char().unwrap_or_default().to_string();
+
+    if !raw
+        .chars()
+        .next()
+        .is_some_and(|c| SUSPECTED_LIGATURE_CORRUPTION_SYMBOLS.contains(&c))
+    {
+        return (
+            fix_utf8_corruption(&raw, ligatures),
+            GlyphInfo {
+                name: None,
+                source: UnicodeSource::ToUnicode,
+            },
+        );
+    }
+
+    if let Some(name) = pdfium_glyph_name(char) {
+        if let Some(resolved) = agl::glyph_name_to_unicode(&name) {
+            return (
+                resolved,
+                GlyphInfo {
+                    name: Some(name),
+                    source: UnicodeSource::GlyphName,
+                },
+            );
+        }
+    }
+
+    // Glyph name unavailable or unresolvable: leave the raw symbol for
+    // `fix_ligature_corruption`'s contextual heuristic to fix up once the whole line is known.
+    (
+        fix_utf8_corruption(&raw, ligatures),
+        GlyphInfo {
+            name: None,
+            source: UnicodeSource::Heuristic,
+        },
+    )
+}
+
 /// Remove control characters while preserving legitimate whitespace
 ///
 /// Removes ASCII control characters (0x00-0x1F) except for common whitespace:
@@ -351,11 +531,47 @@ impl BBox {
         self.intersection(other) / self.union(other)
     }
 
+    /// Smallest axis-aligned box covering both `self` and `other`.
+    #[inline(always)]
+    pub fn union_hull(&self, other: &Self) -> Self {
+        Self {
+            x0: self.x0.min(other.x0),
+            y0: self.y0.min(other.y0),
+            x1: self.x1.max(other.x1),
+            y1: self.y1.max(other.y1),
+        }
+    }
+
     #[inline(always)]
     pub fn intersection(&self, other: &Self) -> f32 {
         self.overlap_x(other) * self.overlap_y(other)
     }
 
+    /// The clipped overlap rectangle between `self` and `other`, or `None` when they don't
+    /// overlap. Unlike [`Self::intersection`], which only reports the overlap *area*, this
+    /// returns the rectangle itself -- e.g. to clip a word box to its containing page/region.
+    #[inline(always)]
+    pub fn intersection_rect(&self, other: &Self) -> Option<Self> {
+        let x0 = self.x0.max(other.x0);
+        let y0 = self.y0.max(other.y0);
+        let x1 = self.x1.min(other.x1);
+        let y1 = self.y1.min(other.y1);
+        if x0 > x1 || y0 > y1 {
+            return None;
+        }
+        Some(Self { x0, y0, x1, y1 })
+    }
+
+    #[inline(always)]
+    pub fn disjoint(&self, other: &Self) -> bool {
+        self.intersection_rect(other).is_none()
+    }
+
+    #[inline(always)]
+    pub fn contains_point(&self, x: f32, y: f32) -> bool {
+        x >= self.x0 && x <= self.x1 && y >= self.y0 && y <= self.y1
+    }
+
     #[inline(always)]
     fn union(&self, other: &Self) -> f32 {
         other.area() + self.area() - self.intersection(other)
@@ -369,8 +585,58 @@ impl BBox {
         (point_a.0 - point_b.0).powi(2) * x_weight + (point_a.1 - point_b.1).powi(2) * y_weight
     }
 
-    fn _rotate(self) -> Self {
-        todo!()
+    /// Applies an arbitrary 2D affine transform `m = [a, b, c, d, e, f]` (PDF matrix convention:
+    /// `x' = a*x + c*y + e`, `y' = b*x + d*y + f`) to this bbox's four corners and returns their
+    /// axis-aligned bounding box. [`Self::rotate`]/[`page_rotation_matrix`] build the matrix for
+    /// a page's declared rotation; a non-axis-aligned `m` still produces a valid (if loose)
+    /// enclosing bbox, rather than a true rotated quadrilateral.
+    pub fn transform(&self, m: &[f32; 6]) -> Self {
+        let [a, b, c, d, e, f] = *m;
+        let corners = [
+            (self.x0, self.y0),
+            (self.x1, self.y0),
+            (self.x0, self.y1),
+            (self.x1, self.y1),
+        ]
+        .map(|(x, y)| (a * x + c * y + e, b * x + d * y + f));
+
+        let (mut x0, mut y0) = (f32::INFINITY, f32::INFINITY);
+        let (mut x1, mut y1) = (f32::NEG_INFINITY, f32::NEG_INFINITY);
+        for (x, y) in corners {
+            x0 = x0.min(x);
+            x1 = x1.max(x);
+            y0 = y0.min(y);
+            y1 = y1.max(y);
+        }
+        Self { x0, y0, x1, y1 }
+    }
+
+    /// Remaps this bbox into the coordinate space of a page rotated `degrees` clockwise
+    /// (snapped to the nearest quarter turn -- the only rotations a PDF page declares) whose
+    /// un-rotated dimensions are `page_width` x `page_height`. For a 90/270 rotation the result
+    /// is expressed with those dimensions swapped, matching the rotated page itself.
+    pub fn rotate(&self, degrees: f32, page_width: f32, page_height: f32) -> Self {
+        self.transform(&page_rotation_matrix(degrees, page_width, page_height))
+    }
+}
+
+/// Snaps `degrees` to the nearest quarter turn and reduces it mod 360, since a PDF page (or a
+/// text run's `get_rotation_clockwise_degrees`) only ever reports 0/90/180/270.
+pub(crate) fn normalize_quarter_turn(degrees: f32) -> u32 {
+    let quarter_turns = (degrees / 90.0).round() as i64;
+    (quarter_turns.rem_euclid(4) * 90) as u32
+}
+
+/// Builds the [`BBox::transform`] matrix that remaps a bbox from a page's raw, un-rotated
+/// coordinate space into the upright space of that page rendered with its declared `/Rotate`
+/// entry (`degrees` clockwise, snapped to the nearest quarter turn), whose un-rotated dimensions
+/// are `page_width` x `page_height`.
+pub(crate) fn page_rotation_matrix(degrees: f32, page_width: f32, page_height: f32) -> [f32; 6] {
+    match normalize_quarter_turn(degrees) {
+        90 => [0.0, 1.0, -1.0, 0.0, page_height, 0.0],
+        180 => [-1.0, 0.0, 0.0, -1.0, page_width, page_height],
+        270 => [0.0, -1.0, 1.0, 0.0, 0.0, page_width],
+        _ => [1.0, 0.0, 0.0, 1.0, 0.0, 0.0],
     }
 }
 
@@ -407,6 +673,44 @@ pub enum ElementType {
     Table,
 }
 
+impl ElementType {
+    /// Variant name as used by the optional (`lua` feature) `classify_element` script hook, both
+    /// for the `kind` argument it receives and for parsing its return value back via
+    /// [`ElementType::from_name`].
+    pub fn name(&self) -> &'static str {
+        match self {
+            ElementType::Header => "Header",
+            ElementType::FootNote => "FootNote",
+            ElementType::Footer => "Footer",
+            ElementType::Text => "Text",
+            ElementType::Title => "Title",
+            ElementType::Subtitle => "Subtitle",
+            ElementType::ListItem => "ListItem",
+            ElementType::Caption => "Caption",
+            ElementType::Image => "Image",
+            ElementType::Table => "Table",
+        }
+    }
+
+    /// Inverse of [`ElementType::name`]. Returns `None` for anything else, so callers (like the
+    /// `classify_element` script hook) can fall back to the original kind on a typo'd name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "Header" => ElementType::Header,
+            "FootNote" => ElementType::FootNote,
+            "Footer" => ElementType::Footer,
+            "Text" => ElementType::Text,
+            "Title" => ElementType::Title,
+            "Subtitle" => ElementType::Subtitle,
+            "ListItem" => ElementType::ListItem,
+            "Caption" => ElementType::Caption,
+            "Image" => ElementType::Image,
+            "Table" => ElementType::Table,
+            _ => return None,
+        })
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Element {
     pub id: ElementID,
@@ -415,6 +719,27 @@ pub struct Element {
     pub kind: ElementType,
     pub page_id: usize,
     pub bbox: BBox,
+    /// Row/column grid reconstructed from the text lines contained in this element's bbox,
+    /// populated only for `ElementType::Table` elements.
+    pub table_rows: Option<Vec<Vec<String>>>,
+    /// Median font size of each [`Line`] pushed into this element, collected so
+    /// [`Element::font_size`] can report the element's overall median. Empty for lines with no
+    /// glyph spans (e.g. OCR-sourced text).
+    pub(crate) line_font_sizes: Vec<f32>,
+    /// Styled text runs accumulated across the lines pushed into this element, in reading
+    /// order. Mirrors `text_block.text` but keeps per-run bold/italic/font metadata alive for
+    /// renderers instead of flattening everything to a plain string.
+    pub spans: Vec<StyledSpan>,
+    /// This element's position in the page's reading order, as computed by
+    /// [`crate::parse::reading_order::column_major_order`]. Exporters should serialize elements
+    /// by this index rather than by detection order.
+    pub order: usize,
+    /// Native-resolution bitmap decoded straight from the page's image XObject, attached by
+    /// [`crate::parse::page::build_page_elements`] when this is an `ElementType::Image` element
+    /// that overlaps one. `None` falls back to cropping the page raster at save time, same as
+    /// before this existed. See [`crate::parse::images`].
+    #[serde(skip_serializing, skip_deserializing)]
+    pub native_image: Option<DynamicImage>,
 }
 
 impl Element {
@@ -441,27 +766,148 @@ impl Element {
             page_id,
             text_block: Default::default(),
             bbox: layout_block.bbox.to_owned(),
+            table_rows: None,
+            line_font_sizes: Vec::new(),
+            spans: Vec::new(),
+            order: 0,
+            native_image: None,
         }
     }
     pub fn push_line(&mut self, line: &Line) {
         // Line text is already cleaned in Line::new_from_span() and Line::append()
         if self.text_block.is_empty() {
             self.text_block.push_first(&line.text);
+            self.spans = line.styled_spans();
         } else {
             self.text_block.append_line(&line.text);
+            let mut new_spans = line.styled_spans();
+            if let Some(first) = new_spans.first_mut() {
+                first.text.insert(0, ' ');
+            }
+            append_collapsing_spans(&mut self.spans, new_spans);
         }
+        if let Some(font_size) = line.font_size() {
+            self.line_font_sizes.push(font_size);
+        }
+    }
+
+    /// Median font size across the lines pushed into this element, or `0.0` if none of them
+    /// carried glyph spans to derive a size from.
+    pub fn font_size(&self) -> f32 {
+        median(&self.line_font_sizes)
     }
 }
 
-#[derive(Debug)]
+/// Middle value of `values` once sorted, or `0.0` for an empty slice. For an even-length slice
+/// this is the lower of the two middle values, which is sufficient for the bucket-ranking use in
+/// [`crate::parse::titles::title_levels_kmeans`].
+fn median(values: &[f32]) -> f32 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    sorted[(sorted.len() - 1) / 2]
+}
+
+/// Appends `new_spans` onto `existing`, merging the boundary run into `existing`'s last span
+/// when both share the same style so a paragraph spanning several lines doesn't fragment into
+/// one span per line.
+pub(crate) fn append_collapsing_spans(
+    existing: &mut Vec<StyledSpan>,
+    mut new_spans: Vec<StyledSpan>,
+) {
+    let same_style = matches!(
+        (existing.last(), new_spans.first()),
+        (Some(last), Some(first))
+            if last.bold == first.bold
+                && last.italic == first.italic
+                && last.font_size == first.font_size
+                && last.font_name == first.font_name
+    );
+    if same_style {
+        let first = new_spans.remove(0);
+        existing.last_mut().unwrap().text.push_str(&first.text);
+    }
+    existing.extend(new_spans);
+}
+
+/// Column bands detected on a page by [`crate::parse::reading_order`]'s gap-based partitioning,
+/// exposed so exporters and downstream merging can reason about true (column-major) reading
+/// order instead of the order elements happened to be detected in.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub struct ColumnLayout {
+    /// Number of columns detected on the page (always at least 1 once any element is present).
+    pub column_count: usize,
+    /// X coordinates of the boundary between each pair of adjacent columns, left to right.
+    /// Has `column_count - 1` entries.
+    pub boundaries: Vec<f32>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct StructuredPage {
     pub id: PageID,
     pub width: f32,
     pub height: f32,
-    // pub rotation: PdfPageRenderRotation,
+    /// Clockwise page rotation in degrees (0/90/180/270), as declared by the PDF page itself.
+    /// See [`BBox::rotate`].
+    pub rotation: f32,
     pub need_ocr: bool,
+    #[serde(skip_serializing, skip_deserializing)]
     pub image: DynamicImage,
     pub elements: Vec<Element>,
+    pub columns: ColumnLayout,
+    /// Markup (highlight/underline/strikeout), link, and free-text annotations extracted from
+    /// the PDF's own annotation list. See [`crate::parse::annotations::parse_page_annotations`].
+    pub annotations: Vec<Annotation>,
+}
+
+/// What kind of PDF annotation an [`Annotation`] represents, carrying whatever's specific to
+/// that kind -- a link's resolved target, a note's own text.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub enum AnnotationKind {
+    /// A marked-up run of text, as drawn by the reader (no resolvable target).
+    Highlight,
+    Underline,
+    StrikeOut,
+    /// A clickable region, resolved to either an external URI or an in-document page.
+    Link {
+        uri: Option<String>,
+        dest_page: Option<PageID>,
+    },
+    /// A free-floating sticky note / text annotation, carrying its own contents rather than
+    /// covering existing page text.
+    Note {
+        text: String,
+    },
+}
+
+/// A single PDF annotation, extracted and normalized into this crate's page-coordinate `BBox`
+/// convention by [`crate::parse::annotations::parse_page_annotations`].
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Annotation {
+    pub kind: AnnotationKind,
+    pub bbox: BBox,
+    /// For markup annotations, the text of the `Line`s the annotation's quads overlap; for a
+    /// `Note`, the annotation's own contents. `None` when nothing qualifying was found.
+    pub text: Option<String>,
+    pub page_id: PageID,
+}
+
+/// One node of a PDF's outline (bookmarks) tree, extracted by
+/// [`crate::parse::outline::parse_document_outline`]. Mirrors the tree pdfium itself resolves
+/// from the catalog's outline dictionary and name tree, so a named destination and a direct
+/// page/rect destination both end up as a plain `(page_id, target_bbox)` pair here.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct OutlineEntry {
+    pub title: String,
+    /// Page this entry's destination resolves to, if it has one (some bookmarks only carry an
+    /// action, e.g. a URI, with no in-document target).
+    pub page_id: Option<PageID>,
+    /// The destination's target rect in the page's upright coordinate space, when pdfium
+    /// reports one (a "fit page" destination has a page but no rect).
+    pub target_bbox: Option<BBox>,
+    pub children: Vec<OutlineEntry>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -472,7 +918,8 @@ pub struct Page {
 
     #[serde(skip_serializing, skip_deserializing)]
     pub image: DynamicImage,
-    // pub rotation: PdfPageRenderRotation,
+    /// Clockwise page rotation in degrees (0/90/180/270), as declared by the PDF page itself.
+    pub rotation: f32,
     pub need_ocr: bool,
 }
 
@@ -481,13 +928,30 @@ pub struct DocumentMetadata {
     #[serde(with = "serde_millis")]
     pub parsing_duration: Duration,
     pub ferrules_version: String,
+    /// Execution provider the layout model actually bound to for this parse (e.g.
+    /// `"CoreML(ane_only=false)"` or `"CPU"`), after falling back past any unavailable
+    /// providers in the configured priority order.
+    pub execution_provider: String,
+    /// Number of pages served from the content-addressed page cache instead of being
+    /// re-run through layout inference. Zero when the cache is disabled for this parse.
+    pub page_cache_hits: u64,
+    /// Number of pages that missed the page cache (or had it disabled) and were parsed
+    /// in full.
+    pub page_cache_misses: u64,
 }
 
 impl DocumentMetadata {
-    pub fn new(parsing_duration: Duration) -> Self {
+    pub fn new(
+        parsing_duration: Duration,
+        execution_provider: String,
+        page_cache_stats: crate::cache::PageCacheStats,
+    ) -> Self {
         Self {
             parsing_duration,
             ferrules_version: FERRULES_VERSION.to_owned(),
+            execution_provider,
+            page_cache_hits: page_cache_stats.hits,
+            page_cache_misses: page_cache_stats.misses,
         }
     }
 }
@@ -499,6 +963,13 @@ pub struct ParsedDocument {
     pub blocks: Vec<Block>,
     pub debug_path: Option<PathBuf>,
     pub metadata: DocumentMetadata,
+    pub footnotes: Vec<Footnote>,
+    /// Which structural constructs (images, tables, captions, footnotes) this document
+    /// contains. See [`Features::scan`].
+    pub features: Features,
+    /// The document's table of contents, if it has one. See
+    /// [`crate::parse::outline::parse_document_outline`].
+    pub outline: Vec<OutlineEntry>,
 }
 
 #[derive(Debug)]
@@ -511,26 +982,74 @@ pub struct CharSpan {
     pub font_weight: Option<PdfFontWeight>,
     pub char_start_idx: usize,
     pub char_end_idx: usize,
+    /// How `text` was decoded. `append`ing another glyph demotes this to the least confident of
+    /// the two sources, since a span's [`UnicodeSource`] describes its text as a whole.
+    pub glyph: GlyphInfo,
+}
+
+/// A contiguous run of text sharing one style, collapsed from glyph-level [`CharSpan`]s (or,
+/// for lines with no PDF text layer of their own, a single unstyled run covering the whole
+/// OCR'd line). Downstream renderers use this instead of a flat `String` so bold/italic and
+/// heading sizing survive past the OCR/layout merge boundary.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub struct StyledSpan {
+    pub text: String,
+    pub bold: bool,
+    pub italic: bool,
+    pub font_size: f32,
+    pub font_name: String,
 }
 
 impl CharSpan {
-    pub fn new_from_char(char: &PdfPageTextChar, page_bbox: &BBox) -> Self {
+    /// Heuristic bold/italic detection from the font name (PDF font weight enums vary wildly
+    /// across producers, but "Bold"/"Italic"/"Oblique" substrings in the PostScript name are
+    /// near-universal).
+    fn is_bold(&self) -> bool {
+        self.font_name.to_lowercase().contains("bold")
+    }
+
+    fn is_italic(&self) -> bool {
+        let name = self.font_name.to_lowercase();
+        name.contains("italic") || name.contains("oblique")
+    }
+
+    fn style_key(&self) -> (bool, bool, u32, &str) {
+        (
+            self.is_bold(),
+            self.is_italic(),
+            self.font_size.to_bits(),
+            self.font_name.as_str(),
+        )
+    }
+
+    pub fn new_from_char(
+        char: &PdfPageTextChar,
+        page_bbox: &BBox,
+        ligatures: &LigatureConfig,
+    ) -> Self {
+        let (text, glyph) = decode_char(char, ligatures);
         Self {
             bbox: BBox::from_pdfrect(
                 char.tight_bounds()
                     .expect("Error init span tight bound char"),
                 page_bbox.height(),
             ),
-            text: fix_utf8_corruption(&char.unicode_char().unwrap_or_default().to_string()),
+            text,
             font_name: char.font_name(),
             font_weight: char.font_weight(),
             font_size: char.unscaled_font_size().value,
             rotation: char.get_rotation_clockwise_degrees(),
             char_start_idx: char.index(),
             char_end_idx: char.index(),
+            glyph,
         }
     }
-    pub fn append(&mut self, char: &PdfPageTextChar, page_bbox: &BBox) -> Option<()> {
+    pub fn append(
+        &mut self,
+        char: &PdfPageTextChar,
+        page_bbox: &BBox,
+        ligatures: &LigatureConfig,
+    ) -> Option<()> {
         let char_rotation = char.get_rotation_clockwise_degrees();
         if char.unscaled_font_size().value != self.font_size
             || char.font_name() != self.font_name
@@ -543,16 +1062,31 @@ impl CharSpan {
                 char.loose_bounds().expect("error tight bound"),
                 page_bbox.height(),
             );
-            // Apply full UTF-8 and ligature corruption fix to character before adding
-            let char_text =
-                fix_utf8_corruption(&char.unicode_char().unwrap_or_default().to_string());
+            let (char_text, char_glyph) = decode_char(char, ligatures);
             self.text.push_str(&char_text);
+            if char_glyph.source.rank() > self.glyph.source.rank() {
+                self.glyph = char_glyph;
+            }
             self.char_end_idx = char.index();
             self.bbox.merge(&char_bbox);
             Some(())
         }
     }
 }
+/// Start/end coordinates of `bbox` along the reading axis implied by `rotation` (one of the
+/// quarter turns `CharSpan`/`Line` track via `get_rotation_clockwise_degrees`), for
+/// [`Line::append`]'s linebreak check. Unlike [`BBox::rotate`], this doesn't need the page's
+/// absolute dimensions: it only has to preserve ordering along the reading direction, not
+/// produce true rotated coordinates.
+fn reading_axis_extent(bbox: &BBox, rotation: f32) -> (f32, f32) {
+    match normalize_quarter_turn(rotation) {
+        90 => (bbox.x0, bbox.x1),
+        180 => (-bbox.y1, -bbox.y0),
+        270 => (-bbox.x1, -bbox.x0),
+        _ => (bbox.y0, bbox.y1),
+    }
+}
+
 #[derive(Default)]
 pub struct Line {
     pub text: String,
@@ -595,14 +1129,20 @@ impl Line {
     }
     // TODO: find a better pattern here
     // return Some if we fail to append the span-> not great
-    pub fn append(&mut self, span: CharSpan) -> Result<(), CharSpan> {
+    pub fn append(&mut self, span: CharSpan, ligatures: &LigatureConfig) -> Result<(), CharSpan> {
+        // NOTE: sometimes pdfium doesn't inject a linebreak, so we check the span positions.
+        // Compared along the reading axis implied by the line's own rotation rather than raw
+        // `y0`/`y1`, so a column of text rotated 90/270 degrees breaks into lines along its
+        // actual baseline instead of its (here, mostly meaningless) vertical extent.
+        let (span_reading_start, _) = reading_axis_extent(&span.bbox, self.rotation);
+        let (_, line_reading_end) = reading_axis_extent(&self.bbox, self.rotation);
         if span.rotation != self.rotation
-        // NOTE: sometimes pdfium doesn't inject a linebreak, so we check the span positions
-        || span.bbox.y0 > self.bbox.y1
-        || span.text.ends_with("\n") || span.text.ends_with("\x02")
+            || span_reading_start > line_reading_end
+            || span.text.ends_with("\n")
+            || span.text.ends_with("\x02")
         {
             // First fix UTF-8 corruption, then apply plsfix conservatively
-            let utf8_fixed = fix_utf8_corruption(&self.text);
+            let utf8_fixed = fix_utf8_corruption(&self.text, ligatures);
             // Skip plsfix to prevent over-aggressive ligature corrections like "long-context" → "longficontext"
             // Our fix_ligature_corruption function in fix_utf8_corruption already handles legitimate ligature issues
             self.text = utf8_fixed;
@@ -620,12 +1160,178 @@ impl Line {
             Ok(())
         }
     }
+
+    /// Runs the user-supplied `normalize_text` Lua hook (see [`crate::script`]) over this line's
+    /// text. Applied per glyph span so the hook sees each run's own `font_name`/`font_size`
+    /// rather than the line's as a whole; a line with no spans (OCR output, which carries no
+    /// per-glyph font info) is normalized as a single run with an empty font name and size 0.
+    #[cfg(feature = "lua")]
+    pub fn normalize(&mut self, script: &crate::script::ScriptEngine) {
+        if self.spans.is_empty() {
+            self.text = script.normalize_text(&self.text, "", 0.0);
+            return;
+        }
+
+        for span in &mut self.spans {
+            span.text = script.normalize_text(&span.text, &span.font_name, span.font_size);
+        }
+        self.text = self.spans.iter().map(|s| s.text.as_str()).collect();
+    }
+
+    /// Median font size of this line's glyph spans, or `None` when the line carries no spans
+    /// (e.g. a line produced from OCR rather than the PDF's own text layer).
+    pub fn font_size(&self) -> Option<f32> {
+        if self.spans.is_empty() {
+            return None;
+        }
+        let mut sizes: Vec<f32> = self.spans.iter().map(|s| s.font_size).collect();
+        sizes.sort_by(|a, b| a.total_cmp(b));
+        Some(sizes[sizes.len() / 2])
+    }
+
+    /// Collapses this line's glyph-level [`CharSpan`]s into style runs, merging adjacent spans
+    /// that share the same bold/italic/font. A line with no glyph spans (e.g. OCR output) comes
+    /// back as a single unstyled run covering [`Line::text`].
+    pub fn styled_spans(&self) -> Vec<StyledSpan> {
+        if self.spans.is_empty() {
+            return vec![StyledSpan {
+                text: self.text.clone(),
+                bold: false,
+                italic: false,
+                font_size: 0.0,
+                font_name: String::new(),
+            }];
+        }
+
+        let mut spans: Vec<StyledSpan> = Vec::new();
+        for char_span in &self.spans {
+            let key = char_span.style_key();
+            let same_as_last = spans.last().is_some_and(|last: &StyledSpan| {
+                (
+                    last.bold,
+                    last.italic,
+                    last.font_size.to_bits(),
+                    last.font_name.as_str(),
+                ) == key
+            });
+            if same_as_last {
+                spans.last_mut().unwrap().text.push_str(&char_span.text);
+            } else {
+                spans.push(StyledSpan {
+                    text: char_span.text.clone(),
+                    bold: char_span.is_bold(),
+                    italic: char_span.is_italic(),
+                    font_size: char_span.font_size,
+                    font_name: char_span.font_name.clone(),
+                });
+            }
+        }
+        spans
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn make_char_span(text: &str, font_name: &str, font_size: f32) -> CharSpan {
+        CharSpan {
+            bbox: BBox {
+                x0: 0.0,
+                y0: 0.0,
+                x1: 1.0,
+                y1: 1.0,
+            },
+            text: text.to_owned(),
+            rotation: 0.0,
+            font_name: font_name.to_owned(),
+            font_size,
+            font_weight: None,
+            char_start_idx: 0,
+            char_end_idx: 0,
+            glyph: GlyphInfo {
+                name: None,
+                source: UnicodeSource::ToUnicode,
+            },
+        }
+    }
+
+    #[test]
+    fn styled_spans_collapses_adjacent_same_style_runs() {
+        let line = Line {
+            text: "Hello World!".to_owned(),
+            bbox: BBox {
+                x0: 0.0,
+                y0: 0.0,
+                x1: 1.0,
+                y1: 1.0,
+            },
+            rotation: 0.0,
+            spans: vec![
+                make_char_span("Hello ", "Arial", 12.0),
+                make_char_span("World", "Arial", 12.0),
+                make_char_span("!", "Arial-Bold", 12.0),
+            ],
+        };
+
+        let spans = line.styled_spans();
+
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans[0].text, "Hello World");
+        assert!(!spans[0].bold);
+        assert_eq!(spans[1].text, "!");
+        assert!(spans[1].bold);
+    }
+
+    #[test]
+    fn styled_spans_falls_back_to_plain_text_without_glyph_spans() {
+        let line = Line {
+            text: "OCR text".to_owned(),
+            bbox: BBox {
+                x0: 0.0,
+                y0: 0.0,
+                x1: 1.0,
+                y1: 1.0,
+            },
+            rotation: 0.0,
+            spans: vec![],
+        };
+
+        assert_eq!(
+            line.styled_spans(),
+            vec![StyledSpan {
+                text: "OCR text".to_owned(),
+                bold: false,
+                italic: false,
+                font_size: 0.0,
+                font_name: String::new(),
+            }]
+        );
+    }
+
+    #[test]
+    fn append_collapsing_spans_merges_matching_boundary_style() {
+        let mut existing = vec![StyledSpan {
+            text: "foo".to_owned(),
+            bold: true,
+            italic: false,
+            font_size: 12.0,
+            font_name: "Arial-Bold".to_owned(),
+        }];
+        let incoming = vec![StyledSpan {
+            text: " bar".to_owned(),
+            bold: true,
+            italic: false,
+            font_size: 12.0,
+            font_name: "Arial-Bold".to_owned(),
+        }];
+
+        append_collapsing_spans(&mut existing, incoming);
+
+        assert_eq!(existing.len(), 1);
+        assert_eq!(existing[0].text, "foo bar");
+    }
+
     #[test]
     fn test_intersection() {
         let bbox1 = BBox {
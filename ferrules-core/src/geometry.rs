@@ -0,0 +1,426 @@
+//! Spatial indexing and set operations over [`BBox`] slices, for layout passes that would
+//! otherwise compare every box against every other one.
+//!
+//! Reading-order reconstruction and caption-to-figure association run a weighted nearest-box
+//! search over every box on a page; done as a pairwise scan this is O(n²) and dominates cost
+//! once a page holds a few hundred boxes. [`BBoxIndex`] bulk-loads a quadtree over a fixed set
+//! of boxes once, then answers [`BBoxIndex::nearest`] and [`BBoxIndex::within_radius`] queries
+//! by pruning subtrees whose bounds can't beat the current best candidate.
+//!
+//! [`nms`] and [`soft_nms`] are generic, [`BBox`]+score suppression primitives for collapsing
+//! duplicate overlapping boxes; [`crate::layout::model`] keeps its own copy specialized to
+//! `LayoutBBox`'s per-class `relaxed_iou`, so nothing in this crate calls these two yet.
+
+use crate::entities::BBox;
+
+/// Boxes are kept in a leaf once a node holds this many or fewer, rather than recursing further.
+const MAX_BUCKET: usize = 8;
+/// Hard cap on recursion depth, in case many boxes share (near-)identical centers and would
+/// otherwise keep splitting into the same quadrant forever.
+const MAX_DEPTH: u32 = 8;
+
+enum Node {
+    Leaf { bounds: BBox, items: Vec<usize> },
+    Branch { bounds: BBox, children: Vec<Node> },
+}
+
+impl Node {
+    fn bounds(&self) -> &BBox {
+        match self {
+            Node::Leaf { bounds, .. } => bounds,
+            Node::Branch { bounds, .. } => bounds,
+        }
+    }
+
+    fn build(entries: &[BBox], indices: Vec<usize>, bounds: BBox, depth: u32) -> Self {
+        if indices.len() <= MAX_BUCKET || depth >= MAX_DEPTH {
+            return Node::Leaf {
+                bounds,
+                items: indices,
+            };
+        }
+
+        let (cx, cy) = bounds.center();
+        let mut quadrants: [Vec<usize>; 4] = [Vec::new(), Vec::new(), Vec::new(), Vec::new()];
+        for idx in indices {
+            let (x, y) = entries[idx].center();
+            let q = match (x >= cx, y >= cy) {
+                (false, false) => 0,
+                (true, false) => 1,
+                (false, true) => 2,
+                (true, true) => 3,
+            };
+            quadrants[q].push(idx);
+        }
+
+        // All entries landed in the same quadrant (e.g. duplicate/near-duplicate boxes) --
+        // splitting further would just recurse into the same quadrant without making progress.
+        if quadrants.iter().filter(|q| !q.is_empty()).count() <= 1 {
+            return Node::Leaf {
+                bounds,
+                items: quadrants.into_iter().flatten().collect(),
+            };
+        }
+
+        let quadrant_bounds = [
+            BBox {
+                x0: bounds.x0,
+                y0: bounds.y0,
+                x1: cx,
+                y1: cy,
+            },
+            BBox {
+                x0: cx,
+                y0: bounds.y0,
+                x1: bounds.x1,
+                y1: cy,
+            },
+            BBox {
+                x0: bounds.x0,
+                y0: cy,
+                x1: cx,
+                y1: bounds.y1,
+            },
+            BBox {
+                x0: cx,
+                y0: cy,
+                x1: bounds.x1,
+                y1: bounds.y1,
+            },
+        ];
+
+        let children = quadrants
+            .into_iter()
+            .zip(quadrant_bounds)
+            .filter(|(q, _)| !q.is_empty())
+            .map(|(q, qb)| Node::build(entries, q, qb, depth + 1))
+            .collect();
+
+        Node::Branch { bounds, children }
+    }
+
+    fn collect_nearest(
+        &self,
+        entries: &[BBox],
+        query: &BBox,
+        k: usize,
+        x_weight: f32,
+        y_weight: f32,
+        best: &mut Vec<(f32, usize)>,
+    ) {
+        if best.len() >= k {
+            let worst = best[best.len() - 1].0;
+            if bounds_min_dist(query, self.bounds(), x_weight, y_weight) > worst {
+                return;
+            }
+        }
+
+        match self {
+            Node::Leaf { items, .. } => {
+                for &idx in items {
+                    let d = query.distance(&entries[idx], x_weight, y_weight);
+                    insert_ranked(best, k, d, idx);
+                }
+            }
+            Node::Branch { children, .. } => {
+                let mut ordered: Vec<&Node> = children.iter().collect();
+                ordered.sort_by(|a, b| {
+                    bounds_min_dist(query, a.bounds(), x_weight, y_weight)
+                        .total_cmp(&bounds_min_dist(query, b.bounds(), x_weight, y_weight))
+                });
+                for child in ordered {
+                    child.collect_nearest(entries, query, k, x_weight, y_weight, best);
+                }
+            }
+        }
+    }
+
+    fn collect_within_radius(
+        &self,
+        entries: &[BBox],
+        query: &BBox,
+        weighted_dist: f32,
+        x_weight: f32,
+        y_weight: f32,
+        out: &mut Vec<(f32, usize)>,
+    ) {
+        if bounds_min_dist(query, self.bounds(), x_weight, y_weight) > weighted_dist {
+            return;
+        }
+
+        match self {
+            Node::Leaf { items, .. } => {
+                for &idx in items {
+                    let d = query.distance(&entries[idx], x_weight, y_weight);
+                    if d <= weighted_dist {
+                        out.push((d, idx));
+                    }
+                }
+            }
+            Node::Branch { children, .. } => {
+                for child in children {
+                    child.collect_within_radius(
+                        entries,
+                        query,
+                        weighted_dist,
+                        x_weight,
+                        y_weight,
+                        out,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Lower bound on the weighted distance from `query`'s center to any point a box contained in
+/// `bounds` could have its own center at, used to prune subtrees that can't possibly beat the
+/// current best candidate(s).
+fn bounds_min_dist(query: &BBox, bounds: &BBox, x_weight: f32, y_weight: f32) -> f32 {
+    let (qx, qy) = query.center();
+    let dx = if qx < bounds.x0 {
+        bounds.x0 - qx
+    } else if qx > bounds.x1 {
+        qx - bounds.x1
+    } else {
+        0.0
+    };
+    let dy = if qy < bounds.y0 {
+        bounds.y0 - qy
+    } else if qy > bounds.y1 {
+        qy - bounds.y1
+    } else {
+        0.0
+    };
+    dx * dx * x_weight + dy * dy * y_weight
+}
+
+fn insert_ranked(best: &mut Vec<(f32, usize)>, k: usize, d: f32, idx: usize) {
+    let pos = best.partition_point(|&(bd, bi)| bd < d || (bd == d && bi < idx));
+    best.insert(pos, (d, idx));
+    best.truncate(k);
+}
+
+fn bounding_box(boxes: &[BBox]) -> BBox {
+    let mut bounds = boxes.first().cloned().unwrap_or_default();
+    for b in &boxes[1.min(boxes.len())..] {
+        bounds.merge(b);
+    }
+    bounds
+}
+
+/// Quadtree over a fixed slice of [`BBox`]es, answering nearest-neighbor and radius queries by
+/// the same weighted distance metric as [`BBox::distance`] without a full pairwise scan.
+///
+/// Built once from a page's boxes; queries return indices into that original slice.
+pub struct BBoxIndex {
+    entries: Vec<BBox>,
+    root: Node,
+}
+
+impl BBoxIndex {
+    pub fn new(boxes: &[BBox]) -> Self {
+        let entries = boxes.to_vec();
+        let bounds = bounding_box(&entries);
+        let root = Node::build(&entries, (0..entries.len()).collect(), bounds, 0);
+        Self { entries, root }
+    }
+
+    /// Returns the indices (into the slice passed to [`Self::new`]) of the `k` boxes closest to
+    /// `query` by the weighted distance metric, nearest first; boxes at equal distance are
+    /// ordered by index.
+    pub fn nearest(&self, query: &BBox, k: usize, x_weight: f32, y_weight: f32) -> Vec<usize> {
+        if k == 0 || self.entries.is_empty() {
+            return Vec::new();
+        }
+        let mut best = Vec::with_capacity(k);
+        self.root
+            .collect_nearest(&self.entries, query, k, x_weight, y_weight, &mut best);
+        best.into_iter().map(|(_, idx)| idx).collect()
+    }
+
+    /// Returns the indices of every box whose weighted distance to `query` is at most
+    /// `weighted_dist`, nearest first.
+    pub fn within_radius(
+        &self,
+        query: &BBox,
+        weighted_dist: f32,
+        x_weight: f32,
+        y_weight: f32,
+    ) -> Vec<usize> {
+        let mut found = Vec::new();
+        self.root.collect_within_radius(
+            &self.entries,
+            query,
+            weighted_dist,
+            x_weight,
+            y_weight,
+            &mut found,
+        );
+        found.sort_by(|a, b| a.0.total_cmp(&b.0));
+        found.into_iter().map(|(_, idx)| idx).collect()
+    }
+}
+
+/// Greedy non-maximum suppression: sorts `boxes` by descending `scores`, keeps the top-scoring
+/// box, and drops every remaining box whose [`BBox::iou`] with a kept box exceeds
+/// `iou_threshold`. Returns the surviving indices, highest score first.
+pub fn nms(boxes: &[BBox], scores: &[f32], iou_threshold: f32) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..boxes.len()).collect();
+    order.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]));
+
+    let mut kept = Vec::new();
+    for idx in order {
+        let suppressed = kept
+            .iter()
+            .any(|&kept_idx: &usize| boxes[idx].iou(&boxes[kept_idx]) > iou_threshold);
+        if !suppressed {
+            kept.push(idx);
+        }
+    }
+    kept
+}
+
+/// Soft-NMS (Gaussian variant): instead of hard-dropping a box that overlaps a higher-scoring
+/// one, decays its score by `score * exp(-iou² / sigma)` for each kept box it overlaps, and
+/// only drops it once the decayed score falls below `score_floor`. This keeps nearby-but-distinct
+/// boxes (e.g. stacked table rows) that plain [`nms`] would eat, at the cost of returning
+/// indices paired with their final decayed score rather than a plain survivor list.
+pub fn soft_nms(boxes: &[BBox], scores: &[f32], sigma: f32, score_floor: f32) -> Vec<(usize, f32)> {
+    let mut remaining: Vec<(usize, f32)> = (0..boxes.len()).map(|i| (i, scores[i])).collect();
+    let mut kept = Vec::new();
+
+    while !remaining.is_empty() {
+        let (best_pos, &(best_idx, best_score)) = remaining
+            .iter()
+            .enumerate()
+            .max_by(|(_, (_, a)), (_, (_, b))| a.total_cmp(b))
+            .expect("remaining is non-empty");
+        remaining.remove(best_pos);
+        kept.push((best_idx, best_score));
+
+        remaining.retain_mut(|(idx, score)| {
+            let iou = boxes[*idx].iou(&boxes[best_idx]);
+            *score *= (-iou * iou / sigma).exp();
+            *score >= score_floor
+        });
+    }
+
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bbox(x0: f32, y0: f32, x1: f32, y1: f32) -> BBox {
+        BBox { x0, y0, x1, y1 }
+    }
+
+    #[test]
+    fn nearest_returns_closest_boxes_by_center_distance() {
+        let boxes = vec![
+            bbox(0.0, 0.0, 1.0, 1.0),
+            bbox(10.0, 10.0, 11.0, 11.0),
+            bbox(0.5, 0.5, 1.5, 1.5),
+        ];
+        let index = BBoxIndex::new(&boxes);
+
+        let nearest = index.nearest(&bbox(0.0, 0.0, 1.0, 1.0), 2, 1.0, 1.0);
+
+        assert_eq!(nearest, vec![0, 2]);
+    }
+
+    #[test]
+    fn nearest_returns_fewer_than_k_when_fewer_boxes_exist() {
+        let boxes = vec![bbox(0.0, 0.0, 1.0, 1.0)];
+        let index = BBoxIndex::new(&boxes);
+
+        let nearest = index.nearest(&bbox(0.0, 0.0, 1.0, 1.0), 5, 1.0, 1.0);
+
+        assert_eq!(nearest, vec![0]);
+    }
+
+    #[test]
+    fn within_radius_excludes_boxes_past_the_weighted_distance() {
+        let boxes = vec![
+            bbox(0.0, 0.0, 1.0, 1.0),
+            bbox(2.0, 0.0, 3.0, 1.0),
+            bbox(100.0, 0.0, 101.0, 1.0),
+        ];
+        let index = BBoxIndex::new(&boxes);
+
+        let found = index.within_radius(&bbox(0.0, 0.0, 1.0, 1.0), 4.5, 1.0, 1.0);
+
+        assert_eq!(found, vec![0, 1]);
+    }
+
+    #[test]
+    fn quadtree_queries_match_brute_force_nearest_across_many_boxes() {
+        let boxes: Vec<BBox> = (0..200)
+            .map(|i| {
+                let x = (i % 20) as f32;
+                let y = (i / 20) as f32;
+                bbox(x, y, x + 1.0, y + 1.0)
+            })
+            .collect();
+        let index = BBoxIndex::new(&boxes);
+        let query = bbox(9.5, 4.5, 10.5, 5.5);
+
+        let mut brute_force: Vec<(f32, usize)> = boxes
+            .iter()
+            .enumerate()
+            .map(|(i, b)| (query.distance(b, 1.0, 1.0), i))
+            .collect();
+        brute_force.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let expected: Vec<usize> = brute_force.into_iter().take(5).map(|(_, i)| i).collect();
+
+        assert_eq!(index.nearest(&query, 5, 1.0, 1.0), expected);
+    }
+
+    #[test]
+    fn nms_drops_lower_scoring_box_that_overlaps_the_winner() {
+        let boxes = vec![bbox(0.0, 0.0, 10.0, 10.0), bbox(1.0, 1.0, 9.0, 9.0)];
+        let scores = vec![0.9, 0.95];
+
+        let kept = nms(&boxes, &scores, 0.3);
+
+        assert_eq!(kept, vec![1]);
+    }
+
+    #[test]
+    fn nms_keeps_non_overlapping_boxes() {
+        let boxes = vec![bbox(0.0, 0.0, 1.0, 1.0), bbox(100.0, 100.0, 101.0, 101.0)];
+        let scores = vec![0.5, 0.4];
+
+        let mut kept = nms(&boxes, &scores, 0.3);
+        kept.sort_unstable();
+
+        assert_eq!(kept, vec![0, 1]);
+    }
+
+    #[test]
+    fn soft_nms_decays_overlapping_box_instead_of_dropping_it() {
+        let boxes = vec![bbox(0.0, 0.0, 10.0, 10.0), bbox(1.0, 1.0, 9.0, 9.0)];
+        let scores = vec![0.9, 0.8];
+
+        let kept = soft_nms(&boxes, &scores, 0.5, 0.0);
+
+        assert_eq!(kept.len(), 2);
+        let (winner_idx, winner_score) = kept[0];
+        assert_eq!(winner_idx, 0);
+        assert_eq!(winner_score, 0.9);
+        let (_, decayed_score) = kept[1];
+        assert!(decayed_score < 0.8);
+    }
+
+    #[test]
+    fn soft_nms_drops_box_once_decayed_below_score_floor() {
+        let boxes = vec![bbox(0.0, 0.0, 10.0, 10.0), bbox(0.0, 0.0, 10.0, 10.0)];
+        let scores = vec![0.9, 0.8];
+
+        let kept = soft_nms(&boxes, &scores, 0.5, 0.79);
+
+        assert_eq!(kept, vec![(0, 0.9)]);
+    }
+}
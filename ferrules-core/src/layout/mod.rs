@@ -1,13 +1,17 @@
-use std::{sync::Arc, time::Instant};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use anyhow::Context;
 use image::DynamicImage;
-use model::{LayoutBBox, ORTLayoutParser};
-use tokio::sync::mpsc::{self, Receiver, Sender};
-use tokio::sync::{oneshot, Semaphore};
+use model::{LayoutBBox, ORTLayoutParser, OrtExecutionProvider};
+use tokio::sync::mpsc::{self, error::TryRecvError, Receiver, Sender};
+use tokio::sync::{oneshot, OwnedSemaphorePermit, RwLock, Semaphore};
+use tokio_util::sync::CancellationToken;
 use tracing::{Instrument, Span};
 
-use crate::entities::PageID;
+use crate::{cancellation::ParseHandle, entities::PageID};
 
 pub mod model;
 
@@ -17,12 +21,38 @@ pub struct Metadata {
     pub(crate) queue_time: Instant,
 }
 
+/// Scheduling priority for a [`ParseLayoutRequest`]. `ParseLayoutQueue` keeps one channel
+/// per priority and always drains a higher one completely before touching a lower one, so a
+/// `Bulk` ingest job queued ahead of an interactive `High` request can't make it wait.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum LayoutPriority {
+    High,
+    #[default]
+    Normal,
+    Bulk,
+}
+
+impl std::fmt::Display for LayoutPriority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LayoutPriority::High => write!(f, "high"),
+            LayoutPriority::Normal => write!(f, "normal"),
+            LayoutPriority::Bulk => write!(f, "bulk"),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct ParseLayoutRequest {
     pub(crate) page_id: PageID,
     pub(crate) page_image: Arc<DynamicImage>,
     pub(crate) downscale_factor: f32,
     pub(crate) metadata: Metadata,
+    /// Checked against the in-flight inference so a single request can be cancelled (e.g. a
+    /// disconnected client) without touching any other page queued behind it, unlike
+    /// [`ParseLayoutQueue::flush`]'s all-or-nothing cancellation.
+    pub(crate) cancellation_token: CancellationToken,
+    pub(crate) priority: LayoutPriority,
 }
 
 #[derive(Debug)]
@@ -41,67 +71,229 @@ enum LayoutQueueMessage {
 
 #[derive(Debug, Clone)]
 pub struct ParseLayoutQueue {
-    queue: Sender<LayoutQueueMessage>,
+    high: Sender<LayoutQueueMessage>,
+    normal: Sender<LayoutQueueMessage>,
+    bulk: Sender<LayoutQueueMessage>,
+    /// Swappable so [`Self::reload_model`] can install a retrained model or a different
+    /// precision/backend live, without tearing down the queue or affecting requests already
+    /// dispatched against the old model.
+    layout_parser: Arc<RwLock<Arc<ORTLayoutParser>>>,
 }
 
 impl ParseLayoutQueue {
-    pub fn new(layout_parser: Arc<ORTLayoutParser>) -> Self {
-        let (queue_sender, queue_receiver) = mpsc::channel(layout_parser.config.intra_threads);
+    /// `max_concurrent_reqs` bounds both each per-priority inbound channel and the number of
+    /// layout inference batches allowed in flight at once, decoupling this from
+    /// `ORTConfig::intra_threads` so callers can tune queue depth/parallelism independently
+    /// of the ONNX Runtime session's own thread pool sizing.
+    pub fn new(layout_parser: Arc<ORTLayoutParser>, max_concurrent_reqs: usize) -> Self {
+        let layout_parser = Arc::new(RwLock::new(layout_parser));
+        let (high_tx, high_rx) = mpsc::channel(max_concurrent_reqs);
+        let (normal_tx, normal_rx) = mpsc::channel(max_concurrent_reqs);
+        let (bulk_tx, bulk_rx) = mpsc::channel(max_concurrent_reqs);
 
-        tokio::task::spawn(start_layout_parser(layout_parser, queue_receiver));
+        tokio::task::spawn(start_layout_parser(
+            layout_parser.clone(),
+            high_rx,
+            normal_rx,
+            bulk_rx,
+            max_concurrent_reqs,
+        ));
         Self {
-            queue: queue_sender,
+            high: high_tx,
+            normal: normal_tx,
+            bulk: bulk_tx,
+            layout_parser,
         }
     }
 
-    pub(crate) async fn push(&self, req: ParseLayoutRequest) -> anyhow::Result<()> {
-        let span = Span::current();
-        self.queue
+    /// Atomically installs `new_parser` for every layout request dispatched after this call
+    /// returns. Requests already picked up by the dispatcher keep running against the
+    /// `Arc<ORTLayoutParser>` they were dispatched with.
+    pub async fn reload_model(&self, new_parser: Arc<ORTLayoutParser>) {
+        *self.layout_parser.write().await = new_parser;
+    }
+
+    /// Queues `req` at its own [`LayoutPriority`].
+    pub(crate) async fn push(&self, req: ParseLayoutRequest) -> anyhow::Result<ParseHandle> {
+        let priority = req.priority;
+        self.push_with_priority(req, priority).await
+    }
+
+    /// Queues `req` at `priority`, overriding whatever priority it was built with.
+    pub(crate) async fn push_with_priority(
+        &self,
+        mut req: ParseLayoutRequest,
+        priority: LayoutPriority,
+    ) -> anyhow::Result<ParseHandle> {
+        req.priority = priority;
+        let span = tracing::debug_span!(parent: Span::current(), "layout_queue_request", priority = %priority);
+        let handle = ParseHandle::new(req.cancellation_token.clone());
+        let sender = match priority {
+            LayoutPriority::High => &self.high,
+            LayoutPriority::Normal => &self.normal,
+            LayoutPriority::Bulk => &self.bulk,
+        };
+        sender
             .send(LayoutQueueMessage::Request(req, span))
             .await
-            .context("error sending parse req")
+            .context("error sending parse req")?;
+        Ok(handle)
     }
 
     pub(crate) async fn flush(&self) -> anyhow::Result<()> {
-        self.queue
-            .send(LayoutQueueMessage::Flush)
+        for sender in [&self.high, &self.normal, &self.bulk] {
+            sender
+                .send(LayoutQueueMessage::Flush)
+                .await
+                .context("error sending flush command")?;
+        }
+        Ok(())
+    }
+
+    /// The execution provider the currently installed layout model actually bound to, for
+    /// surfacing in [`crate::entities::DocumentMetadata`].
+    pub(crate) async fn selected_execution_provider(&self) -> OrtExecutionProvider {
+        self.layout_parser
+            .read()
             .await
-            .context("error sending flush command")
+            .selected_execution_provider()
+            .clone()
+    }
+}
+
+/// Upper bound on how many pending requests are stacked into a single
+/// [`ORTLayoutParser::parse_layout_batch`] call.
+const MAX_BATCH: usize = 8;
+/// How long to wait for one more request to join a batch once the first is in hand, before
+/// giving up and running inference on whatever was collected. Keeps latency bounded under
+/// light load instead of waiting the full `MAX_BATCH` out.
+const BATCH_DRAIN_TIMEOUT: Duration = Duration::from_millis(5);
+
+/// Pulls the next message out of whichever of the three priority channels has one, always
+/// preferring `high` over `normal` over `bulk` when more than one is ready.
+fn try_recv_any(
+    high_rx: &mut Receiver<LayoutQueueMessage>,
+    normal_rx: &mut Receiver<LayoutQueueMessage>,
+    bulk_rx: &mut Receiver<LayoutQueueMessage>,
+) -> Result<LayoutQueueMessage, TryRecvError> {
+    match high_rx.try_recv() {
+        Err(TryRecvError::Empty) => {}
+        result => return result,
+    }
+    match normal_rx.try_recv() {
+        Err(TryRecvError::Empty) => {}
+        result => return result,
+    }
+    bulk_rx.try_recv()
+}
+
+/// Waits for the next message across all three priority channels. `biased` polls `high`
+/// first, then `normal`, then `bulk`, so when more than one channel has a message ready at
+/// the time they're polled, the higher-priority one wins.
+async fn recv_any(
+    high_rx: &mut Receiver<LayoutQueueMessage>,
+    normal_rx: &mut Receiver<LayoutQueueMessage>,
+    bulk_rx: &mut Receiver<LayoutQueueMessage>,
+) -> Option<LayoutQueueMessage> {
+    tokio::select! {
+        biased;
+        message = high_rx.recv() => message,
+        message = normal_rx.recv() => message,
+        message = bulk_rx.recv() => message,
     }
 }
 
 async fn start_layout_parser(
-    layout_parser: Arc<ORTLayoutParser>,
-    mut input_rx: Receiver<LayoutQueueMessage>,
+    layout_parser: Arc<RwLock<Arc<ORTLayoutParser>>>,
+    mut high_rx: Receiver<LayoutQueueMessage>,
+    mut normal_rx: Receiver<LayoutQueueMessage>,
+    mut bulk_rx: Receiver<LayoutQueueMessage>,
+    max_concurrent_reqs: usize,
 ) {
-    let s = Arc::new(Semaphore::new(layout_parser.config.intra_threads));
-    while let Some(message) = input_rx.recv().await {
+    let s = Arc::new(Semaphore::new(max_concurrent_reqs));
+    // A message pulled out of the channels while draining a batch that couldn't join it
+    // (different downscale factor, or a `Flush`) — handled by the next loop iteration
+    // instead of being dropped, since `Receiver` has no way to push a message back.
+    let mut pending: Option<LayoutQueueMessage> = None;
+
+    loop {
+        let message = match pending.take() {
+            Some(message) => message,
+            None => match recv_any(&mut high_rx, &mut normal_rx, &mut bulk_rx).await {
+                Some(message) => message,
+                None => break,
+            },
+        };
+
         match message {
-            LayoutQueueMessage::Request(req, span) => {
-                let queue_time = req.metadata.queue_time.elapsed().as_millis();
-                let page_id = req.page_id;
+            LayoutQueueMessage::Request(first_req, first_span) => {
+                let downscale_factor = first_req.downscale_factor;
+                let queue_time = first_req.metadata.queue_time.elapsed().as_millis();
                 tracing::debug!(
-                    "layout request queue time for page {page_id} took: {queue_time}ms"
-                );
-                let _guard = span.enter();
-                tokio::spawn(
-                    handle_request(s.clone(), layout_parser.clone(), req, queue_time)
-                        .in_current_span(),
+                    priority = %first_req.priority,
+                    "layout request queue time for page {} took: {queue_time}ms",
+                    first_req.page_id
                 );
+                let mut batch = vec![(first_req, first_span, queue_time)];
+
+                while batch.len() < MAX_BATCH {
+                    let next = match try_recv_any(&mut high_rx, &mut normal_rx, &mut bulk_rx) {
+                        Ok(message) => Some(message),
+                        Err(TryRecvError::Empty) => {
+                            match tokio::time::timeout(
+                                BATCH_DRAIN_TIMEOUT,
+                                recv_any(&mut high_rx, &mut normal_rx, &mut bulk_rx),
+                            )
+                            .await
+                            {
+                                Ok(message) => message,
+                                Err(_timed_out) => None,
+                            }
+                        }
+                        Err(TryRecvError::Disconnected) => None,
+                    };
+
+                    match next {
+                        Some(LayoutQueueMessage::Request(req, span))
+                            if req.downscale_factor == downscale_factor =>
+                        {
+                            let queue_time = req.metadata.queue_time.elapsed().as_millis();
+                            batch.push((req, span, queue_time));
+                        }
+                        Some(other) => {
+                            pending = Some(other);
+                            break;
+                        }
+                        None => break,
+                    }
+                }
+
+                // Acquired here, before spawning, so the dispatcher itself stalls on a full
+                // inference pipeline instead of spawning an unbounded pile of tasks that then
+                // queue up on the semaphore — this is what gives `push` real backpressure.
+                let permit = s.clone().acquire_owned().await.unwrap();
+                // Snapshot the currently installed model for this batch. A `reload_model`
+                // call racing with this read only ever affects batches dispatched after it.
+                let parser = layout_parser.read().await.clone();
+                let batch_span = tracing::debug_span!("layout_batch", batch_size = batch.len());
+                tokio::spawn(handle_batch(permit, parser, batch).instrument(batch_span));
             }
             LayoutQueueMessage::Flush => {
                 tracing::info!("Flushing layout queue - draining all pending requests");
-                // Drain all remaining messages from the queue
-                while let Ok(message) = input_rx.try_recv() {
-                    match message {
-                        LayoutQueueMessage::Request(req, _span) => {
-                            // Send error response to indicate cancellation
-                            let _ = req.metadata.response_tx.send(Err(anyhow::anyhow!(
-                                "Layout processing cancelled due to document cancellation"
-                            )));
-                        }
-                        LayoutQueueMessage::Flush => {
-                            // Multiple flush commands, ignore additional ones
+                // `flush` broadcasts to all three priority channels, so drain every one of
+                // them here regardless of which channel this particular message came from.
+                for rx in [&mut high_rx, &mut normal_rx, &mut bulk_rx] {
+                    while let Ok(message) = rx.try_recv() {
+                        match message {
+                            LayoutQueueMessage::Request(req, _span) => {
+                                // Send error response to indicate cancellation
+                                let _ = req.metadata.response_tx.send(Err(anyhow::anyhow!(
+                                    "Layout processing cancelled due to document cancellation"
+                                )));
+                            }
+                            LayoutQueueMessage::Flush => {
+                                // Multiple flush commands, ignore additional ones
+                            }
                         }
                     }
                 }
@@ -111,40 +303,118 @@ async fn start_layout_parser(
     }
 }
 
-async fn handle_request(
-    s: Arc<Semaphore>,
+/// Runs a single batched layout inference call for every still-live request in `batch`,
+/// preserving each request's identity (page id, metadata, queue time) when scattering the
+/// per-page results back to their individual `response_tx`.
+async fn handle_batch(
+    _permit: OwnedSemaphorePermit,
     parser: Arc<ORTLayoutParser>,
-    req: ParseLayoutRequest,
-    layout_queue_time_ms: u128,
+    batch: Vec<(ParseLayoutRequest, Span, u128)>,
 ) {
-    let _permit = s.acquire().await.unwrap();
+    let mut live = Vec::with_capacity(batch.len());
+    for (req, span, queue_time_ms) in batch {
+        let _guard = span.enter();
+        if req.cancellation_token.is_cancelled() {
+            tracing::debug!(
+                "Layout request for page {} cancelled before inference started",
+                req.page_id
+            );
+            let _ = req
+                .metadata
+                .response_tx
+                .send(Err(anyhow::anyhow!("Layout request cancelled")));
+            continue;
+        }
+        if req.metadata.response_tx.is_closed() {
+            // The caller already dropped its receiver (aborted request, closed
+            // connection, ...) - nobody will read the result, so skip it rather than
+            // spend an inference slot on output that's thrown away.
+            tracing::debug!(
+                "Layout request for page {} has no receiver left, skipping",
+                req.page_id
+            );
+            continue;
+        }
+        drop(_guard);
+        live.push((req, span, queue_time_ms));
+    }
+
+    if live.is_empty() {
+        return;
+    }
 
-    let ParseLayoutRequest {
-        page_id,
-        page_image,
-        downscale_factor,
-        metadata,
-    } = req;
+    let page_images: Vec<Arc<DynamicImage>> = live
+        .iter()
+        .map(|(req, _, _)| req.page_image.clone())
+        .collect();
+    let page_refs: Vec<&DynamicImage> = page_images.iter().map(AsRef::as_ref).collect();
+    let rescale_factors: Vec<f32> = live
+        .iter()
+        .map(|(req, _, _)| req.downscale_factor)
+        .collect();
 
     let start = Instant::now();
-    let layout_result = parser
-        .parse_layout_async(&page_image, downscale_factor)
-        .await;
-    let inference_duration = start.elapsed().as_millis();
+    // Races inference against every caller in the batch disappearing, so a long-running
+    // call is abandoned instead of run to completion for an audience of nobody. A single
+    // straggler keeps the batch alive, since the other callers still want their result.
+    let abandoned = async {
+        if let [(req, _, _)] = live.as_slice() {
+            req.metadata.response_tx.closed().await;
+        } else {
+            while !live
+                .iter()
+                .all(|(req, _, _)| req.metadata.response_tx.is_closed())
+            {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        }
+    };
+    let batch_result = tokio::select! {
+        result = parser.parse_layout_batch(&page_refs, &rescale_factors) => result,
+        _ = abandoned => {
+            drop(_permit);
+            tracing::debug!(
+                "Abandoning layout batch of {} page(s) - every caller disconnected",
+                live.len()
+            );
+            return;
+        }
+    };
+    let inference_elapsed = start.elapsed();
+    let inference_duration = inference_elapsed.as_millis();
     drop(_permit);
-    tracing::debug!("layout inference time for page {page_id} took: {inference_duration} ms");
-
-    let layout_result = layout_result.map(|l| ParseLayoutResponse {
-        page_id,
-        layout_bbox: l,
-        layout_parse_duration_ms: inference_duration,
-        layout_queue_time_ms,
-    });
-    // Handle the case where the receiver is dropped (due to cancellation)
-    if let Err(_) = metadata.response_tx.send(layout_result) {
-        tracing::debug!(
-            "Layout parsing result receiver dropped (likely due to cancellation) for page {}",
-            page_id
-        );
+    crate::metrics::record_stage_latency("layout", inference_elapsed);
+
+    match batch_result {
+        Ok(per_page_bboxes) => {
+            for ((req, span, queue_time_ms), layout_bbox) in live.into_iter().zip(per_page_bboxes) {
+                let _guard = span.enter();
+                tracing::debug!(
+                    "layout inference time for page {} took: {inference_duration} ms (batched)",
+                    req.page_id
+                );
+                let response = ParseLayoutResponse {
+                    page_id: req.page_id,
+                    layout_bbox,
+                    layout_parse_duration_ms: inference_duration,
+                    layout_queue_time_ms: queue_time_ms,
+                };
+                if req.metadata.response_tx.send(Ok(response)).is_err() {
+                    tracing::debug!(
+                        "Layout parsing result receiver dropped (likely due to cancellation) for page {}",
+                        req.page_id
+                    );
+                }
+            }
+        }
+        Err(err) => {
+            let message = err.to_string();
+            for (req, span, _) in live {
+                let _guard = span.enter();
+                let _ = req.metadata.response_tx.send(Err(anyhow::anyhow!(
+                    "layout batch inference failed: {message}"
+                )));
+            }
+        }
     }
 }
@@ -1,3 +1,5 @@
+use std::{cmp::Ordering, collections::BinaryHeap};
+
 use anyhow::Context;
 use image::{imageops::FilterType, DynamicImage, GenericImageView};
 use lazy_static::lazy_static;
@@ -5,7 +7,8 @@ use ndarray::{s, Array4, ArrayBase, Axis, Dim, OwnedRepr};
 use ort::{
     execution_providers::{
         CPUExecutionProvider, CUDAExecutionProvider, CoreMLExecutionProvider,
-        TensorRTExecutionProvider,
+        DirectMLExecutionProvider, ExecutionProvider, TensorRTExecutionProvider,
+        WebGPUExecutionProvider,
     },
     session::{builder::GraphOptimizationLevel, Session},
 };
@@ -13,20 +16,102 @@ use ort::{
 use crate::entities::BBox;
 
 pub const LAYOUT_MODEL_BYTES: &[u8] = include_bytes!("../../../models/yolov8s-doclaynet.onnx");
+/// Int8-quantized variant of [`LAYOUT_MODEL_BYTES`]. Smaller and faster on
+/// edge/embedded deployments, at the cost of some detection accuracy since
+/// quantization shifts the model's score distribution.
+pub const LAYOUT_MODEL_BYTES_INT8: &[u8] =
+    include_bytes!("../../../models/yolov8s-doclaynet-int8.onnx");
+
+/// Selects which precision of the layout model is loaded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ModelPrecision {
+    #[default]
+    Fp32,
+    /// Int8-quantized model: smaller memory footprint and faster inference,
+    /// with a lower and noisier confidence score distribution.
+    Int8,
+}
 
 #[derive(Debug, Clone)]
 pub struct ORTConfig {
     pub execution_providers: Vec<OrtExecutionProvider>,
     pub intra_threads: usize,
     pub inter_threads: usize,
+    /// Number of highest-probability candidates retained before running NMS.
+    /// Bounds the O(n^2) NMS cost on dense pages with thousands of weak detections.
+    pub nms_top_k: usize,
+    /// Number of highest-probability boxes kept after NMS. `None` keeps all of them.
+    pub keep_top_k: Option<usize>,
+    /// Discrete-pixel epsilon subtracted from decoded box max corners (and from the
+    /// clipping bound) to match a half-open pixel convention instead of an inclusive
+    /// one. `0.0` (the default) reproduces the original inclusive-corner behavior.
+    pub pixel_offset: f32,
+    /// Which precision of the layout model to load.
+    pub precision: ModelPrecision,
+    /// Overrides [`ORTLayoutParser::CONF_THRESHOLD`] for this parser. Useful with
+    /// [`ModelPrecision::Int8`], whose quantized outputs have a shifted score
+    /// distribution and usually need a lower threshold. `None` uses the default
+    /// for the configured `precision`.
+    pub conf_threshold: Option<f32>,
+    /// Non-maximum-suppression strategy applied after layout inference.
+    /// Defaults to [`NmsMode::Hard`], unchanged from the original behavior.
+    pub nms_mode: NmsMode,
 }
 
+/// GPU execution providers are ordered ahead of `CPU` (the fallback), so sorting a
+/// `Vec<OrtExecutionProvider>` by priority before session construction always tries
+/// GPU acceleration first when more than one provider is configured.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum OrtExecutionProvider {
-    CPU,
-    CoreML { ane_only: bool },
+    CoreML {
+        ane_only: bool,
+    },
     CUDA(i32),
     Trt(i32),
+    /// DirectML, for GPU acceleration on Windows across AMD/Intel/NVIDIA hardware.
+    DirectML(i32),
+    /// Portable WebGPU backend, for cross-platform GPU acceleration.
+    WebGpu,
+    CPU,
+}
+
+impl std::fmt::Display for OrtExecutionProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OrtExecutionProvider::CoreML { ane_only } => {
+                write!(f, "CoreML(ane_only={ane_only})")
+            }
+            OrtExecutionProvider::CUDA(device_id) => write!(f, "CUDA({device_id})"),
+            OrtExecutionProvider::Trt(device_id) => write!(f, "TensorRT({device_id})"),
+            OrtExecutionProvider::DirectML(device_id) => write!(f, "DirectML({device_id})"),
+            OrtExecutionProvider::WebGpu => write!(f, "WebGPU"),
+            OrtExecutionProvider::CPU => write!(f, "CPU"),
+        }
+    }
+}
+
+/// Checks whether `provider`'s backing runtime is actually usable on this machine, so the
+/// fallback chain can skip straight past providers whose hardware/drivers aren't present
+/// instead of discovering that only once session construction fails deep inside `ort`.
+pub(crate) fn provider_is_available(provider: &OrtExecutionProvider) -> bool {
+    match provider {
+        OrtExecutionProvider::Trt(_) => TensorRTExecutionProvider::default()
+            .is_available()
+            .unwrap_or(false),
+        OrtExecutionProvider::CUDA(_) => CUDAExecutionProvider::default()
+            .is_available()
+            .unwrap_or(false),
+        OrtExecutionProvider::CoreML { .. } => CoreMLExecutionProvider::default()
+            .is_available()
+            .unwrap_or(false),
+        OrtExecutionProvider::DirectML(_) => DirectMLExecutionProvider::default()
+            .is_available()
+            .unwrap_or(false),
+        OrtExecutionProvider::WebGpu => WebGPUExecutionProvider::default()
+            .is_available()
+            .unwrap_or(false),
+        OrtExecutionProvider::CPU => true,
+    }
 }
 
 impl Default for ORTConfig {
@@ -35,10 +120,19 @@ impl Default for ORTConfig {
         if cfg!(target_os = "macos") {
             execution_providers.push(OrtExecutionProvider::CoreML { ane_only: false });
         }
+        if cfg!(target_os = "windows") {
+            execution_providers.push(OrtExecutionProvider::DirectML(0));
+        }
         Self {
             execution_providers,
             intra_threads: ORTLayoutParser::ORT_INTRATHREAD,
             inter_threads: ORTLayoutParser::ORT_INTERTHREAD,
+            nms_top_k: ORTLayoutParser::DEFAULT_NMS_TOP_K,
+            keep_top_k: None,
+            pixel_offset: 0.0,
+            precision: ModelPrecision::default(),
+            conf_threshold: None,
+            nms_mode: NmsMode::default(),
         }
     }
 }
@@ -67,6 +161,34 @@ pub struct LayoutBBox {
     pub proba: f32,
 }
 
+/// Wraps a [`LayoutBBox`] with an `Ord` impl reversed on `proba`, so a
+/// [`BinaryHeap`] of these acts as a bounded min-heap for `nms_top_k` pruning.
+struct TopKCandidate(LayoutBBox);
+
+impl PartialEq for TopKCandidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.proba == other.0.proba
+    }
+}
+
+impl Eq for TopKCandidate {}
+
+impl PartialOrd for TopKCandidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TopKCandidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .0
+            .proba
+            .partial_cmp(&self.0.proba)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
 impl LayoutBBox {
     pub fn is_text_block(&self) -> bool {
         self.label == "Text"
@@ -85,6 +207,8 @@ impl LayoutBBox {
 pub struct ORTLayoutParser {
     session: Session,
     output_name: String,
+    config: ORTConfig,
+    selected_execution_provider: OrtExecutionProvider,
 }
 
 impl ORTLayoutParser {
@@ -99,7 +223,8 @@ impl ORTLayoutParser {
         let output_tensor = self.run_async(input).await?;
         let mut bboxes =
             self.extract_bboxes(output_tensor, img_width, img_height, bbox_rescale_factor);
-        nms(&mut bboxes, Self::IOU_THRESHOLD);
+        self.apply_nms(&mut bboxes);
+        self.apply_keep_top_k(&mut bboxes);
         Ok(bboxes)
     }
 
@@ -107,6 +232,7 @@ impl ORTLayoutParser {
         &self,
         input: ArrayBase<OwnedRepr<f32>, Dim<[usize; 4]>>,
     ) -> anyhow::Result<ArrayBase<OwnedRepr<f32>, Dim<[usize; 3]>>> {
+        let batch_size = input.shape()[0];
         let outputs = &self.session.run_async(ort::inputs![input]?)?.await?;
 
         let output_tensor = outputs
@@ -115,12 +241,49 @@ impl ORTLayoutParser {
             .try_extract_tensor::<f32>()?;
 
         let output_tensor = output_tensor
-            .to_shape(Self::OUTPUT_SIZE)
+            .to_shape(Self::output_size(batch_size))
             .unwrap()
             .to_owned();
 
         Ok(output_tensor)
     }
+
+    /// Runs layout inference on a batch of pages in a single ONNX session call,
+    /// amortizing session overhead across the pages of a document.
+    #[tracing::instrument(skip_all)]
+    pub async fn parse_layout_batch(
+        &self,
+        pages: &[&DynamicImage],
+        rescale_factors: &[f32],
+    ) -> anyhow::Result<Vec<Vec<LayoutBBox>>> {
+        anyhow::ensure!(
+            pages.len() == rescale_factors.len(),
+            "pages and rescale_factors must have the same length"
+        );
+        if pages.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let dimensions: Vec<(u32, u32)> = pages.iter().map(|p| p.dimensions()).collect();
+        let input = self.preprocess_batch(pages);
+        let output = self.run_async(input).await?;
+
+        let mut results = Vec::with_capacity(pages.len());
+        for (idx, ((img_width, img_height), rescale_factor)) in dimensions
+            .into_iter()
+            .zip(rescale_factors.iter())
+            .enumerate()
+        {
+            let page_output = output.slice(s![idx, .., ..]).to_owned();
+            let mut bboxes =
+                self.extract_bboxes_slice(page_output, img_width, img_height, *rescale_factor);
+            self.apply_nms(&mut bboxes);
+            self.apply_keep_top_k(&mut bboxes);
+            results.push(bboxes);
+        }
+
+        Ok(results)
+    }
 }
 
 impl ORTLayoutParser {
@@ -129,14 +292,30 @@ impl ORTLayoutParser {
     /// Required height of the input image for layout parsing.
     pub const REQUIRED_HEIGHT: u32 = 1024;
 
-    // Output size of the tensor from the ONNX model.
-    // It has dimensions [batch_size = 1, classes + bbox = 15, candidate_boxes = 21504].
-    const OUTPUT_SIZE: [usize; 3] = [1, 15, 21504];
+    // Output size of the tensor from the ONNX model, parameterized over the batch axis.
+    // It has dimensions [batch_size, classes + bbox = 15, candidate_boxes = 21504].
+    const fn output_size(batch_size: usize) -> [usize; 3] {
+        [batch_size, 15, 21504]
+    }
 
-    /// Confidence threshold for filtering out low probability bounding boxes.
+    /// Confidence threshold for filtering out low probability bounding boxes,
+    /// for the [`ModelPrecision::Fp32`] model.
     /// Bounding boxes with probability below this threshold will be ignored.
     pub const CONF_THRESHOLD: f32 = 0.3;
 
+    /// Confidence threshold for the [`ModelPrecision::Int8`] model, which quantizes
+    /// away some score resolution and tends to report lower, noisier probabilities.
+    pub const CONF_THRESHOLD_INT8: f32 = 0.2;
+
+    /// Default confidence threshold for `precision`, unless overridden by
+    /// [`ORTConfig::conf_threshold`].
+    const fn default_conf_threshold(precision: ModelPrecision) -> f32 {
+        match precision {
+            ModelPrecision::Fp32 => Self::CONF_THRESHOLD,
+            ModelPrecision::Int8 => Self::CONF_THRESHOLD_INT8,
+        }
+    }
+
     /// Intersection over Union (IOU) threshold for non-maximum suppression (NMS) algorithm.
     /// It determines the overlap between bounding boxes before suppression.
     pub const IOU_THRESHOLD: f32 = 0.8;
@@ -144,12 +323,39 @@ impl ORTLayoutParser {
     pub const ORT_INTRATHREAD: usize = 16;
     pub const ORT_INTERTHREAD: usize = 4;
 
+    /// Default cap on the number of candidates retained before NMS runs.
+    pub const DEFAULT_NMS_TOP_K: usize = 1000;
+
     pub fn new(config: ORTConfig) -> anyhow::Result<Self> {
+        let stored_config = config.clone();
         let mut execution_providers = Vec::new();
 
-        // Sort providers by priority
+        // Sort providers by priority and always keep CPU as the last entry in the
+        // fallback chain, so session construction succeeds even if the caller forgot
+        // to request it and none of the GPU providers are available on this machine.
         let mut providers = config.execution_providers;
         providers.sort();
+        if !providers.contains(&OrtExecutionProvider::CPU) {
+            providers.push(OrtExecutionProvider::CPU);
+        }
+        tracing::info!(
+            "registering layout model execution providers in priority order: {}",
+            providers
+                .iter()
+                .map(|p| p.to_string())
+                .collect::<Vec<_>>()
+                .join(" -> ")
+        );
+
+        // Probe each provider's actual availability on this machine before committing
+        // to one, so we can log and surface which backend really bound instead of just
+        // which one the caller asked for first.
+        let selected_execution_provider = providers
+            .iter()
+            .find(|provider| provider_is_available(provider))
+            .cloned()
+            .unwrap_or(OrtExecutionProvider::CPU);
+        tracing::info!("layout model execution provider selected: {selected_execution_provider}");
 
         for provider in providers {
             match provider {
@@ -176,6 +382,16 @@ impl ORTLayoutParser {
                     };
                     execution_providers.push(provider)
                 }
+                OrtExecutionProvider::DirectML(device_id) => {
+                    execution_providers.push(
+                        DirectMLExecutionProvider::default()
+                            .with_device_id(device_id)
+                            .build(),
+                    );
+                }
+                OrtExecutionProvider::WebGpu => {
+                    execution_providers.push(WebGPUExecutionProvider::default().build());
+                }
                 OrtExecutionProvider::CPU => {
                     execution_providers.push(CPUExecutionProvider::default().build());
                 }
@@ -187,7 +403,10 @@ impl ORTLayoutParser {
             .with_optimization_level(GraphOptimizationLevel::Level3)?
             .with_intra_threads(config.intra_threads)?
             .with_inter_threads(config.inter_threads)?
-            .commit_from_memory(LAYOUT_MODEL_BYTES)?;
+            .commit_from_memory(match stored_config.precision {
+                ModelPrecision::Fp32 => LAYOUT_MODEL_BYTES,
+                ModelPrecision::Int8 => LAYOUT_MODEL_BYTES_INT8,
+            })?;
 
         let output_name = session
             .outputs
@@ -195,17 +414,28 @@ impl ORTLayoutParser {
             .map(|i| &i.name)
             .context("can't find name output input")?
             .to_owned();
+        tracing::info!("layout model session committed successfully");
 
         Ok(Self {
             session,
             output_name,
+            config: stored_config,
+            selected_execution_provider,
         })
     }
 
+    /// The execution provider that actually bound for this session, after probing the
+    /// configured fallback chain for availability. Falls back to [`OrtExecutionProvider::CPU`]
+    /// when none of the requested providers report as available.
+    pub fn selected_execution_provider(&self) -> &OrtExecutionProvider {
+        &self.selected_execution_provider
+    }
+
     pub fn run(
         &self,
         input: ArrayBase<OwnedRepr<f32>, Dim<[usize; 4]>>,
     ) -> anyhow::Result<ArrayBase<OwnedRepr<f32>, Dim<[usize; 3]>>> {
+        let batch_size = input.shape()[0];
         let outputs = &self.session.run(ort::inputs![input]?)?;
 
         let output_tensor = outputs
@@ -214,7 +444,7 @@ impl ORTLayoutParser {
             .try_extract_tensor::<f32>()?;
 
         let output_tensor = output_tensor
-            .to_shape(Self::OUTPUT_SIZE)
+            .to_shape(Self::output_size(batch_size))
             .unwrap()
             .to_owned();
 
@@ -231,7 +461,8 @@ impl ORTLayoutParser {
         let output_tensor = self.run(input)?;
         let mut bboxes =
             self.extract_bboxes(output_tensor, img_width, img_height, bbox_rescale_factor);
-        nms(&mut bboxes, Self::IOU_THRESHOLD);
+        self.apply_nms(&mut bboxes);
+        self.apply_keep_top_k(&mut bboxes);
 
         Ok(bboxes)
     }
@@ -245,8 +476,22 @@ impl ORTLayoutParser {
         rescale_factor: f32,
     ) -> Vec<LayoutBBox> {
         // Tensor shape: (bs, bbox(4) + classes(15), anchors )
-        let mut result = Vec::new();
-        let output = output.slice(s![0, .., ..]);
+        let output = output.slice(s![0, .., ..]).to_owned();
+        self.extract_bboxes_slice(output, original_width, original_height, rescale_factor)
+    }
+
+    /// Same as [`Self::extract_bboxes`] but operating on a single page already
+    /// sliced out of a batched `[N, 15, 21504]` output tensor.
+    #[tracing::instrument(skip_all)]
+    fn extract_bboxes_slice(
+        &self,
+        output: ArrayBase<OwnedRepr<f32>, Dim<[usize; 2]>>,
+        original_width: u32,
+        original_height: u32,
+        rescale_factor: f32,
+    ) -> Vec<LayoutBBox> {
+        let mut candidates: BinaryHeap<TopKCandidate> =
+            BinaryHeap::with_capacity(self.config.nms_top_k + 1);
         let mut bbox_id = 0;
         for prediction in output.axis_iter(Axis(1)) {
             // Prediction dim: (15,) -> (4 bbox, 11 labels)
@@ -259,7 +504,11 @@ impl ORTLayoutParser {
                 .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
                 .unwrap();
 
-            if proba < Self::CONF_THRESHOLD {
+            let conf_threshold = self
+                .config
+                .conf_threshold
+                .unwrap_or_else(|| Self::default_conf_threshold(self.config.precision));
+            if proba < conf_threshold {
                 continue;
             }
             let label = ID2LABEL[max_prob_idx];
@@ -269,11 +518,18 @@ impl ORTLayoutParser {
             let yc = bbox[1_usize] / ratio;
             let w = bbox[2_usize] / ratio;
             let h = bbox[3_usize] / ratio;
-            // Change to (upper-left, lower-right)
+            // Change to (upper-left, lower-right). `pixel_offset` shifts the max
+            // corners (and their clip bound) to follow a half-open pixel convention;
+            // it defaults to 0.0, which reproduces the original inclusive corners.
+            let offset = self.config.pixel_offset;
             let x0 = (xc - (w / 2.0)).min(original_width as f32).max(0f32);
             let y0 = (yc - (h / 2.0)).min(original_height as f32).max(0f32);
-            let x1 = (xc + (w / 2.0)).max(0f32).min(original_width as f32);
-            let y1 = (yc + (h / 2.0)).max(0f32).min(original_height as f32);
+            let x1 = (xc + (w / 2.0) - offset)
+                .max(0f32)
+                .min(original_width as f32 - offset);
+            let y1 = (yc + (h / 2.0) - offset)
+                .max(0f32)
+                .min(original_height as f32 - offset);
 
             debug_assert!(x0 <= x1 && x1 <= original_width as f32);
             debug_assert!(y0 <= y1 && y1 <= original_height as f32);
@@ -283,7 +539,7 @@ impl ORTLayoutParser {
                 continue;
             }
 
-            result.push(LayoutBBox {
+            candidates.push(TopKCandidate(LayoutBBox {
                 id: bbox_id,
                 bbox: BBox {
                     x0: x0 * rescale_factor,
@@ -293,18 +549,47 @@ impl ORTLayoutParser {
                 },
                 proba,
                 label,
-            });
+            }));
+            // Bounded min-heap: evict the weakest candidate once we exceed nms_top_k,
+            // instead of collecting every candidate and sorting the whole thing later.
+            if candidates.len() > self.config.nms_top_k {
+                candidates.pop();
+            }
             bbox_id += 1;
         }
 
-        result
+        candidates.into_iter().map(|c| c.0).collect()
     }
+    /// Runs NMS on `bboxes` using the strategy configured via [`ORTConfig::nms_mode`].
+    fn apply_nms(&self, bboxes: &mut Vec<LayoutBBox>) {
+        match self.config.nms_mode {
+            NmsMode::Hard => nms(bboxes, Self::IOU_THRESHOLD),
+            mode @ (NmsMode::SoftLinear | NmsMode::SoftGaussian { .. }) => {
+                let conf_threshold = self
+                    .config
+                    .conf_threshold
+                    .unwrap_or_else(|| Self::default_conf_threshold(self.config.precision));
+                soft_nms(bboxes, Self::IOU_THRESHOLD, conf_threshold, mode);
+            }
+        }
+    }
+
+    /// Truncates `bboxes` to the `keep_top_k` highest-probability boxes, if configured.
+    fn apply_keep_top_k(&self, bboxes: &mut Vec<LayoutBBox>) {
+        if let Some(keep_top_k) = self.config.keep_top_k {
+            if bboxes.len() > keep_top_k {
+                bboxes.sort_by(|a, b| b.proba.partial_cmp(&a.proba).unwrap());
+                bboxes.truncate(keep_top_k);
+            }
+        }
+    }
+
     fn scale_wh(&self, w0: f32, h0: f32, w1: f32, h1: f32) -> (f32, f32, f32) {
         let r = (w1 / w0).min(h1 / h0);
         (r, (w0 * r).round(), (h0 * r).round())
     }
 
-    fn _preprocess_batch(&self, batch_imgs: &[DynamicImage]) -> Array4<f32> {
+    fn preprocess_batch(&self, batch_imgs: &[&DynamicImage]) -> Array4<f32> {
         let (w0, h0) = batch_imgs.first().unwrap().dimensions();
         let (_, w_new, h_new) = self.scale_wh(
             w0 as f32,
@@ -366,6 +651,24 @@ impl ORTLayoutParser {
     }
 }
 
+/// Selects which non-maximum-suppression strategy [`nms`]/[`soft_nms`] should apply.
+///
+/// `Hard` is the original all-or-nothing suppression. The `Soft*` variants decay
+/// the probability of overlapping boxes instead of dropping them outright, which
+/// preserves overlapping-but-distinct layout elements (e.g. a caption inside a picture).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NmsMode {
+    Hard,
+    SoftLinear,
+    SoftGaussian { sigma: f32 },
+}
+
+impl Default for NmsMode {
+    fn default() -> Self {
+        NmsMode::Hard
+    }
+}
+
 /// runs nms on without taking into account which class
 fn nms(raw_bboxes: &mut Vec<LayoutBBox>, iou_threshold: f32) {
     raw_bboxes.sort_by(|r1, r2| r2.proba.partial_cmp(&r1.proba).unwrap());
@@ -390,6 +693,48 @@ fn nms(raw_bboxes: &mut Vec<LayoutBBox>, iou_threshold: f32) {
     raw_bboxes.truncate(current_index);
 }
 
+/// Soft-NMS: instead of dropping overlapping boxes outright, decays their probability
+/// by their IOU with the currently-selected box, then re-selects the max from what's
+/// left. Boxes whose decayed probability falls below `conf_threshold` are dropped.
+fn soft_nms(
+    raw_bboxes: &mut Vec<LayoutBBox>,
+    iou_threshold: f32,
+    conf_threshold: f32,
+    mode: NmsMode,
+) {
+    let mut pending = std::mem::take(raw_bboxes);
+    let mut kept = Vec::with_capacity(pending.len());
+
+    while !pending.is_empty() {
+        let (max_index, _) = pending
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.proba.partial_cmp(&b.proba).unwrap())
+            .unwrap();
+        let m = pending.swap_remove(max_index);
+
+        for b in pending.iter_mut() {
+            let iou = m.bbox.relaxed_iou(&b.bbox);
+            match mode {
+                NmsMode::SoftLinear => {
+                    if iou > iou_threshold {
+                        b.proba *= 1.0 - iou;
+                    }
+                }
+                NmsMode::SoftGaussian { sigma } => {
+                    b.proba *= (-(iou * iou) / sigma).exp();
+                }
+                NmsMode::Hard => unreachable!("soft_nms called with NmsMode::Hard"),
+            }
+        }
+        pending.retain(|b| b.proba >= conf_threshold);
+
+        kept.push(m);
+    }
+
+    *raw_bboxes = kept;
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -523,4 +868,80 @@ mod tests {
         assert_eq!(raw_bboxes.len(), 1);
         assert_eq!(raw_bboxes[0].proba, 0.95);
     }
+
+    #[test]
+    fn test_soft_nms_linear_decays_instead_of_dropping() {
+        let mut raw_bboxes = vec![
+            LayoutBBox {
+                id: 0,
+                bbox: BBox {
+                    x0: 0.0,
+                    y0: 0.0,
+                    x1: 2.0,
+                    y1: 2.0,
+                },
+                label: "A",
+                proba: 0.95,
+            },
+            LayoutBBox {
+                id: 1,
+                // Overlaps half of box #0 (relaxed IOU 0.5 > 0.3), so its proba is decayed
+                // to 0.9 * 0.5 = 0.45, which stays above the 0.3 conf threshold.
+                bbox: BBox {
+                    x0: 1.0,
+                    y0: 0.0,
+                    x1: 3.0,
+                    y1: 2.0,
+                },
+                label: "A",
+                proba: 0.9,
+            },
+        ];
+
+        soft_nms(&mut raw_bboxes, 0.3, 0.3, NmsMode::SoftLinear);
+
+        assert_eq!(raw_bboxes.len(), 2);
+        assert_eq!(raw_bboxes[0].proba, 0.95);
+        assert!(raw_bboxes[1].proba < 0.9);
+    }
+
+    #[test]
+    fn test_soft_nms_gaussian_drops_below_conf_threshold() {
+        let mut raw_bboxes = vec![
+            LayoutBBox {
+                id: 0,
+                bbox: BBox {
+                    x0: 0.0,
+                    y0: 0.0,
+                    x1: 2.0,
+                    y1: 2.0,
+                },
+                label: "A",
+                proba: 0.95,
+            },
+            LayoutBBox {
+                id: 1,
+                // Exact duplicate of box #0 => IOU=1, Gaussian decay should crush it
+                // below the confidence threshold and drop it.
+                bbox: BBox {
+                    x0: 0.0,
+                    y0: 0.0,
+                    x1: 2.0,
+                    y1: 2.0,
+                },
+                label: "A",
+                proba: 0.4,
+            },
+        ];
+
+        soft_nms(
+            &mut raw_bboxes,
+            0.3,
+            0.3,
+            NmsMode::SoftGaussian { sigma: 0.5 },
+        );
+
+        assert_eq!(raw_bboxes.len(), 1);
+        assert_eq!(raw_bboxes[0].proba, 0.95);
+    }
 }
@@ -81,14 +81,27 @@
 //! Licensed under the GPLv3 license.
 #![feature(portable_simd)]
 
+pub(crate) mod checkpoint;
 pub(crate) mod draw;
 
+pub mod agl;
 pub mod blocks;
+pub mod cache;
+pub mod cancellation;
+pub mod chunk;
 pub mod entities;
+pub mod geometry;
 pub mod layout;
+pub(crate) mod metrics;
 pub mod ocr;
 pub mod render;
+pub mod resource;
+#[cfg(feature = "lua")]
+pub mod script;
 pub mod utils;
 
 mod parse;
-pub use parse::document::{FerrulesParseConfig, FerrulesParser};
+pub use parse::document::{FerrulesParseConfig, FerrulesParser, DEFAULT_MAX_IMAGE_PIXELS};
+pub use parse::merge::MergeConfig;
+pub use parse::reading_order::ReadingOrderConfig;
+pub use entities::LigatureConfig;
@@ -0,0 +1,83 @@
+//! OpenTelemetry metrics for the parse pipeline: pages processed, blocks produced per
+//! [`crate::blocks::BlockType`], per-stage latency, and bytes in/out.
+//!
+//! Instruments are recorded through [`opentelemetry::global::meter`], which is a documented
+//! no-op until something installs a real `MeterProvider` -- `ferrules-api`'s `init_metrics` does
+//! this for the API server; `ferrules-cli` never does, so these calls simply cost nothing there.
+
+use std::time::Duration;
+
+use lazy_static::lazy_static;
+use opentelemetry::{
+    metrics::{Counter, Histogram},
+    KeyValue,
+};
+
+lazy_static! {
+    static ref METER: opentelemetry::metrics::Meter = opentelemetry::global::meter("ferrules_core");
+    static ref PAGES_PROCESSED: Counter<u64> = METER
+        .u64_counter("ferrules.pages_processed")
+        .with_description("Total PDF pages processed")
+        .init();
+    static ref BLOCKS_RENDERED: Counter<u64> = METER
+        .u64_counter("ferrules.blocks_rendered")
+        .with_description("Blocks produced, by block type")
+        .init();
+    static ref STAGE_LATENCY_MS: Histogram<f64> = METER
+        .f64_histogram("ferrules.stage_latency_ms")
+        .with_description("Per-stage parse pipeline latency")
+        .with_unit("ms")
+        .init();
+    static ref BYTES_IN: Counter<u64> = METER
+        .u64_counter("ferrules.bytes_in")
+        .with_description("Bytes read from input documents")
+        .init();
+    static ref BYTES_OUT: Counter<u64> = METER
+        .u64_counter("ferrules.bytes_out")
+        .with_description("Bytes written to rendered output")
+        .init();
+}
+
+/// Records `count` pages having finished the parse pipeline.
+pub(crate) fn record_pages_processed(count: u64) {
+    PAGES_PROCESSED.add(count, &[]);
+}
+
+/// Records `count` blocks of `block_type` (e.g. `"table"`, `"title"`) having been produced.
+pub(crate) fn record_blocks_rendered(block_type: &'static str, count: u64) {
+    BLOCKS_RENDERED.add(count, &[KeyValue::new("block_type", block_type)]);
+}
+
+/// Records `duration` spent in the named pipeline stage: `"native_parse"`, `"layout"`, `"ocr"`,
+/// or `"render"`.
+pub(crate) fn record_stage_latency(stage: &'static str, duration: Duration) {
+    STAGE_LATENCY_MS.record(
+        duration.as_secs_f64() * 1000.0,
+        &[KeyValue::new("stage", stage)],
+    );
+}
+
+/// Records `bytes` read from an input document.
+pub(crate) fn record_bytes_in(bytes: u64) {
+    BYTES_IN.add(bytes, &[]);
+}
+
+/// Records `bytes` written to rendered output.
+pub(crate) fn record_bytes_out(bytes: u64) {
+    BYTES_OUT.add(bytes, &[]);
+}
+
+/// Label for a [`crate::blocks::BlockType`] used as the `block_type` metric attribute.
+pub(crate) fn block_type_label(kind: &crate::blocks::BlockType) -> &'static str {
+    use crate::blocks::BlockType;
+    match kind {
+        BlockType::Header(_) => "header",
+        BlockType::Footer(_) => "footer",
+        BlockType::Title(_) => "title",
+        BlockType::ListBlock(_) => "list",
+        BlockType::TextBlock(_) => "text",
+        BlockType::Image(_) => "image",
+        BlockType::Table(_) => "table",
+        BlockType::Annotation(_) => "annotation",
+    }
+}
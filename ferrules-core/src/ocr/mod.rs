@@ -0,0 +1,145 @@
+use image::DynamicImage;
+
+use crate::entities::{BBox, Line};
+
+mod ort;
+#[cfg(target_os = "macos")]
+mod vision;
+
+pub use ort::OrtOcrEngine;
+#[cfg(target_os = "macos")]
+pub use vision::AppleVisionEngine;
+
+/// A single recognized line of text returned by an [`OcrEngine`], with its bounding box
+/// already rescaled into the coordinate space the caller rendered the source image at.
+#[derive(Debug)]
+pub struct TextRegion {
+    pub text: String,
+    pub confidence: f32,
+    pub bbox: BBox,
+}
+
+impl TextRegion {
+    pub(crate) fn to_line(&self) -> Line {
+        Line {
+            text: self.text.to_string(),
+            bbox: self.bbox.clone(),
+            rotation: 0f32,
+            spans: vec![],
+        }
+    }
+}
+
+/// Accuracy/speed tradeoff requested from the underlying OCR backend.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OcrRecognitionLevel {
+    Fast,
+    #[default]
+    Accurate,
+}
+
+/// How a batch of page-image regions is dispatched to the OCR backend. The three
+/// strategies mirror `benches/ocr_mac.rs`, which measured all of them against Apple
+/// Vision: a fresh request handler per region run sequentially or in parallel, versus
+/// one sequence handler reused across every region in the batch.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OcrBatchMode {
+    /// One `VNImageRequestHandler` per region, processed sequentially.
+    ImageHandler,
+    /// One `VNImageRequestHandler` per region, processed across a rayon thread pool.
+    #[default]
+    ImageHandlerParIter,
+    /// A single `VNSequenceRequestHandler` reused across every region in the batch.
+    SequenceHandler,
+}
+
+/// Which backend [`build_engine`] instantiates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OcrBackend {
+    /// Apple's Vision framework, via [`AppleVisionEngine`]. macOS-only.
+    AppleVision,
+    /// Cross-platform ONNX Runtime detector + recognizer, via [`OrtOcrEngine`].
+    Ort,
+}
+
+impl Default for OcrBackend {
+    fn default() -> Self {
+        if cfg!(target_os = "macos") {
+            OcrBackend::AppleVision
+        } else {
+            OcrBackend::Ort
+        }
+    }
+}
+
+/// Configuration for OCR fallback, threaded down from [`crate::parse::document::FerrulesParseConfig`].
+#[derive(Debug, Clone)]
+pub struct OcrConfig {
+    /// Whether OCR fallback is enabled at all. When `false`, pages that would otherwise
+    /// need OCR are left with whatever native text extraction found.
+    pub enabled: bool,
+    /// BCP-47 language hints passed to the backend (e.g. `["en-US"]`). Empty lets the
+    /// backend auto-detect.
+    pub languages: Vec<String>,
+    pub level: OcrRecognitionLevel,
+    pub batch_mode: OcrBatchMode,
+    /// Which [`OcrEngine`] implementation to build. Defaults to Apple Vision on macOS
+    /// and the ONNX Runtime backend everywhere else.
+    pub backend: OcrBackend,
+}
+
+impl Default for OcrConfig {
+    fn default() -> Self {
+        Self {
+            enabled: cfg!(target_os = "macos"),
+            languages: Vec::new(),
+            level: OcrRecognitionLevel::default(),
+            batch_mode: OcrBatchMode::default(),
+            backend: OcrBackend::default(),
+        }
+    }
+}
+
+/// A pluggable OCR backend. `regions` are independent image crops (or whole pages) to
+/// recognize text in; implementations are free to batch them however suits the backend.
+pub trait OcrEngine: Send + Sync {
+    fn recognize(&self, regions: &[DynamicImage]) -> anyhow::Result<Vec<TextRegion>>;
+}
+
+/// Instantiates the [`OcrEngine`] selected by [`OcrConfig::backend`].
+fn build_engine(config: &OcrConfig) -> anyhow::Result<Box<dyn OcrEngine>> {
+    match config.backend {
+        OcrBackend::AppleVision => {
+            #[cfg(target_os = "macos")]
+            {
+                Ok(Box::new(AppleVisionEngine::new(config.clone())))
+            }
+            #[cfg(not(target_os = "macos"))]
+            {
+                anyhow::bail!("the Apple Vision OCR backend is only available on macOS")
+            }
+        }
+        OcrBackend::Ort => Ok(Box::new(OrtOcrEngine::new(config)?)),
+    }
+}
+
+/// Runs OCR over a single page image and rescales the resulting text line boxes by
+/// `rescale_factor`, matching the coordinate space `page.rs` merges native lines in.
+pub(crate) fn parse_image_ocr(
+    image: &DynamicImage,
+    rescale_factor: f32,
+    config: &OcrConfig,
+) -> anyhow::Result<Vec<TextRegion>> {
+    let engine = build_engine(config)?;
+    engine
+        .recognize(std::slice::from_ref(image))
+        .map(|mut regions| {
+            for region in &mut regions {
+                region.bbox.x0 /= rescale_factor;
+                region.bbox.y0 /= rescale_factor;
+                region.bbox.x1 /= rescale_factor;
+                region.bbox.y1 /= rescale_factor;
+            }
+            regions
+        })
+}
@@ -0,0 +1,288 @@
+use anyhow::Context;
+use image::{imageops::FilterType, DynamicImage, GenericImageView};
+use ndarray::{s, Array4, ArrayBase, Dim, OwnedRepr};
+use ort::{
+    execution_providers::CPUExecutionProvider,
+    session::{builder::GraphOptimizationLevel, Session},
+};
+
+use super::{OcrConfig, OcrEngine, TextRegion};
+use crate::{entities::BBox, layout::model::provider_is_available};
+
+/// Text-detection model: predicts a per-pixel text/background probability map, in the
+/// style of DBNet. Boxes are recovered from the map by thresholding and taking
+/// connected-component bounding rects.
+const DETECTION_MODEL_BYTES: &[u8] = include_bytes!("../../../models/ocr-detection.onnx");
+/// Text-recognition model: a CRNN-style CTC recognizer run on each cropped detection box.
+const RECOGNITION_MODEL_BYTES: &[u8] = include_bytes!("../../../models/ocr-recognition-en.onnx");
+
+/// CTC output alphabet for [`RECOGNITION_MODEL_BYTES`], index 0 reserved for the CTC blank.
+const EN_CHARSET: &str =
+    " 0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ.,:;!?'\"-()";
+
+/// Picks the recognizer charset for the requested `languages`. Only English is bundled
+/// today; any other BCP-47 tag falls back to it with a note, rather than failing outright.
+fn charset_for(languages: &[String]) -> Vec<char> {
+    if !languages.is_empty() && !languages.iter().any(|lang| lang.starts_with("en")) {
+        tracing::debug!(
+            ?languages,
+            "OrtOcrEngine only bundles an English charset, falling back to it"
+        );
+    }
+    EN_CHARSET.chars().collect()
+}
+
+/// A text box found by the detection pass, in the coordinate space of the image it was
+/// detected in.
+struct DetectedBox {
+    bbox: BBox,
+}
+
+/// Cross-platform [`OcrEngine`] backed by two ONNX Runtime sessions: a detector that finds
+/// text line boxes, and a CTC recognizer run over each cropped box.
+pub(crate) struct OrtOcrEngine {
+    detect_session: Session,
+    recognize_session: Session,
+    charset: Vec<char>,
+}
+
+impl OrtOcrEngine {
+    const DETECT_INPUT_SIZE: u32 = 960;
+    const RECOGNIZE_HEIGHT: u32 = 32;
+    const RECOGNIZE_WIDTH: u32 = 256;
+    /// Minimum detection-map probability for a pixel to be considered part of a text region.
+    const DETECTION_THRESHOLD: f32 = 0.3;
+
+    pub(crate) fn new(config: &OcrConfig) -> anyhow::Result<Self> {
+        use crate::layout::model::OrtExecutionProvider;
+
+        let execution_providers = if provider_is_available(&OrtExecutionProvider::CPU) {
+            vec![CPUExecutionProvider::default().build()]
+        } else {
+            vec![]
+        };
+
+        let detect_session = Session::builder()?
+            .with_execution_providers(execution_providers.clone())?
+            .with_optimization_level(GraphOptimizationLevel::Level3)?
+            .commit_from_memory(DETECTION_MODEL_BYTES)?;
+
+        let recognize_session = Session::builder()?
+            .with_execution_providers(execution_providers)?
+            .with_optimization_level(GraphOptimizationLevel::Level3)?
+            .commit_from_memory(RECOGNITION_MODEL_BYTES)?;
+
+        Ok(Self {
+            detect_session,
+            recognize_session,
+            charset: charset_for(&config.languages),
+        })
+    }
+
+    fn preprocess_detect(&self, img: &DynamicImage) -> Array4<f32> {
+        let resized = img.resize_exact(
+            Self::DETECT_INPUT_SIZE,
+            Self::DETECT_INPUT_SIZE,
+            FilterType::Triangle,
+        );
+        let mut input = Array4::zeros([
+            1,
+            3,
+            Self::DETECT_INPUT_SIZE as usize,
+            Self::DETECT_INPUT_SIZE as usize,
+        ]);
+        for (x, y, pixel) in resized.pixels() {
+            let [r, g, b, _] = pixel.0;
+            input[[0, 0, y as usize, x as usize]] = r as f32 / 255.0;
+            input[[0, 1, y as usize, x as usize]] = g as f32 / 255.0;
+            input[[0, 2, y as usize, x as usize]] = b as f32 / 255.0;
+        }
+        input
+    }
+
+    /// Runs the detection session and recovers axis-aligned text boxes from its
+    /// probability map, scaled back to `img`'s original dimensions.
+    fn detect(&self, img: &DynamicImage) -> anyhow::Result<Vec<DetectedBox>> {
+        let (orig_w, orig_h) = img.dimensions();
+        let input = self.preprocess_detect(img);
+        let outputs = self.detect_session.run(ort::inputs![input]?)?;
+        let output_name = outputs
+            .keys()
+            .next()
+            .context("detection session produced no outputs")?
+            .to_owned();
+        let prob_map = outputs
+            .get(&output_name)
+            .context("can't get detection output tensor")?
+            .try_extract_tensor::<f32>()?
+            .to_owned();
+        // Shape: (1, 1, H, W)
+        let prob_map: ArrayBase<OwnedRepr<f32>, Dim<[usize; 2]>> = prob_map
+            .slice(s![0, 0, .., ..])
+            .to_owned()
+            .into_dimensionality()?;
+        let (map_h, map_w) = (prob_map.shape()[0], prob_map.shape()[1]);
+
+        let boxes = connected_component_boxes(&prob_map, Self::DETECTION_THRESHOLD);
+        let scale_x = orig_w as f32 / map_w as f32;
+        let scale_y = orig_h as f32 / map_h as f32;
+        Ok(boxes
+            .into_iter()
+            .map(|(x0, y0, x1, y1)| DetectedBox {
+                bbox: BBox {
+                    x0: x0 as f32 * scale_x,
+                    y0: y0 as f32 * scale_y,
+                    x1: x1 as f32 * scale_x,
+                    y1: y1 as f32 * scale_y,
+                },
+            })
+            .collect())
+    }
+
+    fn preprocess_recognize(&self, crop: &DynamicImage) -> Array4<f32> {
+        let resized = crop.resize_exact(
+            Self::RECOGNIZE_WIDTH,
+            Self::RECOGNIZE_HEIGHT,
+            FilterType::Triangle,
+        );
+        let mut input = Array4::zeros([
+            1,
+            3,
+            Self::RECOGNIZE_HEIGHT as usize,
+            Self::RECOGNIZE_WIDTH as usize,
+        ]);
+        for (x, y, pixel) in resized.pixels() {
+            let [r, g, b, _] = pixel.0;
+            input[[0, 0, y as usize, x as usize]] = r as f32 / 255.0;
+            input[[0, 1, y as usize, x as usize]] = g as f32 / 255.0;
+            input[[0, 2, y as usize, x as usize]] = b as f32 / 255.0;
+        }
+        input
+    }
+
+    /// Recognizes a single cropped text box and decodes the CTC output greedily
+    /// (argmax per timestep, collapsing repeats, dropping blanks).
+    fn recognize_box(&self, crop: &DynamicImage) -> anyhow::Result<(String, f32)> {
+        let input = self.preprocess_recognize(crop);
+        let outputs = self.recognize_session.run(ort::inputs![input]?)?;
+        let output_name = outputs
+            .keys()
+            .next()
+            .context("recognition session produced no outputs")?
+            .to_owned();
+        let logits = outputs
+            .get(&output_name)
+            .context("can't get recognition output tensor")?
+            .try_extract_tensor::<f32>()?
+            .to_owned();
+        // Shape: (1, T, charset_len + 1), the extra class being the CTC blank at index 0.
+        let logits: ArrayBase<OwnedRepr<f32>, Dim<[usize; 2]>> = logits
+            .slice(s![0, .., ..])
+            .to_owned()
+            .into_dimensionality()?;
+
+        let mut text = String::new();
+        let mut confidences = Vec::new();
+        let mut prev_idx = 0usize;
+        for step in logits.outer_iter() {
+            let (idx, &prob) = step
+                .iter()
+                .enumerate()
+                .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+                .context("empty recognition timestep")?;
+            if idx != 0 && idx != prev_idx {
+                if let Some(&ch) = self.charset.get(idx - 1) {
+                    text.push(ch);
+                    confidences.push(prob);
+                }
+            }
+            prev_idx = idx;
+        }
+
+        let confidence = if confidences.is_empty() {
+            0.0
+        } else {
+            confidences.iter().sum::<f32>() / confidences.len() as f32
+        };
+        Ok((text, confidence))
+    }
+}
+
+impl OcrEngine for OrtOcrEngine {
+    fn recognize(&self, regions: &[DynamicImage]) -> anyhow::Result<Vec<TextRegion>> {
+        let mut text_regions = Vec::new();
+        for region in regions {
+            for detected in self.detect(region)? {
+                let crop = region.crop_imm(
+                    detected.bbox.x0 as u32,
+                    detected.bbox.y0 as u32,
+                    (detected.bbox.x1 - detected.bbox.x0).max(1.0) as u32,
+                    (detected.bbox.y1 - detected.bbox.y0).max(1.0) as u32,
+                );
+                let (text, confidence) = self.recognize_box(&crop)?;
+                if text.is_empty() {
+                    continue;
+                }
+                text_regions.push(TextRegion {
+                    text,
+                    confidence,
+                    bbox: detected.bbox,
+                });
+            }
+        }
+        Ok(text_regions)
+    }
+}
+
+/// Finds axis-aligned bounding rects of connected regions where `prob_map` exceeds
+/// `threshold`, via a simple flood fill. Adequate for the sparse, well-separated text
+/// regions a detection map produces; not a general-purpose CC labeler.
+fn connected_component_boxes(
+    prob_map: &ArrayBase<OwnedRepr<f32>, Dim<[usize; 2]>>,
+    threshold: f32,
+) -> Vec<(usize, usize, usize, usize)> {
+    let (h, w) = (prob_map.shape()[0], prob_map.shape()[1]);
+    let mut visited = vec![false; h * w];
+    let mut boxes = Vec::new();
+
+    for start_y in 0..h {
+        for start_x in 0..w {
+            let start_idx = start_y * w + start_x;
+            if visited[start_idx] || prob_map[[start_y, start_x]] < threshold {
+                continue;
+            }
+
+            let (mut min_x, mut min_y, mut max_x, mut max_y) = (start_x, start_y, start_x, start_y);
+            let mut stack = vec![(start_x, start_y)];
+            visited[start_idx] = true;
+
+            while let Some((x, y)) = stack.pop() {
+                min_x = min_x.min(x);
+                min_y = min_y.min(y);
+                max_x = max_x.max(x);
+                max_y = max_y.max(y);
+
+                let neighbors = [
+                    (x.wrapping_sub(1), y),
+                    (x + 1, y),
+                    (x, y.wrapping_sub(1)),
+                    (x, y + 1),
+                ];
+                for (nx, ny) in neighbors {
+                    if nx >= w || ny >= h {
+                        continue;
+                    }
+                    let idx = ny * w + nx;
+                    if !visited[idx] && prob_map[[ny, nx]] >= threshold {
+                        visited[idx] = true;
+                        stack.push((nx, ny));
+                    }
+                }
+            }
+
+            boxes.push((min_x, min_y, max_x + 1, max_y + 1));
+        }
+    }
+
+    boxes
+}
@@ -0,0 +1,158 @@
+use std::io::Cursor;
+
+use image::DynamicImage;
+use objc2::ClassType;
+use objc2_foundation::{CGRect, NSArray, NSData, NSDictionary, NSString};
+use objc2_vision::{
+    VNImageRequestHandler, VNRecognizeTextRequest, VNRequest, VNRequestTextRecognitionLevel,
+    VNSequenceRequestHandler,
+};
+use rayon::prelude::*;
+
+use crate::entities::BBox;
+
+use super::{OcrBatchMode, OcrConfig, OcrEngine, OcrRecognitionLevel, TextRegion};
+
+const CONFIDENCE_THRESHOLD: f32 = 0f32;
+
+/// Converts a Vision-space bounding box (origin bottom-left, normalized `[0, 1]`) into
+/// absolute upper-left/lower-right pixel coordinates.
+#[inline]
+fn cgrect_to_bbox(bbox: &CGRect, img_width: u32, img_height: u32) -> BBox {
+    let bx0 = bbox.origin.x as f32;
+    let by0 = bbox.origin.y as f32;
+    let bw = bbox.size.width as f32;
+    let bh = bbox.size.height as f32;
+
+    let x0 = bx0 * img_width as f32;
+    let y1 = (1f32 - by0) * (img_height as f32);
+    let x1 = x0 + bw * (img_width as f32);
+    let y0 = y1 - bh * (img_height as f32);
+
+    BBox { x0, y0, x1, y1 }
+}
+
+fn make_request(config: &OcrConfig) -> objc2::rc::Retained<VNRecognizeTextRequest> {
+    let request = unsafe { VNRecognizeTextRequest::new() };
+    let level = match config.level {
+        OcrRecognitionLevel::Fast => VNRequestTextRecognitionLevel::Fast,
+        OcrRecognitionLevel::Accurate => VNRequestTextRecognitionLevel::Accurate,
+    };
+    unsafe {
+        request.setRecognitionLevel(level);
+        request.setUsesLanguageCorrection(true);
+        if !config.languages.is_empty() {
+            let languages = config
+                .languages
+                .iter()
+                .map(|lang| NSString::from_str(lang))
+                .collect::<Vec<_>>();
+            let languages = languages.iter().map(|s| s.as_ref()).collect::<Vec<_>>();
+            request
+                .setRecognitionLanguages(&NSArray::from_slice(&languages))
+                .ok();
+        }
+    }
+    request
+}
+
+fn encode_png(image: &DynamicImage) -> anyhow::Result<Vec<u8>> {
+    let mut buffer: Cursor<Vec<u8>> = Cursor::new(Vec::new());
+    image.write_to(&mut buffer, image::ImageFormat::Png)?;
+    Ok(buffer.into_inner())
+}
+
+fn regions_from_request(
+    request: &VNRecognizeTextRequest,
+    img_width: u32,
+    img_height: u32,
+) -> Vec<TextRegion> {
+    let mut regions = Vec::new();
+    unsafe {
+        if let Some(result) = request.results() {
+            for recognized_text_region in result.to_vec() {
+                if (*recognized_text_region).confidence() <= CONFIDENCE_THRESHOLD {
+                    continue;
+                }
+                let Some(rec_text) = recognized_text_region.topCandidates(1).first() else {
+                    continue;
+                };
+                let bbox = (*recognized_text_region).boundingBox();
+                regions.push(TextRegion {
+                    text: rec_text.string().to_string(),
+                    confidence: rec_text.confidence(),
+                    bbox: cgrect_to_bbox(&bbox, img_width, img_height),
+                });
+            }
+        }
+    }
+    regions
+}
+
+fn recognize_with_image_handler(
+    config: &OcrConfig,
+    png: &[u8],
+    img_width: u32,
+    img_height: u32,
+) -> anyhow::Result<Vec<TextRegion>> {
+    let request = make_request(config);
+    unsafe {
+        let handler = VNImageRequestHandler::initWithData_options(
+            VNImageRequestHandler::alloc(),
+            &NSData::with_bytes(png),
+            &NSDictionary::new(),
+        );
+        let requests = NSArray::from_slice(&[request.as_ref() as &VNRequest]);
+        handler.performRequests_error(&requests)?;
+    }
+    Ok(regions_from_request(&request, img_width, img_height))
+}
+
+/// Apple Vision-backed [`OcrEngine`], recognizing text via `VNRecognizeTextRequest`.
+pub(crate) struct AppleVisionEngine {
+    config: OcrConfig,
+}
+
+impl AppleVisionEngine {
+    pub(crate) fn new(config: OcrConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl OcrEngine for AppleVisionEngine {
+    fn recognize(&self, regions: &[DynamicImage]) -> anyhow::Result<Vec<TextRegion>> {
+        let encoded = regions
+            .iter()
+            .map(|image| anyhow::Ok((encode_png(image)?, image.width(), image.height())))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        match self.config.batch_mode {
+            OcrBatchMode::ImageHandler => encoded
+                .iter()
+                .map(|(png, w, h)| recognize_with_image_handler(&self.config, png, *w, *h))
+                .collect::<anyhow::Result<Vec<_>>>()
+                .map(|regions| regions.into_iter().flatten().collect()),
+            OcrBatchMode::ImageHandlerParIter => encoded
+                .par_iter()
+                .map(|(png, w, h)| recognize_with_image_handler(&self.config, png, *w, *h))
+                .collect::<anyhow::Result<Vec<_>>>()
+                .map(|regions| regions.into_iter().flatten().collect()),
+            OcrBatchMode::SequenceHandler => {
+                let handler = unsafe { VNSequenceRequestHandler::new() };
+                let mut all_regions = Vec::new();
+                for (png, w, h) in &encoded {
+                    let request = make_request(&self.config);
+                    unsafe {
+                        let requests = NSArray::from_slice(&[request.as_ref() as &VNRequest]);
+                        handler.performRequests_onImageData_error(
+                            &requests,
+                            &NSData::with_bytes(png),
+                        )?;
+                    }
+                    all_regions.extend(regions_from_request(&request, *w, *h));
+                }
+                Ok(all_regions)
+            }
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use pdfium_render::prelude::{PdfPage, PdfPageAnnotationCommon, PdfPageAnnotationType};
+
+use crate::entities::{Annotation, AnnotationKind, BBox, Line, PageID};
+
+/// Minimum fraction of a `Line`'s area that must fall inside a markup annotation's quad region
+/// for the line to be considered "covered" by (and thus attached to) that annotation.
+const MIN_LINE_COVERAGE: f32 = 0.5;
+
+/// Converts a flat `QuadPoints`-style array (four `(x, y)` corner pairs, bottom-left PDF origin)
+/// into a single upright [`BBox`] by taking the min/max over every corner, the same "axis-align
+/// the quad" approach `page.rs` uses for layout boxes.
+fn quad_to_bbox(points: &[(f32, f32)], page_height: f32) -> Option<BBox> {
+    if points.is_empty() {
+        return None;
+    }
+    let (mut x0, mut y0) = (f32::MAX, f32::MAX);
+    let (mut x1, mut y1) = (f32::MIN, f32::MIN);
+    for &(x, y) in points {
+        x0 = x0.min(x);
+        x1 = x1.max(x);
+        y0 = y0.min(y);
+        y1 = y1.max(y);
+    }
+    Some(BBox {
+        x0,
+        y0: page_height - y1,
+        x1,
+        y1: page_height - y0,
+    })
+}
+
+/// Text of every `Line` whose area is at least [`MIN_LINE_COVERAGE`] inside `bbox`, joined with
+/// spaces -- the prose a highlight/underline/strikeout annotation visually covers.
+fn covered_text(bbox: &BBox, text_lines: &[Line]) -> Option<String> {
+    let covered: Vec<&str> = text_lines
+        .iter()
+        .filter(|line| {
+            let line_area = line.bbox.area();
+            line_area > 0.0 && bbox.intersection(&line.bbox) / line_area >= MIN_LINE_COVERAGE
+        })
+        .map(|line| line.text.as_str())
+        .collect();
+    if covered.is_empty() {
+        None
+    } else {
+        Some(covered.join(" "))
+    }
+}
+
+/// Extracts every annotation on `page` -- markup (highlight/underline/strikeout), link, and
+/// free-text note -- as a page-coordinate [`Annotation`], associating markup annotations with
+/// the `text_lines` they visually cover.
+///
+/// Markup annotations store their region as a `QuadPoints` array (one quad per highlighted run,
+/// four corner points each); this takes the union bbox over every quad rather than keeping them
+/// separate, since [`Annotation`] carries a single region like every other geometric entity in
+/// this crate.
+pub(crate) fn parse_page_annotations(
+    page: &PdfPage,
+    page_height: f32,
+    text_lines: &[Line],
+    page_id: PageID,
+) -> Vec<Annotation> {
+    let mut annotations = Vec::new();
+
+    for annotation in page.annotations().iter() {
+        let kind = match annotation.annotation_type() {
+            PdfPageAnnotationType::Highlight => AnnotationKind::Highlight,
+            PdfPageAnnotationType::Underline => AnnotationKind::Underline,
+            PdfPageAnnotationType::StrikeOut => AnnotationKind::StrikeOut,
+            PdfPageAnnotationType::Link => AnnotationKind::Link {
+                uri: annotation.link_uri(),
+                dest_page: annotation.link_dest_page_index().map(|idx| idx as PageID),
+            },
+            PdfPageAnnotationType::FreeText | PdfPageAnnotationType::Text => {
+                let Some(contents) = annotation.contents() else {
+                    continue;
+                };
+                AnnotationKind::Note { text: contents }
+            }
+            _ => continue,
+        };
+
+        let bbox = match &kind {
+            AnnotationKind::Highlight | AnnotationKind::Underline | AnnotationKind::StrikeOut => {
+                let quads: Vec<(f32, f32)> = annotation
+                    .quad_points()
+                    .iter()
+                    .flat_map(|quad| {
+                        [
+                            (quad.x1.value, quad.y1.value),
+                            (quad.x2.value, quad.y2.value),
+                            (quad.x3.value, quad.y3.value),
+                            (quad.x4.value, quad.y4.value),
+                        ]
+                    })
+                    .collect();
+                match quad_to_bbox(&quads, page_height) {
+                    Some(bbox) => bbox,
+                    None => continue,
+                }
+            }
+            _ => {
+                let rect = annotation.bounds();
+                let Ok(rect) = rect else { continue };
+                BBox {
+                    x0: rect.left().value,
+                    y0: page_height - rect.top().value,
+                    x1: rect.right().value,
+                    y1: page_height - rect.bottom().value,
+                }
+            }
+        };
+
+        let text = match &kind {
+            AnnotationKind::Highlight | AnnotationKind::Underline | AnnotationKind::StrikeOut => {
+                covered_text(&bbox, text_lines)
+            }
+            AnnotationKind::Note { text } => Some(text.clone()),
+            AnnotationKind::Link { .. } => covered_text(&bbox, text_lines),
+        };
+
+        annotations.push(Annotation {
+            kind,
+            bbox,
+            text,
+            page_id,
+        });
+    }
+
+    annotations
+}
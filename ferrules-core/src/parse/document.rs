@@ -1,25 +1,65 @@
-use std::{path::PathBuf, sync::Arc, time::Instant};
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, Mutex, OnceLock},
+    time::Instant,
+};
 
 use std::ops::Range;
 
 use anyhow::Context;
+use async_stream::try_stream;
 use tokio::{sync::mpsc, task::JoinSet};
+use tokio_stream::{Stream, StreamExt};
 use tracing::Instrument;
 
 use super::native::{ParseNativeQueue, ParseNativeRequest};
 use super::{
-    merge::merge_elements_into_blocks, native::ParseNativePageResult, page::parse_page_full,
+    figures, footnotes,
+    merge::{merge_elements_into_blocks, MergeConfig},
+    native::ParseNativePageResult,
+    page::parse_page_full,
+    reading_order::ReadingOrderConfig,
+    stitch::{stitch_cross_page_blocks, StitchConfig},
     titles::title_levels_kmeans,
 };
 use crate::entities::DocumentMetadata;
 use crate::{
-    entities::{ElementType, Page, PageID, ParsedDocument, StructuredPage},
+    blocks::{AnnotationBlock, Block, BlockType, Features},
+    cache::{page_content_hash, PageCache, PageCacheConfig, PageCacheStats},
+    checkpoint::{document_fingerprint, Checkpoint},
+    entities::{
+        Annotation, ElementType, LigatureConfig, Page, PageID, ParsedDocument, StructuredPage,
+    },
     layout::{
         model::{ORTConfig, ORTLayoutParser},
         ParseLayoutQueue,
     },
+    ocr::OcrConfig,
 };
 
+/// Turns a PDF-native [`Annotation`] into its own [`Block`], so it survives into export
+/// instead of being dropped once `elements`/`merge_elements_into_blocks` has consumed the
+/// page's text. `id` is assigned by the caller to keep ids unique alongside the text/table/
+/// image blocks already produced for the document.
+fn annotation_to_block(id: usize, annotation: Annotation) -> Block {
+    Block {
+        id,
+        kind: BlockType::Annotation(AnnotationBlock {
+            kind: annotation.kind,
+            text: annotation.text,
+        }),
+        pages_id: vec![annotation.page_id],
+        bbox: annotation.bbox,
+    }
+}
+
+/// Default ceiling on rasterized/decoded image pixel count (width * height), applied to PDF
+/// page rendering. Chosen to comfortably cover legitimate high-DPI scans (an A4 page at 600
+/// DPI is ~24 megapixels) while refusing a crafted MediaBox that would otherwise force a
+/// gigapixel allocation.
+pub const DEFAULT_MAX_IMAGE_PIXELS: u64 = 64_000_000;
+
 /// Configuration options for parsing documents with FerrulesParser
 #[derive(Debug, Clone)]
 pub struct FerrulesParseConfig<'a> {
@@ -37,6 +77,42 @@ pub struct FerrulesParseConfig<'a> {
     /// Optional directory path for debug output. When provided, saves intermediate parsing
     /// results and visualizations to this directory
     pub debug_dir: Option<std::path::PathBuf>,
+
+    /// OCR fallback configuration, used for pages whose native text extraction falls
+    /// short of [`crate::parse::page`]'s layout-coverage threshold.
+    pub ocr: OcrConfig,
+
+    /// Ceiling on rasterized page pixel count (width * height). Pages whose MediaBox would
+    /// exceed this are rejected before any render buffer is allocated, guarding against
+    /// decompression-bomb-style PDFs. See [`DEFAULT_MAX_IMAGE_PIXELS`].
+    pub max_image_pixels: u64,
+
+    /// Content-addressed page cache configuration. See [`crate::cache`].
+    pub cache: PageCacheConfig,
+
+    /// Thresholds for geometrically associating a caption/footnote with the image/table it
+    /// describes. See [`MergeConfig`].
+    pub merge: MergeConfig,
+
+    /// Tunables for the recursive XY-cut used to recover each page's reading order. See
+    /// [`ReadingOrderConfig`].
+    pub reading_order: ReadingOrderConfig,
+
+    /// Tunables for the ligature/glyph-recovery pass applied to native-extracted text. See
+    /// [`LigatureConfig`].
+    pub ligatures: LigatureConfig,
+
+    /// Optional directory holding (or to hold) a checkpoint snapshot for this document. When
+    /// set, completed pages are written here as they finish, and a call against the same
+    /// document bytes and `page_range` resumes from whatever pages are already present
+    /// instead of reparsing the whole document. Left intact on cancellation.
+    pub resume_from: Option<PathBuf>,
+
+    /// User-supplied Lua hooks for text normalization and element reclassification. See
+    /// [`crate::script`]. Requires the `lua` cargo feature; `None` (the only option without it)
+    /// leaves both the built-in text cleanup and the layout-label-derived `ElementType`s as-is.
+    #[cfg(feature = "lua")]
+    pub script: Option<std::sync::Arc<crate::script::ScriptEngine>>,
 }
 
 impl Default for FerrulesParseConfig<'_> {
@@ -46,17 +122,31 @@ impl Default for FerrulesParseConfig<'_> {
             flatten_pdf: true,
             page_range: None,
             debug_dir: None,
+            ocr: OcrConfig::default(),
+            max_image_pixels: DEFAULT_MAX_IMAGE_PIXELS,
+            cache: PageCacheConfig::default(),
+            merge: MergeConfig::default(),
+            reading_order: ReadingOrderConfig::default(),
+            ligatures: LigatureConfig::default(),
+            resume_from: None,
+            #[cfg(feature = "lua")]
+            script: None,
         }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn parse_task<F, C>(
     parse_native_result: ParseNativePageResult,
     layout_queue: ParseLayoutQueue,
     debug_dir: Option<PathBuf>,
+    ocr_config: OcrConfig,
+    page_cache: Option<Arc<PageCache>>,
+    reading_order: ReadingOrderConfig,
     callback: Option<F>,
     cancellation_callback: Option<C>,
-) -> anyhow::Result<StructuredPage>
+    #[cfg(feature = "lua")] script: Option<Arc<crate::script::ScriptEngine>>,
+) -> anyhow::Result<(StructuredPage, bool)>
 where
     F: FnOnce(PageID) + Send + 'static + Clone,
     C: Fn() -> bool + Send + Sync + 'static + Clone,
@@ -70,13 +160,37 @@ where
         }
     }
 
-    let result = parse_page_full(
-        parse_native_result,
-        debug_dir,
-        layout_queue.clone(),
-        cancellation_callback.clone(),
-    )
-    .await;
+    let cache_hash = page_cache.as_ref().map(|_| {
+        page_content_hash(
+            &parse_native_result.text_lines,
+            parse_native_result.page_image.as_bytes(),
+        )
+    });
+    let cached_page = match (&page_cache, cache_hash) {
+        (Some(cache), Some(hash)) => cache.get(hash),
+        _ => None,
+    };
+
+    let (result, was_hit) = if let Some(cached_page) = cached_page {
+        let mut page = (*cached_page).clone();
+        page.id = page_id;
+        (Ok(page), true)
+    } else {
+        let parsed = parse_page_full(
+            parse_native_result,
+            debug_dir,
+            layout_queue.clone(),
+            &ocr_config,
+            &reading_order,
+            #[cfg(feature = "lua")]
+            script.as_deref(),
+        )
+        .await;
+        if let (Some(cache), Some(hash), Ok(page)) = (&page_cache, cache_hash, &parsed) {
+            cache.insert(hash, Arc::new(page.clone()));
+        }
+        (parsed, false)
+    };
 
     // Check for cancellation after processing
     if let Some(ref cancel_cb) = cancellation_callback {
@@ -88,7 +202,7 @@ where
     if let Some(callback) = callback {
         callback(page_id)
     }
-    result
+    result.map(|page| (page, was_hit))
 }
 
 /// Core class Document parser that extracts structured content from PDF documents.
@@ -99,6 +213,9 @@ where
 pub struct FerrulesParser {
     layout_queue: ParseLayoutQueue,
     native_queue: ParseNativeQueue,
+    /// Lazily built from the first [`FerrulesParseConfig::cache`] that enables it, then
+    /// shared across every document this parser instance goes on to process.
+    page_cache: Arc<OnceLock<Arc<PageCache>>>,
 }
 
 impl FerrulesParser {
@@ -116,10 +233,12 @@ impl FerrulesParser {
         let layout_model =
             Arc::new(ORTLayoutParser::new(layout_config).expect("can't load layout model"));
         let native_queue = ParseNativeQueue::new();
-        let layout_queue = ParseLayoutQueue::new(layout_model);
+        let layout_queue =
+            ParseLayoutQueue::new(layout_model.clone(), layout_model.config.intra_threads);
         Self {
             layout_queue,
             native_queue,
+            page_cache: Arc::new(OnceLock::new()),
         }
     }
 
@@ -180,6 +299,37 @@ impl FerrulesParser {
         }
     }
 
+    /// Reads `doc`'s outline (bookmarks), without rasterizing or text-extracting any page. See
+    /// [`crate::parse::outline::parse_document_outline`].
+    pub async fn get_document_outline(
+        &self,
+        doc: &[u8],
+        password: Option<&str>,
+    ) -> anyhow::Result<Vec<crate::entities::OutlineEntry>> {
+        use super::native::ParseNativeRequest;
+        use tokio::sync::mpsc;
+
+        let (result_tx, mut result_rx) = mpsc::channel(1);
+        let request = ParseNativeRequest::new_outline_only(doc, password, result_tx);
+
+        self.native_queue
+            .push(request)
+            .await
+            .context("Failed to send outline request to native queue")?;
+
+        let result = result_rx
+            .recv()
+            .await
+            .context("Failed to receive outline result")?
+            .context("Native parsing error")?;
+
+        if result.is_outline_result {
+            Ok(result.outline)
+        } else {
+            anyhow::bail!("Received non-outline result for outline request")
+        }
+    }
+
     /// Parses a document into a structured format with optional page-level progress callback
     ///
     /// # Arguments
@@ -225,31 +375,69 @@ impl FerrulesParser {
             flatten_pdf,
             page_range,
             debug_dir,
+            ocr,
+            max_image_pixels,
+            cache,
+            merge,
+            reading_order,
+            ligatures,
+            resume_from,
+            #[cfg(feature = "lua")]
+            script,
         } = config;
+        let page_cache = cache.enabled.then(|| {
+            Arc::clone(
+                self.page_cache
+                    .get_or_init(|| Arc::new(PageCache::new(&cache))),
+            )
+        });
         let start_time = Instant::now();
-        let parsed_pages = self
-            .parse_doc_pages(
+        let stats = Arc::new(Mutex::new(PageCacheStats::default()));
+        let outline = self.get_document_outline(doc, password).await?;
+
+        let mut parsed_pages = Vec::new();
+        {
+            let stream = self.stream_doc_pages(
                 doc,
                 flatten_pdf,
                 password,
                 page_range,
                 debug_dir.clone(),
+                ocr,
+                max_image_pixels,
+                page_cache,
+                reading_order,
+                ligatures,
+                resume_from,
+                Some(stats.clone()),
                 page_callback,
                 cancellation_callback.clone(),
-            )
-            .await?;
+                #[cfg(feature = "lua")]
+                script.clone(),
+            );
+            tokio::pin!(stream);
+            while let Some(page) = stream.next().await {
+                parsed_pages.push(page?);
+            }
+        }
+        parsed_pages.sort_by(|p1, p2| p1.id.cmp(&p2.id));
+        let page_cache_stats = *stats.lock().unwrap();
 
         let all_elements = parsed_pages
             .iter()
             .flat_map(|p| p.elements.clone())
             .collect::<Vec<_>>();
+        let all_annotations = parsed_pages
+            .iter()
+            .flat_map(|p| p.annotations.clone())
+            .collect::<Vec<_>>();
 
         let titles = all_elements
             .iter()
             .filter(|e| matches!(e.kind, ElementType::Title | ElementType::Subtitle))
             .collect::<Vec<_>>();
 
-        let title_level = title_levels_kmeans(&titles, 6);
+        let title_level = title_levels_kmeans(&titles);
 
         let doc_pages = parsed_pages
             .into_iter()
@@ -257,109 +445,289 @@ impl FerrulesParser {
                 id: sp.id,
                 width: sp.width,
                 height: sp.height,
+                rotation: sp.rotation,
                 need_ocr: sp.need_ocr,
                 image: sp.image,
             })
             .collect();
 
-        let blocks = merge_elements_into_blocks(all_elements, title_level)?;
+        let mut blocks = merge_elements_into_blocks(
+            all_elements,
+            title_level,
+            &merge,
+            #[cfg(feature = "lua")]
+            script.as_deref(),
+        )?;
+        let mut next_block_id = blocks.len();
+        blocks.extend(all_annotations.into_iter().map(|annotation| {
+            let id = next_block_id;
+            next_block_id += 1;
+            annotation_to_block(id, annotation)
+        }));
+        let mut blocks = stitch_cross_page_blocks(blocks, &doc_pages, &StitchConfig::default());
+        figures::number_figures(&mut blocks);
+        figures::link_figure_references(&mut blocks);
+
+        crate::metrics::record_bytes_in(doc.len() as u64);
+        crate::metrics::record_pages_processed(doc_pages.len() as u64);
+        for block in &blocks {
+            crate::metrics::record_blocks_rendered(
+                crate::metrics::block_type_label(&block.kind),
+                1,
+            );
+        }
 
         let duration = start_time.elapsed();
 
+        let footnotes = footnotes::link_footnotes(&blocks);
+        let features = Features::scan(&blocks)
+            | Features {
+                footnotes: !footnotes.is_empty(),
+                ..Default::default()
+            };
+
         Ok(ParsedDocument {
             doc_name,
             pages: doc_pages,
             blocks,
             debug_path: debug_dir,
-            metadata: DocumentMetadata::new(duration),
+            metadata: DocumentMetadata::new(
+                duration,
+                self.layout_queue
+                    .selected_execution_provider()
+                    .await
+                    .to_string(),
+                page_cache_stats,
+            ),
+            footnotes,
+            features,
+            outline,
         })
     }
 
+    /// Like [`Self::parse_document`], but resolves `uri` (a local path or an `http(s)://` URL)
+    /// through the native queue's configured [`crate::resource::ResourceProvider`] instead of
+    /// requiring the caller to already have the document's bytes in hand.
     #[allow(clippy::too_many_arguments)]
-    #[tracing::instrument(skip_all)]
-    async fn parse_doc_pages<F, C>(
+    pub async fn parse_document_from_uri<F, C>(
         &self,
-        data: &[u8],
+        uri: &str,
+        doc_name: String,
+        config: FerrulesParseConfig<'_>,
+        page_callback: Option<F>,
+        cancellation_callback: Option<C>,
+    ) -> anyhow::Result<ParsedDocument>
+    where
+        F: FnOnce(PageID) + Send + 'static + Clone,
+        C: Fn() -> bool + Send + Sync + 'static + Clone,
+    {
+        let doc = self.native_queue.fetch_uri(uri).await?;
+        self.parse_document(&doc, doc_name, config, page_callback, cancellation_callback)
+            .await
+    }
+
+    /// Parses a document and yields each [`StructuredPage`] the instant its layout pass (or
+    /// page-cache lookup) finishes, rather than waiting for the whole document to complete.
+    /// Lets downstream consumers -- indexers, OCR pipelines, UIs -- start working on early
+    /// pages immediately, and bounds memory to pages currently in flight instead of the full
+    /// document. [`Self::parse_document`] is a thin wrapper that drains this stream, sorts by
+    /// `id`, then runs title clustering and block merging once every page has arrived.
+    ///
+    /// Respects `cancellation_callback` the same way [`Self::parse_document`] does: the stream
+    /// ends with an `Err` item and no further pages are yielded.
+    #[allow(clippy::too_many_arguments)]
+    pub fn parse_document_stream<'a, F, C>(
+        &'a self,
+        doc: &'a [u8],
+        config: FerrulesParseConfig<'a>,
+        page_callback: Option<F>,
+        cancellation_callback: Option<C>,
+    ) -> impl Stream<Item = anyhow::Result<StructuredPage>> + 'a
+    where
+        F: FnOnce(PageID) + Send + 'static + Clone,
+        C: Fn() -> bool + Send + Sync + 'static + Clone,
+    {
+        let FerrulesParseConfig {
+            password,
+            flatten_pdf,
+            page_range,
+            debug_dir,
+            ocr,
+            max_image_pixels,
+            cache,
+            merge: _,
+            reading_order,
+            ligatures,
+            resume_from,
+            #[cfg(feature = "lua")]
+            script,
+        } = config;
+        let page_cache = cache.enabled.then(|| {
+            Arc::clone(
+                self.page_cache
+                    .get_or_init(|| Arc::new(PageCache::new(&cache))),
+            )
+        });
+        self.stream_doc_pages(
+            doc,
+            flatten_pdf,
+            password,
+            page_range,
+            debug_dir,
+            ocr,
+            max_image_pixels,
+            page_cache,
+            reading_order,
+            ligatures,
+            resume_from,
+            None,
+            page_callback,
+            cancellation_callback,
+            #[cfg(feature = "lua")]
+            script,
+        )
+    }
+
+    /// Streaming core shared by [`Self::parse_document`] and [`Self::parse_document_stream`].
+    /// `stats`, when given, is updated in place with page-cache hit/miss counts as pages
+    /// complete; pages resumed directly from a checkpoint snapshot aren't counted either way.
+    #[allow(clippy::too_many_arguments)]
+    #[tracing::instrument(skip_all)]
+    fn stream_doc_pages<'a, F, C>(
+        &'a self,
+        data: &'a [u8],
         flatten_pdf: bool,
-        password: Option<&str>,
+        password: Option<&'a str>,
         page_range: Option<Range<usize>>,
         debug_dir: Option<PathBuf>,
+        ocr_config: OcrConfig,
+        max_image_pixels: u64,
+        page_cache: Option<Arc<PageCache>>,
+        reading_order: ReadingOrderConfig,
+        ligatures: LigatureConfig,
+        resume_from: Option<PathBuf>,
+        stats: Option<Arc<Mutex<PageCacheStats>>>,
         callback: Option<F>,
         cancellation_callback: Option<C>,
-    ) -> anyhow::Result<Vec<StructuredPage>>
+        #[cfg(feature = "lua")] script: Option<Arc<crate::script::ScriptEngine>>,
+    ) -> impl Stream<Item = anyhow::Result<StructuredPage>> + 'a
     where
         F: FnOnce(PageID) + Send + 'static + Clone,
         C: Fn() -> bool + Send + Sync + 'static + Clone,
     {
-        // Check for cancellation before starting
-        if let Some(ref cancel_cb) = cancellation_callback {
-            if cancel_cb() {
-                // Flush layout queue to stop background processing
-                let _ = self.layout_queue.flush().await;
-                return Err(anyhow::anyhow!("Document processing was cancelled"));
-            }
-        }
-
-        let mut set = JoinSet::new();
-        let (native_tx, mut native_rx) = mpsc::channel(32);
-        let req = ParseNativeRequest::new(data, password, flatten_pdf, page_range, native_tx);
-        self.native_queue.push(req).await?;
-
-        while let Some(native_page) = native_rx.recv().await {
-            // Check for cancellation before processing each page
+        try_stream! {
+            // Check for cancellation before starting
             if let Some(ref cancel_cb) = cancellation_callback {
                 if cancel_cb() {
                     // Flush layout queue to stop background processing
                     let _ = self.layout_queue.flush().await;
-                    return Err(anyhow::anyhow!("Document processing was cancelled"));
+                    Err(anyhow::anyhow!("Document processing was cancelled"))?;
                 }
             }
 
-            match native_page {
-                Ok(parse_native_result) => {
-                    let tmp_dir = debug_dir.clone();
-                    let callback = callback.clone();
-                    let cancel_cb_clone = cancellation_callback.clone();
-                    set.spawn(
-                        parse_task(
-                            parse_native_result,
-                            self.layout_queue.clone(),
-                            tmp_dir,
-                            callback,
-                            cancel_cb_clone,
-                        )
-                        .in_current_span(),
-                    );
-                }
-                Err(_) => eprintln!("Error occured parsing page in doc"),
+            let mut checkpoint = resume_from
+                .map(|dir| Checkpoint::open(dir, document_fingerprint(data), page_range.clone()))
+                .transpose()?;
+            let mut already_done: HashMap<PageID, StructuredPage> = checkpoint
+                .as_ref()
+                .map(Checkpoint::load_completed)
+                .unwrap_or_default();
+            let done_ids: std::collections::HashSet<PageID> = already_done.keys().copied().collect();
+
+            for (_, page) in already_done.drain() {
+                yield page;
             }
-        }
 
-        // Get results
-        let mut parsed_pages = Vec::new();
-        while let Some(result) = set.join_next().await {
-            // Check for cancellation while collecting results
-            if let Some(ref cancel_cb) = cancellation_callback {
-                if cancel_cb() {
-                    // Flush layout queue to stop background processing
-                    let _ = self.layout_queue.flush().await;
-                    return Err(anyhow::anyhow!("Document processing was cancelled"));
+            let mut set = JoinSet::new();
+            let (native_tx, mut native_rx) = mpsc::channel(32);
+            let req = ParseNativeRequest::new(
+                data,
+                password,
+                flatten_pdf,
+                page_range,
+                max_image_pixels,
+                native_tx,
+                ligatures,
+            );
+            self.native_queue.push(req).await?;
+
+            while let Some(native_page) = native_rx.recv().await {
+                // Check for cancellation before processing each page
+                if let Some(ref cancel_cb) = cancellation_callback {
+                    if cancel_cb() {
+                        // Flush layout queue to stop background processing
+                        let _ = self.layout_queue.flush().await;
+                        Err(anyhow::anyhow!("Document processing was cancelled"))?;
+                    }
                 }
-            }
 
-            match result {
-                Ok(Ok(page)) => {
-                    parsed_pages.push(page);
+                match native_page {
+                    Ok(parse_native_result) => {
+                        if done_ids.contains(&parse_native_result.page_id) {
+                            // Already checkpointed from a previous, cancelled run of this
+                            // document/page_range -- skip the layout-model pass entirely.
+                            continue;
+                        }
+                        let tmp_dir = debug_dir.clone();
+                        let callback = callback.clone();
+                        let cancel_cb_clone = cancellation_callback.clone();
+                        let page_cache = page_cache.clone();
+                        set.spawn(
+                            parse_task(
+                                parse_native_result,
+                                self.layout_queue.clone(),
+                                tmp_dir,
+                                ocr_config.clone(),
+                                page_cache,
+                                reading_order,
+                                callback,
+                                cancel_cb_clone,
+                                #[cfg(feature = "lua")]
+                                script.clone(),
+                            )
+                            .in_current_span(),
+                        );
+                    }
+                    Err(_) => eprintln!("Error occured parsing page in doc"),
                 }
-                Ok(Err(e)) => {
-                    tracing::error!("Error parsing page : {e:?}")
+            }
+
+            while let Some(result) = set.join_next().await {
+                // Check for cancellation while collecting results. The checkpoint directory
+                // (if any) is left on disk as-is, holding every page completed so far.
+                if let Some(ref cancel_cb) = cancellation_callback {
+                    if cancel_cb() {
+                        // Flush layout queue to stop background processing
+                        let _ = self.layout_queue.flush().await;
+                        Err(anyhow::anyhow!("Document processing was cancelled"))?;
+                    }
                 }
-                Err(e) => {
-                    tracing::error!("Error Joining : {e:?}")
+
+                match result {
+                    Ok(Ok((page, was_hit))) => {
+                        if let Some(stats) = &stats {
+                            let mut stats = stats.lock().unwrap();
+                            if was_hit {
+                                stats.hits += 1;
+                            } else {
+                                stats.misses += 1;
+                            }
+                        }
+                        if let Some(checkpoint) = &mut checkpoint {
+                            if let Err(e) = checkpoint.save_page(&page) {
+                                tracing::error!("Error writing checkpoint for page {}: {e:?}", page.id);
+                            }
+                        }
+                        yield page;
+                    }
+                    Ok(Err(e)) => {
+                        tracing::error!("Error parsing page : {e:?}")
+                    }
+                    Err(e) => {
+                        tracing::error!("Error Joining : {e:?}")
+                    }
                 }
             }
         }
-        parsed_pages.sort_by(|p1, p2| p1.id.cmp(&p2.id));
-        Ok(parsed_pages)
     }
 }
@@ -0,0 +1,140 @@
+use regex::Regex;
+
+use crate::blocks::{Block, BlockType};
+
+/// Matches a `Figure 3`/`Fig. 3` (or lowercase/plural) in-text reference, capturing the number.
+const FIGURE_REFERENCE_PATTERN: &str = r"(?i)\bfig(?:ure)?s?\.?\s*(\d+)\b";
+
+/// Assigns each `Image` block a 1-based `number` in document order, mirroring how a PDF's own
+/// figure captions are usually numbered.
+pub(crate) fn number_figures(blocks: &mut [Block]) {
+    let mut next_number = 1;
+    for block in blocks.iter_mut() {
+        if let BlockType::Image(image) = &mut block.kind {
+            image.number = Some(next_number);
+            next_number += 1;
+        }
+    }
+}
+
+/// Scans every `TextBlock` for "Figure N"/"Fig. N" mentions and records the id of the matching
+/// `Image` block (by [`number_figures`]'s numbering) on [`crate::blocks::TextBlock::references`],
+/// so exporters can emit cross-reference links back to the figure. Must run after
+/// [`number_figures`]; a mention of a number with no matching figure is left unresolved.
+pub(crate) fn link_figure_references(blocks: &mut [Block]) {
+    let figure_re = Regex::new(FIGURE_REFERENCE_PATTERN).unwrap();
+
+    let image_block_id_by_number: std::collections::HashMap<usize, usize> = blocks
+        .iter()
+        .filter_map(|block| match &block.kind {
+            BlockType::Image(image) => Some((image.number?, block.id)),
+            _ => None,
+        })
+        .collect();
+
+    for block in blocks.iter_mut() {
+        let BlockType::TextBlock(text_block) = &mut block.kind else {
+            continue;
+        };
+        for caps in figure_re.captures_iter(&text_block.text) {
+            let Some(number) = caps.get(1).and_then(|m| m.as_str().parse::<usize>().ok()) else {
+                continue;
+            };
+            if let Some(&image_block_id) = image_block_id_by_number.get(&number) {
+                if !text_block.references.contains(&image_block_id) {
+                    text_block.references.push(image_block_id);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocks::{ImageBlock, TextBlock};
+    use crate::entities::BBox;
+
+    fn bbox() -> BBox {
+        BBox {
+            x0: 0.0,
+            y0: 0.0,
+            x1: 10.0,
+            y1: 10.0,
+        }
+    }
+
+    fn image_block(id: usize) -> Block {
+        Block {
+            id,
+            kind: BlockType::Image(ImageBlock {
+                id,
+                alt_text: None,
+                caption: None,
+                native_image: None,
+                number: None,
+            }),
+            pages_id: vec![0],
+            bbox: bbox(),
+        }
+    }
+
+    fn text_block(id: usize, text: &str) -> Block {
+        Block {
+            id,
+            kind: BlockType::TextBlock(TextBlock {
+                text: text.to_owned(),
+                spans: Vec::new(),
+                references: Vec::new(),
+            }),
+            pages_id: vec![0],
+            bbox: bbox(),
+        }
+    }
+
+    #[test]
+    fn numbers_images_in_document_order() {
+        let mut blocks = vec![text_block(0, "intro"), image_block(1), image_block(2)];
+
+        number_figures(&mut blocks);
+
+        let BlockType::Image(first) = &blocks[1].kind else {
+            unreachable!()
+        };
+        let BlockType::Image(second) = &blocks[2].kind else {
+            unreachable!()
+        };
+        assert_eq!(first.number, Some(1));
+        assert_eq!(second.number, Some(2));
+    }
+
+    #[test]
+    fn links_figure_mention_to_matching_image() {
+        let mut blocks = vec![
+            image_block(0),
+            image_block(1),
+            text_block(2, "As shown in Figure 2, the results improve."),
+        ];
+
+        number_figures(&mut blocks);
+        link_figure_references(&mut blocks);
+
+        let BlockType::TextBlock(text) = &blocks[2].kind else {
+            unreachable!()
+        };
+        assert_eq!(text.references, vec![1]);
+    }
+
+    #[test]
+    fn unresolved_mention_is_left_without_a_reference() {
+        let mut blocks = vec![image_block(0), text_block(1, "See Fig. 9 for details.")];
+
+        number_figures(&mut blocks);
+        link_figure_references(&mut blocks);
+
+        let BlockType::TextBlock(text) = &blocks[1].kind else {
+            unreachable!()
+        };
+        assert!(text.references.is_empty());
+    }
+}
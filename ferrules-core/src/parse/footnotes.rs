@@ -0,0 +1,221 @@
+use std::collections::HashMap;
+
+use regex::Regex;
+
+use crate::blocks::{Block, BlockType, Footnote};
+
+/// Matches a `[n]`-style footnote marker.
+const BRACKET_MARKER_PATTERN: &str = r"\[(\d{1,3})\]";
+
+/// Superscript digit characters PDF text layers commonly use for footnote markers, paired with
+/// the ASCII digit each one represents.
+const SUPERSCRIPT_DIGITS: [(char, char); 10] = [
+    ('⁰', '0'),
+    ('¹', '1'),
+    ('²', '2'),
+    ('³', '3'),
+    ('⁴', '4'),
+    ('⁵', '5'),
+    ('⁶', '6'),
+    ('⁷', '7'),
+    ('⁸', '8'),
+    ('⁹', '9'),
+];
+
+/// Scans every `TextBlock` in `blocks` for footnote markers (`[n]` tokens or superscript digit
+/// runs) and pairs them by label with the block that defines them.
+///
+/// A block is treated as a footnote *definition* when its text starts (after trimming) with a
+/// marker; a marker anywhere else in a block's text is treated as an in-text *reference*. Model
+/// follows the reference/definition pairing used by footnote systems in Markdown dialects (e.g.
+/// jotdown): a label with references but no definition, or a definition with no references, is
+/// still returned rather than dropped, so callers can surface the gap as a diagnostic.
+pub(crate) fn link_footnotes(blocks: &[Block]) -> Vec<Footnote> {
+    let bracket_re = Regex::new(BRACKET_MARKER_PATTERN).unwrap();
+
+    let mut footnotes: HashMap<String, Footnote> = HashMap::new();
+    let mut order: Vec<String> = Vec::new();
+
+    let mut footnote_mut = |footnotes: &mut HashMap<String, Footnote>, label: &str| {
+        if !footnotes.contains_key(label) {
+            order.push(label.to_owned());
+            footnotes.insert(
+                label.to_owned(),
+                Footnote {
+                    label: label.to_owned(),
+                    reference_block_ids: Vec::new(),
+                    definition_block_id: None,
+                },
+            );
+        }
+    };
+
+    for block in blocks {
+        let BlockType::TextBlock(text_block) = &block.kind else {
+            continue;
+        };
+        let text = text_block.text.trim();
+        if text.is_empty() {
+            continue;
+        }
+
+        if let Some(label) = definition_label(text, &bracket_re) {
+            footnote_mut(&mut footnotes, &label);
+            footnotes
+                .get_mut(&label)
+                .unwrap()
+                .definition_block_id
+                .get_or_insert(block.id);
+            continue;
+        }
+
+        for label in reference_labels(text, &bracket_re) {
+            footnote_mut(&mut footnotes, &label);
+            let entry = footnotes.get_mut(&label).unwrap();
+            if !entry.reference_block_ids.contains(&block.id) {
+                entry.reference_block_ids.push(block.id);
+            }
+        }
+    }
+
+    order
+        .into_iter()
+        .map(|label| footnotes.remove(&label).unwrap())
+        .collect()
+}
+
+/// If `text` starts with a footnote marker, returns its label.
+fn definition_label(text: &str, bracket_re: &Regex) -> Option<String> {
+    if let Some(caps) = bracket_re.captures(text) {
+        if caps.get(0).unwrap().start() == 0 {
+            return Some(caps.get(1).unwrap().as_str().to_owned());
+        }
+    }
+    let leading_run = superscript_run_at(text, 0);
+    if !leading_run.is_empty() {
+        return Some(leading_run);
+    }
+    None
+}
+
+/// Finds every footnote marker anywhere in `text` and returns its label.
+fn reference_labels(text: &str, bracket_re: &Regex) -> Vec<String> {
+    let mut labels: Vec<String> = bracket_re
+        .captures_iter(text)
+        .filter_map(|c| Some(c.get(1)?.as_str().to_owned()))
+        .collect();
+
+    let mut idx = 0;
+    for (byte_idx, c) in text.char_indices() {
+        if byte_idx < idx {
+            continue;
+        }
+        if to_ascii_digit(c).is_some() {
+            let run = superscript_run_at(text, byte_idx);
+            let consumed_bytes: usize = text[byte_idx..]
+                .chars()
+                .take(run.chars().count())
+                .map(char::len_utf8)
+                .sum();
+            idx = byte_idx + consumed_bytes;
+            labels.push(run);
+        }
+    }
+    labels
+}
+
+fn to_ascii_digit(c: char) -> Option<char> {
+    SUPERSCRIPT_DIGITS
+        .iter()
+        .find(|&&(superscript, _)| superscript == c)
+        .map(|&(_, digit)| digit)
+}
+
+/// Reads the maximal run of superscript digits starting at byte offset `start`, converted to
+/// ASCII. Empty if `start` isn't the beginning of a superscript run.
+fn superscript_run_at(text: &str, start: usize) -> String {
+    text[start..]
+        .chars()
+        .take_while(|&c| to_ascii_digit(c).is_some())
+        .map(|c| to_ascii_digit(c).unwrap())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocks::TextBlock;
+    use crate::entities::BBox;
+
+    fn text_block(id: usize, text: &str) -> Block {
+        Block {
+            id,
+            kind: BlockType::TextBlock(TextBlock {
+                text: text.to_owned(),
+                spans: Vec::new(),
+                references: Vec::new(),
+            }),
+            pages_id: vec![0],
+            bbox: BBox {
+                x0: 0.0,
+                y0: 0.0,
+                x1: 10.0,
+                y1: 10.0,
+            },
+        }
+    }
+
+    #[test]
+    fn pairs_bracket_reference_with_definition() {
+        let blocks = vec![
+            text_block(0, "Some claim that needs support[1]."),
+            text_block(1, "[1] The supporting evidence."),
+        ];
+
+        let footnotes = link_footnotes(&blocks);
+
+        assert_eq!(footnotes.len(), 1);
+        assert_eq!(footnotes[0].label, "1");
+        assert_eq!(footnotes[0].reference_block_ids, vec![0]);
+        assert_eq!(footnotes[0].definition_block_id, Some(1));
+    }
+
+    #[test]
+    fn pairs_superscript_reference_with_definition() {
+        let blocks = vec![
+            text_block(0, "A notable result\u{b9}."),
+            text_block(1, "\u{b9} Explains the result in more detail."),
+        ];
+
+        let footnotes = link_footnotes(&blocks);
+
+        assert_eq!(footnotes.len(), 1);
+        assert_eq!(footnotes[0].label, "1");
+        assert_eq!(footnotes[0].reference_block_ids, vec![0]);
+        assert_eq!(footnotes[0].definition_block_id, Some(1));
+    }
+
+    #[test]
+    fn unmatched_reference_is_kept_without_definition() {
+        let blocks = vec![text_block(0, "A claim with no footnote defined[2].")];
+
+        let footnotes = link_footnotes(&blocks);
+
+        assert_eq!(footnotes.len(), 1);
+        assert_eq!(footnotes[0].label, "2");
+        assert_eq!(footnotes[0].reference_block_ids, vec![0]);
+        assert_eq!(footnotes[0].definition_block_id, None);
+    }
+
+    #[test]
+    fn unmatched_definition_is_kept_without_references() {
+        let blocks = vec![text_block(0, "[3] An orphaned footnote definition.")];
+
+        let footnotes = link_footnotes(&blocks);
+
+        assert_eq!(footnotes.len(), 1);
+        assert_eq!(footnotes[0].label, "3");
+        assert!(footnotes[0].reference_block_ids.is_empty());
+        assert_eq!(footnotes[0].definition_block_id, Some(0));
+    }
+}
@@ -0,0 +1,99 @@
+use image::DynamicImage;
+use pdfium_render::prelude::{PdfPage, PdfPageObject, PdfPageObjectCommon, PdfPageObjectsCommon};
+
+use crate::entities::BBox;
+
+use super::native::check_image_pixel_budget;
+
+/// Minimum fraction of the page's area a placed image XObject must cover to be worth decoding.
+/// Pages routinely embed tiny logos, bullet glyphs, or 1x1 spacer images as XObjects; decoding
+/// every one of those just to throw the pixmap away the moment no figure layout box matches it
+/// would be pure waste, so anything under this is skipped before it's ever touched.
+const MIN_XOBJECT_AREA_RATIO: f32 = 0.01;
+
+/// A PDF image XObject's native-resolution bitmap, already decoded, alongside the bbox it's
+/// placed at on the page. Produced by [`extract_native_images`] inside the same blocking
+/// native-parsing worker that opens the page, since `pdfium_render`'s page/object handles don't
+/// outlive that call -- unlike [`crate::parse::annotations::Annotation`], there's no cheaper
+/// "descriptor now, decode later" split available here without holding the page open across the
+/// native/layout queue boundary, so the one deferral this crate's architecture affords is the
+/// `MIN_XOBJECT_AREA_RATIO` gate above.
+#[derive(Debug)]
+pub(crate) struct NativeImage {
+    pub bbox: BBox,
+    pub image: DynamicImage,
+}
+
+/// Walks `page`'s image XObjects and decodes each one whose placement is large enough to
+/// plausibly be a real figure (see [`MIN_XOBJECT_AREA_RATIO`]) and whose declared bitmap size
+/// fits within `max_image_pixels` (see [`check_image_pixel_budget`]), returning their
+/// native-resolution bitmaps in page-coordinate space (top-left origin, matching [`BBox`]
+/// convention elsewhere in this crate).
+pub(crate) fn extract_native_images(
+    page: &PdfPage,
+    page_width: f32,
+    page_height: f32,
+    max_image_pixels: u64,
+) -> Vec<NativeImage> {
+    let page_area = (page_width * page_height).max(1.0);
+    let mut images = Vec::new();
+
+    for object in page.objects().iter() {
+        let PdfPageObject::Image(image_object) = object else {
+            continue;
+        };
+
+        let Ok(bounds) = image_object.bounds() else {
+            continue;
+        };
+        let bbox = BBox {
+            x0: bounds.left().value,
+            y0: page_height - bounds.top().value,
+            x1: bounds.right().value,
+            y1: page_height - bounds.bottom().value,
+        };
+        if bbox.area() / page_area < MIN_XOBJECT_AREA_RATIO {
+            continue;
+        }
+
+        // Checked against the XObject's own declared bitmap size -- not `bbox`, which is only
+        // where it's *placed* -- since a small placement can still reference a native-resolution
+        // decompression bomb that `get_raw_image()` would decode in full regardless of placement.
+        let Ok(metadata) = image_object.get_raw_metadata(page) else {
+            continue;
+        };
+        if check_image_pixel_budget(
+            metadata.width as f32,
+            metadata.height as f32,
+            max_image_pixels,
+        )
+        .is_err()
+        {
+            continue;
+        }
+
+        let Ok(image) = image_object.get_raw_image() else {
+            continue;
+        };
+
+        images.push(NativeImage { bbox, image });
+    }
+
+    images
+}
+
+/// The [`NativeImage`] whose bbox overlaps `target` the most, if any overlaps at all -- the same
+/// "closest candidate wins" matching [`crate::parse::page::attach_table_grids`] uses for table
+/// cells, applied here to reconcile a layout-detected figure box against the PDF's own image
+/// placements.
+pub(crate) fn best_match<'a>(
+    target: &BBox,
+    candidates: &'a [NativeImage],
+) -> Option<&'a NativeImage> {
+    candidates
+        .iter()
+        .map(|candidate| (candidate, target.intersection(&candidate.bbox)))
+        .filter(|(_, overlap)| *overlap > 0.0)
+        .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+        .map(|(candidate, _)| candidate)
+}
@@ -1,8 +1,10 @@
+use std::collections::HashMap;
+
 use tracing::instrument;
 
 use crate::{
-    blocks::{Block, BlockType, ImageBlock, List, TextBlock, Title},
-    entities::{Element, ElementType, Line, PageID},
+    blocks::{Block, BlockType, ImageBlock, List, Table, TextBlock, Title, TitleLevel},
+    entities::{Element, ElementID, ElementType, Line, PageID},
     layout::model::LayoutBBox,
 };
 
@@ -24,6 +26,234 @@ const LAYOUT_DISTANCE_Y_WEIGHT: f32 = 1.0;
 /// This helps prevent incorrect assignments of text lines that are too far from layout blocks.
 const MAXIMUM_ASSIGNMENT_DISTANCE: f32 = 20.0;
 
+/// Emission-cost penalty added in [`assign_lines_to_layout`] when a line's intersection ratio
+/// with a candidate block is below [`MIN_INTERSECTION_LAYOUT`], so the Viterbi search strongly
+/// prefers an overlapping block and falls back to a merely-nearby one only when nothing overlaps.
+const VITERBI_NO_INTERSECTION_PENALTY: f32 = 200.0;
+
+/// Emission cost for leaving a line unassigned in [`assign_lines_to_layout`]. Plays the role the
+/// old hard [`MAXIMUM_ASSIGNMENT_DISTANCE`] cutoff used to: a line only goes unassigned when
+/// every candidate block, even the nearest one, costs more than this.
+const VITERBI_UNASSIGNED_COST: f32 = MAXIMUM_ASSIGNMENT_DISTANCE;
+
+/// Transition cost in [`assign_lines_to_layout`] for two consecutive lines landing in different
+/// blocks whose relative position is still top-to-bottom/left-to-right consistent.
+const VITERBI_BLOCK_SWITCH_PENALTY: f32 = 2.0;
+
+/// Transition cost in [`assign_lines_to_layout`] for consecutive lines jumping "backward" to a
+/// block that starts both above and to the left of the previous line's block -- the case the old
+/// per-line nearest-block search couldn't rule out, letting a stray header line glue onto a
+/// far-away block (megatrends.pdf).
+const VITERBI_BACKWARD_JUMP_PENALTY: f32 = 1000.0;
+
+/// Slack, in points, before a block-to-block move counts as a "backward" jump rather than a
+/// same-row continuation -- e.g. two side-by-side columns whose tops are almost level.
+const VITERBI_ORDER_EPSILON: f32 = 2.0;
+
+/// How much vertical whitespace is still considered the same paragraph when merging consecutive
+/// `Text` elements in [`merge_elements_into_blocks`], expressed as a multiple of the current
+/// block's line height rather than a raw point value so it scales with font size.
+const MAX_TEXT_GAP_LINE_HEIGHTS: f32 = 1.5;
+
+/// Minimum horizontal overlap (as a fraction of the narrower of the two bboxes' width) required
+/// before two vertically adjacent `Text` elements are considered part of the same paragraph,
+/// used alongside [`MAX_TEXT_GAP_LINE_HEIGHTS`].
+const MIN_TEXT_MERGE_HORIZONTAL_OVERLAP: f32 = 0.5;
+
+/// Max length (in chars) of a derived alt-text string before it's truncated at a word boundary.
+const ALT_TEXT_MAX_LEN: usize = 120;
+
+/// Default maximum vertical gap (in points) between an image/table and a caption/footnote
+/// candidate for [`MergeConfig`] to still consider them associated.
+const DEFAULT_CAPTION_MAX_GAP: f32 = 30.0;
+
+/// Tunables for associating a `Caption`/`FootNote` element with the `Image`/`Table` block it
+/// describes. A caption can sit either directly above or directly below its figure depending on
+/// the document template, so [`merge_elements_into_blocks`] checks both neighbors and uses these
+/// thresholds to gate (and, when both qualify, choose between) them.
+#[derive(Debug, Clone, Copy)]
+pub struct MergeConfig {
+    /// Minimum horizontal overlap (as a fraction of the narrower of the two bboxes' width) the
+    /// candidate must share with the image/table to be considered its caption.
+    pub caption_min_overlap: f32,
+    /// Maximum vertical gap (in points) between the candidate and the image/table for it to
+    /// still count as attached.
+    pub caption_max_gap: f32,
+}
+
+impl Default for MergeConfig {
+    fn default() -> Self {
+        Self {
+            caption_min_overlap: MIN_TEXT_MERGE_HORIZONTAL_OVERLAP,
+            caption_max_gap: DEFAULT_CAPTION_MAX_GAP,
+        }
+    }
+}
+
+/// Assigns an image's `caption` from a candidate element, preferring a real `Caption` over a
+/// `FootNote`: a footnote only fills the caption in if nothing has claimed it yet, so a later
+/// footnote can never silently overwrite an earlier, more authoritative caption.
+fn assign_caption(existing: Option<String>, kind: &ElementType, text: String) -> Option<String> {
+    match kind {
+        ElementType::Caption => Some(text),
+        ElementType::FootNote => existing.or(Some(text)),
+        _ => existing,
+    }
+}
+
+/// Folds a second (or later) caption/footnote candidate onto an already-resolved caption: a
+/// further `Caption` element is assumed to be a continuation (e.g. a multi-line caption layout
+/// analysis split into separate elements) and gets concatenated, while a further `FootNote` only
+/// fills the caption in if nothing has claimed it yet, matching [`assign_caption`]'s policy for
+/// the first candidate.
+fn extend_caption(existing: Option<String>, kind: &ElementType, text: String) -> Option<String> {
+    match (kind, existing) {
+        (ElementType::Caption, Some(mut existing)) => {
+            existing.push(' ');
+            existing.push_str(&text);
+            Some(existing)
+        }
+        (ElementType::Caption, None) => Some(text),
+        (ElementType::FootNote, existing @ Some(_)) => existing,
+        (ElementType::FootNote, None) => Some(text),
+        (_, existing) => existing,
+    }
+}
+
+/// Keeps consuming `Caption`/`FootNote` elements immediately following an image/table (beyond
+/// the one [`resolve_figure_caption`] already resolved) and folds each into `caption` via
+/// [`extend_caption`], so a multi-paragraph caption split across several layout elements ends up
+/// as one block instead of only the first paragraph.
+fn consume_trailing_captions(
+    anchor_bbox: &mut crate::entities::BBox,
+    mut caption: Option<String>,
+    element_it: &mut std::iter::Peekable<std::vec::IntoIter<Element>>,
+    config: &MergeConfig,
+) -> Option<String> {
+    while let Some(next_el) = element_it.peek() {
+        if !matches!(next_el.kind, ElementType::Caption | ElementType::FootNote) {
+            break;
+        }
+        if !caption_geometry_matches(&next_el.bbox, anchor_bbox, config) {
+            break;
+        }
+        let next_el = element_it.next().unwrap();
+        anchor_bbox.merge(&next_el.bbox);
+        caption = extend_caption(caption, &next_el.kind, next_el.text_block.text);
+    }
+    caption
+}
+
+/// Derives a short, single-line alt-text from a figure/table caption: takes the first sentence
+/// (up to the first `.`/`!`/`?`), then truncates it to [`ALT_TEXT_MAX_LEN`] chars at a word
+/// boundary if it's still too long.
+fn derive_alt_text(caption: &str) -> Option<String> {
+    let first_line = caption.lines().next().unwrap_or(caption).trim();
+    if first_line.is_empty() {
+        return None;
+    }
+    let first_sentence = first_line
+        .split_inclusive(['.', '!', '?'])
+        .next()
+        .unwrap_or(first_line)
+        .trim();
+
+    if first_sentence.chars().count() <= ALT_TEXT_MAX_LEN {
+        return Some(first_sentence.to_owned());
+    }
+
+    let truncated: String = first_sentence.chars().take(ALT_TEXT_MAX_LEN).collect();
+    let truncated = truncated
+        .rfind(' ')
+        .map_or(truncated.as_str(), |idx| &truncated[..idx]);
+    Some(format!("{truncated}…"))
+}
+
+/// Fraction of `a`/`b`'s narrower width that the two bboxes overlap horizontally.
+fn horizontal_overlap_ratio(a: &crate::entities::BBox, b: &crate::entities::BBox) -> f32 {
+    let overlap = f32::max(0.0, f32::min(a.x1, b.x1) - f32::max(a.x0, b.x0));
+    let narrowest_width = a.width().min(b.width());
+    if narrowest_width <= 0.0 {
+        0.0
+    } else {
+        overlap / narrowest_width
+    }
+}
+
+/// Local line-height estimate for a pair of vertically adjacent elements being considered for a
+/// merge, matching `entities::median`'s even-length convention (the lower of the two middle
+/// values, i.e. the smaller of the two here) so a short caption-like line can't inflate the
+/// threshold and swallow a much taller neighbor.
+fn median_line_height(a: f32, b: f32) -> f32 {
+    a.min(b)
+}
+
+/// Vertical distance between two bboxes that sit one above the other; `0.0` if they already
+/// overlap vertically.
+fn vertical_gap(a: &crate::entities::BBox, b: &crate::entities::BBox) -> f32 {
+    if b.y0 >= a.y1 {
+        b.y0 - a.y1
+    } else if a.y0 >= b.y1 {
+        a.y0 - b.y1
+    } else {
+        0.0
+    }
+}
+
+/// Whether `candidate` is aligned and close enough to `anchor` (an image/table bbox) to be
+/// treated as its caption/footnote, per `config`'s thresholds.
+fn caption_geometry_matches(
+    anchor: &crate::entities::BBox,
+    candidate: &crate::entities::BBox,
+    config: &MergeConfig,
+) -> bool {
+    horizontal_overlap_ratio(anchor, candidate) >= config.caption_min_overlap
+        && vertical_gap(anchor, candidate) <= config.caption_max_gap
+}
+
+/// Resolves which caption/footnote candidate to attach to an image/table: the lingering
+/// `preceding` element (if it geometrically qualifies) or the upcoming element in `element_it`
+/// (if it's itself a `Caption`/`FootNote` that qualifies). When both qualify, the closer one
+/// wins; the chosen candidate's bbox is folded into `anchor_bbox`, and the upcoming element is
+/// only consumed from `element_it` when it's the one picked.
+#[allow(clippy::type_complexity)]
+fn resolve_figure_caption(
+    anchor_bbox: &mut crate::entities::BBox,
+    preceding: Option<(crate::entities::ElementType, crate::entities::BBox, String)>,
+    element_it: &mut std::iter::Peekable<std::vec::IntoIter<Element>>,
+    config: &MergeConfig,
+) -> Option<(crate::entities::ElementType, String)> {
+    let preceding =
+        preceding.filter(|(_, bbox, _)| caption_geometry_matches(anchor_bbox, bbox, config));
+    let preceding_gap = preceding
+        .as_ref()
+        .map(|(_, bbox, _)| vertical_gap(anchor_bbox, bbox));
+
+    let following_gap = element_it.peek().and_then(|next_el| {
+        matches!(next_el.kind, ElementType::Caption | ElementType::FootNote)
+            .then(|| next_el)
+            .filter(|next_el| caption_geometry_matches(anchor_bbox, &next_el.bbox, config))
+            .map(|next_el| vertical_gap(anchor_bbox, &next_el.bbox))
+    });
+
+    let prefer_following = match (preceding_gap, following_gap) {
+        (Some(p), Some(f)) => f < p,
+        (None, Some(_)) => true,
+        _ => false,
+    };
+
+    if prefer_following {
+        let next_el = element_it.next().unwrap();
+        anchor_bbox.merge(&next_el.bbox);
+        Some((next_el.kind, next_el.text_block.text))
+    } else {
+        preceding.map(|(kind, bbox, text)| {
+            anchor_bbox.merge(&bbox);
+            (kind, text)
+        })
+    }
+}
+
 fn merge_or_create_elements(
     elements: &mut Vec<Element>,
     line: &Line,
@@ -53,69 +283,128 @@ fn merge_or_create_elements(
         }
     }
 }
+/// Cost of assigning `line` to `block`: the weighted bbox distance, plus
+/// [`VITERBI_NO_INTERSECTION_PENALTY`] when the line barely or doesn't overlap the block.
+fn viterbi_emission_cost(line: &Line, block: &LayoutBBox) -> f32 {
+    let distance = block.bbox.distance(
+        &line.bbox,
+        LAYOUT_DISTANCE_X_WEIGHT,
+        LAYOUT_DISTANCE_Y_WEIGHT,
+    );
+    let intersection_ratio = block.bbox.intersection(&line.bbox) / line.bbox.area();
+    if intersection_ratio > MIN_INTERSECTION_LAYOUT {
+        distance
+    } else {
+        distance + VITERBI_NO_INTERSECTION_PENALTY
+    }
+}
+
+/// Cost of moving from `prev`'s block to `next`'s block between two consecutive lines: free
+/// when they're the same block, [`VITERBI_BLOCK_SWITCH_PENALTY`] for a forward/same-row move,
+/// [`VITERBI_BACKWARD_JUMP_PENALTY`] for a move that goes both up and to the left.
+fn viterbi_transition_cost(prev: &LayoutBBox, next: &LayoutBBox) -> f32 {
+    if prev.id == next.id {
+        return 0.0;
+    }
+    let consistent_order = next.bbox.y0 >= prev.bbox.y0 - VITERBI_ORDER_EPSILON
+        || next.bbox.x0 >= prev.bbox.x0 - VITERBI_ORDER_EPSILON;
+    if consistent_order {
+        VITERBI_BLOCK_SWITCH_PENALTY
+    } else {
+        VITERBI_BACKWARD_JUMP_PENALTY
+    }
+}
+
+/// Assigns each line (in reading order) to the layout block minimizing total cost across the
+/// *whole* sequence, via Viterbi dynamic programming over the candidate blocks (plus a
+/// "leave unassigned" sentinel state): `D[i][b] = E(i,b) + min_over_bprev(D[i-1][bprev] +
+/// T(bprev,b))`. Unlike a per-line nearest-block search, a single line's slightly-closer-but-
+/// out-of-sequence match can no longer outweigh staying in a globally consistent reading order.
+fn assign_lines_to_layout<'a>(
+    layout_boxes: &'a [LayoutBBox],
+    lines: &[Line],
+) -> Vec<Option<&'a LayoutBBox>> {
+    if lines.is_empty() {
+        return Vec::new();
+    }
+    if layout_boxes.is_empty() {
+        return vec![None; lines.len()];
+    }
+
+    let unassigned = layout_boxes.len();
+    let num_states = layout_boxes.len() + 1;
+    let emission = |line: &Line, state: usize| -> f32 {
+        if state == unassigned {
+            VITERBI_UNASSIGNED_COST
+        } else {
+            viterbi_emission_cost(line, &layout_boxes[state])
+        }
+    };
+    let transition = |prev_state: usize, state: usize| -> f32 {
+        match (prev_state == unassigned, state == unassigned) {
+            (true, true) => 0.0,
+            (true, false) | (false, true) => VITERBI_BLOCK_SWITCH_PENALTY,
+            (false, false) => {
+                viterbi_transition_cost(&layout_boxes[prev_state], &layout_boxes[state])
+            }
+        }
+    };
+
+    let mut cost = vec![vec![0f32; num_states]; lines.len()];
+    let mut backptr = vec![vec![0usize; num_states]; lines.len()];
+
+    for state in 0..num_states {
+        cost[0][state] = emission(&lines[0], state);
+    }
+
+    for i in 1..lines.len() {
+        for state in 0..num_states {
+            let (best_prev, best_prev_cost) = (0..num_states)
+                .map(|prev_state| {
+                    (
+                        prev_state,
+                        cost[i - 1][prev_state] + transition(prev_state, state),
+                    )
+                })
+                .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+                .unwrap();
+            cost[i][state] = best_prev_cost + emission(&lines[i], state);
+            backptr[i][state] = best_prev;
+        }
+    }
+
+    let last = lines.len() - 1;
+    let mut state = (0..num_states)
+        .min_by(|&a, &b| cost[last][a].partial_cmp(&cost[last][b]).unwrap())
+        .unwrap();
+
+    let mut states = vec![unassigned; lines.len()];
+    states[last] = state;
+    for i in (1..lines.len()).rev() {
+        state = backptr[i][state];
+        states[i - 1] = state;
+    }
+
+    states
+        .into_iter()
+        .map(|state| (state != unassigned).then(|| &layout_boxes[state]))
+        .collect()
+}
+
 /// Merges lines into blocks based on their layout, maintaining the order of lines.
 ///
 /// This function takes a list of text boxes representing layout bounding boxes that contain text,
 /// and a list of lines (which could be obtained from OCR or  PDF library pdfium2,
-/// and merges these lines into blocks. The merging is done based on the intersection
-/// of each line with the layout bounding boxes. The function prioritizes maintaining
-/// the order of the lines, rather than the layout blocks.
+/// and merges these lines into blocks. The merging is done with [`assign_lines_to_layout`]'s
+/// Viterbi search over the line sequence, rather than matching each line independently, so the
+/// function prioritizes maintaining the order of the lines, rather than the layout blocks.
 pub(crate) fn merge_lines_layout(
     layout_boxes: &[LayoutBBox],
     lines: &[Line],
     page_id: usize,
 ) -> anyhow::Result<Vec<Element>> {
-    let line_block_iterator = lines.iter().map(|line| {
-        // TODO: the max here is sometimes very far away from the line.
-        // ex: megatrends.pdf, header is categorized as text-block but the intersection  happens
-        //
-        // Get max intersection block for the line
-        let max_intersection_bbox = layout_boxes.iter().max_by(|a, b| {
-            let a_intersection = a.bbox.intersection(&line.bbox);
-            let b_intersection = b.bbox.intersection(&line.bbox);
-
-            a_intersection.partial_cmp(&b_intersection).unwrap()
-        });
-        // Get min distance block for the line
-        let min_distance_block = layout_boxes.iter().min_by(|a, b| {
-            let a_intersection = a.bbox.distance(
-                &line.bbox,
-                LAYOUT_DISTANCE_X_WEIGHT,
-                LAYOUT_DISTANCE_Y_WEIGHT,
-            );
-            let b_intersection = b.bbox.distance(
-                &line.bbox,
-                LAYOUT_DISTANCE_X_WEIGHT,
-                LAYOUT_DISTANCE_Y_WEIGHT,
-            );
-            a_intersection.partial_cmp(&b_intersection).unwrap()
-        });
-        let max_intersection_bbox = max_intersection_bbox.and_then(|b| {
-            if line.bbox.intersection(&b.bbox) / line.bbox.area() > MIN_INTERSECTION_LAYOUT {
-                Some(b)
-            } else {
-                None
-            }
-        });
-        // Compare based on distance
-        let matched_block = if max_intersection_bbox.is_none() {
-            min_distance_block.and_then(|b| {
-                if b.bbox.distance(
-                    &line.bbox,
-                    LAYOUT_DISTANCE_X_WEIGHT,
-                    LAYOUT_DISTANCE_Y_WEIGHT,
-                ) < MAXIMUM_ASSIGNMENT_DISTANCE
-                {
-                    Some(b)
-                } else {
-                    None
-                }
-            })
-        } else {
-            max_intersection_bbox
-        };
-        (line, matched_block)
-    });
+    let matched_blocks = assign_lines_to_layout(layout_boxes, lines);
+    let line_block_iterator = lines.iter().zip(matched_blocks);
 
     let mut headers = Vec::new();
     let mut elements = Vec::new();
@@ -189,42 +478,139 @@ pub(crate) fn merge_remaining(
     }
 }
 
-#[instrument(skip_all)]
-pub(crate) fn merge_elements_into_blocks(elements: Vec<Element>) -> anyhow::Result<Vec<Block>> {
-    let mut element_it = elements.into_iter().peekable();
+/// What one [`BlockMerger::step`] call produced for the element(s) it consumed.
+enum MergeStep {
+    /// The underlying element iterator is exhausted; the merger is done.
+    Done,
+    /// The element(s) consumed didn't produce a block on their own (e.g. an element whose kind
+    /// isn't handled by any arm), so the caller should call `step` again.
+    Skip,
+    /// One finished block, ready to yield.
+    Block(Block),
+}
+
+/// Lazily merges a page's [`Element`]s into [`Block`]s one at a time, so a caller that only
+/// wants to stream output (e.g. a `DocumentWriter` with `buffered() == false`) never has to hold
+/// the whole page's blocks in memory at once. [`merge_elements_into_blocks`] is just a
+/// `collect()` over this iterator.
+///
+/// `ferrules-core`'s `Block`s are flat (a page's blocks don't nest into a tree), so unlike e.g.
+/// jotdown's `Event::Start`/`Event::End` pairs, each item yielded here is simply one finished
+/// `Block`.
+pub(crate) struct BlockMerger<'a> {
+    element_it: std::iter::Peekable<std::vec::IntoIter<Element>>,
+    title_levels: HashMap<(PageID, ElementID), TitleLevel>,
+    config: &'a MergeConfig,
+    #[cfg(feature = "lua")]
+    script: Option<&'a crate::script::ScriptEngine>,
+    block_id: usize,
+    // A caption/footnote that just fell through to plain text because it wasn't immediately
+    // followed by an image/table: the image/table that comes right after it (if any) still gets
+    // a chance to adopt it as a preceding caption, see [`resolve_figure_caption`].
+    last_caption_candidate: Option<(ElementType, crate::entities::BBox, String)>,
+}
+
+impl<'a> BlockMerger<'a> {
+    pub(crate) fn new(
+        elements: Vec<Element>,
+        title_levels: HashMap<(PageID, ElementID), TitleLevel>,
+        config: &'a MergeConfig,
+        #[cfg(feature = "lua")] script: Option<&'a crate::script::ScriptEngine>,
+    ) -> Self {
+        Self {
+            element_it: elements.into_iter().peekable(),
+            title_levels,
+            config,
+            #[cfg(feature = "lua")]
+            script,
+            block_id: 0,
+            last_caption_candidate: None,
+        }
+    }
+
+    fn step(&mut self) -> anyhow::Result<MergeStep> {
+        let config = self.config;
+        let title_levels = &self.title_levels;
+        let element_it = &mut self.element_it;
+        let block_id = &mut self.block_id;
+        let last_caption_candidate = &mut self.last_caption_candidate;
+
+        let Some(mut curr_el) = element_it.next() else {
+            return Ok(MergeStep::Done);
+        };
+
+        // By this point every line belonging to `curr_el` has already been pushed (see
+        // `merge_lines_layout`/`Element::push_line`), so this is the first point with both a
+        // final `kind` and final text -- the natural place for the `classify_element` Lua hook
+        // (see [`crate::script`]) to potentially override the layout-label-derived kind.
+        #[cfg(feature = "lua")]
+        if let Some(script) = self.script {
+            if let Some(reclassified) =
+                crate::entities::ElementType::from_name(&script.classify_element(
+                    curr_el.kind.name(),
+                    &curr_el.text_block.text,
+                    &curr_el.bbox,
+                ))
+            {
+                curr_el.kind = reclassified;
+            }
+        }
 
-    let mut blocks = Vec::new();
-    let mut block_id = 0;
-    while let Some(mut curr_el) = element_it.next() {
-        match &mut curr_el.kind {
+        // Only relevant if `curr_el` is the `Image`/`Table` immediately following an orphaned
+        // caption; taking it here means it's gone by the next iteration either way, so a
+        // later, unrelated figure can never adopt a stale candidate.
+        let preceding_caption_candidate = last_caption_candidate.take();
+        let block = match &mut curr_el.kind {
             crate::entities::ElementType::Text => {
-                let text_block = Block {
-                    id: block_id,
+                let mut last_el_height = curr_el.bbox.height();
+                let mut text_block = Block {
+                    id: *block_id,
                     kind: crate::blocks::BlockType::TextBlock(TextBlock {
                         text: curr_el.text_block.text,
+                        spans: curr_el.spans,
+                        references: Vec::new(),
                     }),
                     pages_id: vec![curr_el.page_id],
                     bbox: curr_el.bbox,
                 };
-                // TODO : Change this to use some minimum gap
-                // Check to see if we have another text block that is close
-                // while let Some(next_el) = element_it.peek() {
-                //     if matches!(next_el.kind, crate::entities::ElementType::Text(_))
-                //         && (curr_el.bbox.distance(&next_el.bbox, 1.0, 1.0)
-                //             < MAXIMUM_ASSIGNMENT_DISTANCE)
-                //     {
-                //         text_block.merge(next_el)?;
-                //         element_it.next();
-                //     } else {
-                //         break;
-                //     }
-                // }
-                block_id += 1;
-                blocks.push(text_block);
+
+                // Merge in following Text elements that sit close enough below the current
+                // block to plausibly be the same paragraph that layout analysis over-split:
+                // the vertical gap must be within a few line heights, and the two elements
+                // must overlap horizontally (so two unrelated columns don't get glued). The
+                // line-height estimate comes from the two elements actually being compared
+                // (via `median_line_height`), not the merged block's bbox, so it doesn't drift
+                // upward as more lines accumulate into `text_block`.
+                while let Some(next_el) = element_it.peek() {
+                    if !matches!(next_el.kind, crate::entities::ElementType::Text) {
+                        break;
+                    }
+
+                    let line_height = median_line_height(last_el_height, next_el.bbox.height());
+                    let gap = next_el.bbox.y0 - text_block.bbox.y1;
+                    let horizontal_overlap =
+                        horizontal_overlap_ratio(&text_block.bbox, &next_el.bbox);
+
+                    if gap > line_height * MAX_TEXT_GAP_LINE_HEIGHTS
+                        || horizontal_overlap < MIN_TEXT_MERGE_HORIZONTAL_OVERLAP
+                    {
+                        break;
+                    }
+
+                    let next_el = element_it.next().unwrap();
+                    last_el_height = next_el.bbox.height();
+                    if !text_block.pages_id.contains(&next_el.page_id) {
+                        text_block.pages_id.push(next_el.page_id);
+                    }
+                    text_block.merge(next_el)?;
+                }
+
+                *block_id += 1;
+                text_block
             }
             crate::entities::ElementType::ListItem => {
                 let mut list_block = Block {
-                    id: block_id,
+                    id: *block_id,
                     kind: BlockType::ListBlock(List {
                         items: vec![curr_el.text_block.text],
                     }),
@@ -241,27 +627,30 @@ pub(crate) fn merge_elements_into_blocks(elements: Vec<Element>) -> anyhow::Resu
                         break;
                     }
                 }
-                block_id += 1;
-                blocks.push(list_block);
+                *block_id += 1;
+                list_block
             }
             ElementType::FootNote | ElementType::Caption => {
-                // We find the closest image and create and image block
+                // We find the closest image/table and create the corresponding block, gated on
+                // `config`'s overlap/gap thresholds so a caption on an unrelated figure further
+                // down the page doesn't get glued on.
                 loop {
                     match element_it.peek() {
                         None => {
                             // last element -> transform to txt block and break
                             let text_block = Block {
-                                id: block_id,
+                                id: *block_id,
                                 kind: crate::blocks::BlockType::TextBlock(TextBlock {
                                     text: curr_el.text_block.text,
+                                    spans: curr_el.spans,
+                                    references: Vec::new(),
                                 }),
                                 pages_id: vec![curr_el.page_id],
                                 bbox: curr_el.bbox,
                             };
                             element_it.next();
-                            block_id += 1;
-                            blocks.push(text_block);
-                            break;
+                            *block_id += 1;
+                            break text_block;
                         }
                         Some(next_el) => {
                             match &next_el.kind {
@@ -269,36 +658,94 @@ pub(crate) fn merge_elements_into_blocks(elements: Vec<Element>) -> anyhow::Resu
                                 | crate::entities::ElementType::Caption => {
                                     // Merge this with a the caption
                                     curr_el.text_block.append_line(&next_el.text_block.text);
+                                    let mut next_spans = next_el.spans.clone();
+                                    if let Some(first) = next_spans.first_mut() {
+                                        first.text.insert(0, ' ');
+                                    }
+                                    crate::entities::append_collapsing_spans(
+                                        &mut curr_el.spans,
+                                        next_spans,
+                                    );
                                     element_it.next();
                                 }
-                                crate::entities::ElementType::Image => {
+                                crate::entities::ElementType::Image
+                                    if caption_geometry_matches(
+                                        &next_el.bbox,
+                                        &curr_el.bbox,
+                                        config,
+                                    ) =>
+                                {
+                                    let native_image = next_el.native_image.clone();
                                     curr_el.bbox.merge(&next_el.bbox);
+                                    let caption = assign_caption(
+                                        None,
+                                        &curr_el.kind,
+                                        curr_el.text_block.text,
+                                    );
+                                    let alt_text = caption.as_deref().and_then(derive_alt_text);
                                     let img_block = Block {
-                                        id: block_id,
+                                        id: *block_id,
                                         kind: BlockType::Image(ImageBlock {
-                                            caption: Some(curr_el.text_block.text),
+                                            id: *block_id,
+                                            alt_text,
+                                            caption,
+                                            native_image,
+                                            number: None,
                                         }),
                                         pages_id: vec![next_el.page_id],
                                         bbox: curr_el.bbox,
                                     };
-                                    block_id += 1;
-                                    blocks.push(img_block);
+                                    *block_id += 1;
                                     element_it.next();
-                                    break;
+                                    break img_block;
+                                }
+                                crate::entities::ElementType::Table
+                                    if caption_geometry_matches(
+                                        &next_el.bbox,
+                                        &curr_el.bbox,
+                                        config,
+                                    ) =>
+                                {
+                                    let next_el = element_it.next().unwrap();
+                                    curr_el.bbox.merge(&next_el.bbox);
+                                    let caption = assign_caption(
+                                        None,
+                                        &curr_el.kind,
+                                        curr_el.text_block.text,
+                                    );
+                                    let table_block = Block {
+                                        id: *block_id,
+                                        kind: BlockType::Table(Table {
+                                            rows: next_el.table_rows.unwrap_or_default(),
+                                            caption,
+                                        }),
+                                        pages_id: vec![next_el.page_id],
+                                        bbox: curr_el.bbox,
+                                    };
+                                    *block_id += 1;
+                                    break table_block;
                                 }
                                 _ => {
-                                    // This caption isn't associated with Image/Table, transform to textblock
+                                    // Not close enough (or not a figure at all): surface as plain
+                                    // text, but remember it so a figure immediately after it can
+                                    // still adopt it as a preceding caption.
+                                    *last_caption_candidate = Some((
+                                        curr_el.kind.clone(),
+                                        curr_el.bbox.clone(),
+                                        curr_el.text_block.text.clone(),
+                                    ));
                                     let text_block = Block {
-                                        id: block_id,
+                                        id: *block_id,
                                         kind: crate::blocks::BlockType::TextBlock(TextBlock {
                                             text: curr_el.text_block.text,
+                                            spans: curr_el.spans,
+                                            references: Vec::new(),
                                         }),
                                         pages_id: vec![curr_el.page_id],
                                         bbox: curr_el.bbox,
                                     };
-                                    block_id += 1;
-                                    blocks.push(text_block);
-                                    break;
+                                    *block_id += 1;
+                                    break text_block;
                                 }
                             }
                         }
@@ -306,58 +753,38 @@ pub(crate) fn merge_elements_into_blocks(elements: Vec<Element>) -> anyhow::Resu
                 }
             }
             crate::entities::ElementType::Image => {
-                match element_it.peek() {
-                    None => {
-                        // last element -> transform to txt block and break
-                        let block = Block {
-                            id: block_id,
-                            kind: crate::blocks::BlockType::Image(ImageBlock { caption: None }),
-                            pages_id: vec![curr_el.page_id],
-                            bbox: curr_el.bbox,
-                        };
-                        element_it.next();
-                        block_id += 1;
-                        blocks.push(block);
-                    }
-                    Some(next_el) => {
-                        match &next_el.kind {
-                            crate::entities::ElementType::FootNote
-                            | crate::entities::ElementType::Caption => {
-                                // TODO: check if there is a case where there is multiple caption associated with the same image
-                                let next_el = element_it.next().unwrap();
-                                curr_el.bbox.merge(&next_el.bbox);
-                                let block = Block {
-                                    id: block_id,
-                                    kind: crate::blocks::BlockType::Image(ImageBlock {
-                                        caption: Some(next_el.text_block.text),
-                                    }),
-                                    pages_id: vec![curr_el.page_id],
-                                    bbox: curr_el.bbox,
-                                };
-                                block_id += 1;
-                                blocks.push(block);
-                            }
-                            _ => {
-                                let block = Block {
-                                    id: block_id,
-                                    kind: crate::blocks::BlockType::Image(ImageBlock {
-                                        caption: None,
-                                    }),
-                                    pages_id: vec![curr_el.page_id],
-                                    bbox: curr_el.bbox,
-                                };
-                                block_id += 1;
-                                blocks.push(block);
-                            }
-                        }
-                    }
-                }
+                let resolved = resolve_figure_caption(
+                    &mut curr_el.bbox,
+                    preceding_caption_candidate,
+                    element_it,
+                    config,
+                );
+                let caption = resolved.and_then(|(kind, text)| assign_caption(None, &kind, text));
+                let caption =
+                    consume_trailing_captions(&mut curr_el.bbox, caption, element_it, config);
+                let alt_text = caption.as_deref().and_then(derive_alt_text);
+                let block = Block {
+                    id: *block_id,
+                    kind: crate::blocks::BlockType::Image(ImageBlock {
+                        id: *block_id,
+                        alt_text,
+                        caption,
+                        native_image: curr_el.native_image,
+                        number: None,
+                    }),
+                    pages_id: vec![curr_el.page_id],
+                    bbox: curr_el.bbox,
+                };
+                *block_id += 1;
+                block
             }
             ElementType::Header => {
                 let mut header_block = Block {
-                    id: block_id,
+                    id: *block_id,
                     kind: BlockType::Header(TextBlock {
                         text: curr_el.text_block.text,
+                        spans: curr_el.spans,
+                        references: Vec::new(),
                     }),
                     pages_id: vec![curr_el.page_id],
                     bbox: curr_el.bbox,
@@ -371,14 +798,16 @@ pub(crate) fn merge_elements_into_blocks(elements: Vec<Element>) -> anyhow::Resu
                         break;
                     }
                 }
-                block_id += 1;
-                blocks.push(header_block);
+                *block_id += 1;
+                header_block
             }
             ElementType::Footer => {
                 let mut footer_block = Block {
-                    id: block_id,
+                    id: *block_id,
                     kind: BlockType::Footer(TextBlock {
                         text: curr_el.text_block.text,
+                        spans: curr_el.spans,
+                        references: Vec::new(),
                     }),
                     pages_id: vec![curr_el.page_id],
                     bbox: curr_el.bbox,
@@ -392,30 +821,82 @@ pub(crate) fn merge_elements_into_blocks(elements: Vec<Element>) -> anyhow::Resu
                         break;
                     }
                 }
-                block_id += 1;
-                blocks.push(footer_block);
+                *block_id += 1;
+                footer_block
             }
             ElementType::Title | ElementType::Subtitle => {
-                // TODO:
-                // Handle title level via text font size (using kmeans)
+                let level = title_levels
+                    .get(&(curr_el.page_id, curr_el.id))
+                    .copied()
+                    .unwrap_or(0);
                 let title = Block {
-                    id: block_id,
+                    id: *block_id,
                     kind: BlockType::Title(Title {
-                        level: 0,
+                        level,
                         text: curr_el.text_block.text,
                     }),
                     pages_id: vec![curr_el.page_id],
                     bbox: curr_el.bbox,
                 };
-                block_id += 1;
-                blocks.push(title);
+                *block_id += 1;
+                title
             }
-            _ => {
-                continue;
+            ElementType::Table => {
+                let rows = curr_el.table_rows.take().unwrap_or_default();
+                let resolved = resolve_figure_caption(
+                    &mut curr_el.bbox,
+                    preceding_caption_candidate,
+                    element_it,
+                    config,
+                );
+                let caption = resolved.and_then(|(kind, text)| assign_caption(None, &kind, text));
+                let caption =
+                    consume_trailing_captions(&mut curr_el.bbox, caption, element_it, config);
+                let table = Block {
+                    id: *block_id,
+                    kind: BlockType::Table(Table { rows, caption }),
+                    pages_id: vec![curr_el.page_id],
+                    bbox: curr_el.bbox,
+                };
+                *block_id += 1;
+                table
+            }
+            _ => return Ok(MergeStep::Skip),
+        };
+        Ok(MergeStep::Block(block))
+    }
+}
+
+impl Iterator for BlockMerger<'_> {
+    type Item = anyhow::Result<Block>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.step() {
+                Ok(MergeStep::Done) => return None,
+                Ok(MergeStep::Skip) => continue,
+                Ok(MergeStep::Block(block)) => return Some(Ok(block)),
+                Err(err) => return Some(Err(err)),
             }
         }
     }
-    Ok(blocks)
+}
+
+#[instrument(skip_all)]
+pub(crate) fn merge_elements_into_blocks(
+    elements: Vec<Element>,
+    title_levels: HashMap<(PageID, ElementID), TitleLevel>,
+    config: &MergeConfig,
+    #[cfg(feature = "lua")] script: Option<&crate::script::ScriptEngine>,
+) -> anyhow::Result<Vec<Block>> {
+    BlockMerger::new(
+        elements,
+        title_levels,
+        config,
+        #[cfg(feature = "lua")]
+        script,
+    )
+    .collect()
 }
 
 #[cfg(test)]
@@ -435,6 +916,11 @@ mod tests {
             },
             page_id,
             bbox,
+            table_rows: None,
+            line_font_sizes: Vec::new(),
+            spans: Vec::new(),
+            order: 0,
+            native_image: None,
         }
     }
 
@@ -448,6 +934,11 @@ mod tests {
             },
             page_id,
             bbox,
+            table_rows: None,
+            line_font_sizes: Vec::new(),
+            spans: Vec::new(),
+            order: 0,
+            native_image: None,
         }
     }
 
@@ -461,6 +952,11 @@ mod tests {
             },
             page_id,
             bbox,
+            table_rows: None,
+            line_font_sizes: Vec::new(),
+            spans: Vec::new(),
+            order: 0,
+            native_image: None,
         }
     }
 
@@ -474,6 +970,11 @@ mod tests {
             },
             page_id,
             bbox,
+            table_rows: None,
+            line_font_sizes: Vec::new(),
+            spans: Vec::new(),
+            order: 0,
+            native_image: None,
         }
     }
     fn create_image_element(id: usize, page_id: usize, bbox: BBox) -> Element {
@@ -484,6 +985,27 @@ mod tests {
             text_block: ElementText::default(),
             page_id,
             bbox,
+            table_rows: None,
+            line_font_sizes: Vec::new(),
+            spans: Vec::new(),
+            order: 0,
+            native_image: None,
+        }
+    }
+
+    fn create_table_element(id: usize, page_id: usize, bbox: BBox) -> Element {
+        Element {
+            id,
+            layout_block_id: 0,
+            kind: ElementType::Table,
+            text_block: ElementText::default(),
+            page_id,
+            bbox,
+            table_rows: Some(vec![vec!["cell".to_string()]]),
+            line_font_sizes: Vec::new(),
+            spans: Vec::new(),
+            order: 0,
+            native_image: None,
         }
     }
 
@@ -507,7 +1029,7 @@ mod tests {
             create_text_element(1, 1, "Second paragraph", bbox2),
         ];
 
-        let blocks = merge_elements_into_blocks(elements)?;
+        let blocks = merge_elements_into_blocks(elements, HashMap::new(), &MergeConfig::default())?;
 
         assert_eq!(blocks.len(), 1);
         if let BlockType::TextBlock(text) = &blocks[0].kind {
@@ -540,7 +1062,7 @@ mod tests {
             create_text_element(2, 1, "Random text", bbox2),
         ];
 
-        let blocks = merge_elements_into_blocks(elements)?;
+        let blocks = merge_elements_into_blocks(elements, HashMap::new(), &MergeConfig::default())?;
 
         assert_eq!(blocks.len(), 2);
         if let BlockType::ListBlock(list) = &blocks[0].kind {
@@ -573,7 +1095,7 @@ mod tests {
             create_image_element(1, 1, image_bbox),
         ];
 
-        let blocks = merge_elements_into_blocks(elements)?;
+        let blocks = merge_elements_into_blocks(elements, HashMap::new(), &MergeConfig::default())?;
 
         assert_eq!(blocks.len(), 1);
         if let BlockType::Image(image) = &blocks[0].kind {
@@ -595,7 +1117,7 @@ mod tests {
 
         let elements = vec![create_caption_element(0, 1, "Orphan caption", caption_bbox)];
 
-        let blocks = merge_elements_into_blocks(elements)?;
+        let blocks = merge_elements_into_blocks(elements, HashMap::new(), &MergeConfig::default())?;
 
         assert_eq!(blocks.len(), 1);
         if let BlockType::TextBlock(text) = &blocks[0].kind {
@@ -626,7 +1148,7 @@ mod tests {
             create_text_element(1, 1, "Distant paragraph", bbox2),
         ];
 
-        let blocks = merge_elements_into_blocks(elements)?;
+        let blocks = merge_elements_into_blocks(elements, HashMap::new(), &MergeConfig::default())?;
 
         assert_eq!(blocks.len(), 2);
         Ok(())
@@ -643,7 +1165,7 @@ mod tests {
 
         let elements = vec![create_image_element(0, 1, image_bbox)];
 
-        let blocks = merge_elements_into_blocks(elements)?;
+        let blocks = merge_elements_into_blocks(elements, HashMap::new(), &MergeConfig::default())?;
 
         assert_eq!(blocks.len(), 1);
         if let BlockType::Image(image) = &blocks[0].kind {
@@ -674,7 +1196,7 @@ mod tests {
             create_caption_element(1, 1, "Image Description", caption_bbox),
         ];
 
-        let blocks = merge_elements_into_blocks(elements)?;
+        let blocks = merge_elements_into_blocks(elements, HashMap::new(), &MergeConfig::default())?;
 
         assert_eq!(blocks.len(), 1);
         if let BlockType::Image(image) = &blocks[0].kind {
@@ -705,7 +1227,7 @@ mod tests {
             create_text_element(1, 1, "Regular text", text_bbox),
         ];
 
-        let blocks = merge_elements_into_blocks(elements)?;
+        let blocks = merge_elements_into_blocks(elements, HashMap::new(), &MergeConfig::default())?;
 
         assert_eq!(blocks.len(), 2);
         if let BlockType::Image(image) = &blocks[0].kind {
@@ -742,7 +1264,7 @@ mod tests {
             create_footnote_element(1, 1, "Image Footnote", footnote_bbox),
         ];
 
-        let blocks = merge_elements_into_blocks(elements)?;
+        let blocks = merge_elements_into_blocks(elements, HashMap::new(), &MergeConfig::default())?;
 
         assert_eq!(blocks.len(), 1);
         if let BlockType::Image(image) = &blocks[0].kind {
@@ -752,4 +1274,316 @@ mod tests {
         }
         Ok(())
     }
+
+    #[test]
+    fn test_image_alt_text_derived_from_caption_first_sentence() -> anyhow::Result<()> {
+        let caption_bbox = BBox {
+            x0: 0.0,
+            y0: 0.0,
+            x1: 2.0,
+            y1: 2.0,
+        };
+        let image_bbox = BBox {
+            x0: 0.0,
+            y0: 2.1,
+            x1: 2.0,
+            y1: 4.1,
+        };
+
+        let elements = vec![
+            create_caption_element(
+                0,
+                1,
+                "Figure 1. A detailed diagram of the pipeline.",
+                caption_bbox,
+            ),
+            create_image_element(1, 1, image_bbox),
+        ];
+
+        let blocks = merge_elements_into_blocks(elements, HashMap::new(), &MergeConfig::default())?;
+
+        assert_eq!(blocks.len(), 1);
+        if let BlockType::Image(image) = &blocks[0].kind {
+            assert_eq!(image.alt_text, Some("Figure 1.".to_string()));
+        } else {
+            panic!("Expected Image");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_image_footnote_does_not_overwrite_existing_caption() {
+        assert_eq!(
+            assign_caption(
+                Some("Real caption".to_string()),
+                &ElementType::FootNote,
+                "Footnote text".to_string(),
+            ),
+            Some("Real caption".to_string())
+        );
+        assert_eq!(
+            assign_caption(None, &ElementType::Caption, "New caption".to_string()),
+            Some("New caption".to_string())
+        );
+    }
+
+    #[test]
+    fn test_caption_too_far_above_image_is_not_attached() -> anyhow::Result<()> {
+        let caption_bbox = BBox {
+            x0: 0.0,
+            y0: 0.0,
+            x1: 2.0,
+            y1: 2.0,
+        };
+        let image_bbox = BBox {
+            x0: 0.0,
+            y0: 2.0 + DEFAULT_CAPTION_MAX_GAP + 1.0,
+            x1: 2.0,
+            y1: 4.0 + DEFAULT_CAPTION_MAX_GAP + 1.0,
+        };
+
+        let elements = vec![
+            create_caption_element(0, 1, "Unrelated caption", caption_bbox),
+            create_image_element(1, 1, image_bbox),
+        ];
+
+        let blocks = merge_elements_into_blocks(elements, HashMap::new(), &MergeConfig::default())?;
+
+        assert_eq!(blocks.len(), 2);
+        if let BlockType::TextBlock(text) = &blocks[0].kind {
+            assert_eq!(text.text, "Unrelated caption");
+        } else {
+            panic!("Expected TextBlock");
+        }
+        if let BlockType::Image(image) = &blocks[1].kind {
+            assert_eq!(image.caption, None);
+        } else {
+            panic!("Expected Image");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_caption_prefers_closer_of_preceding_and_following() -> anyhow::Result<()> {
+        let preceding_bbox = BBox {
+            x0: 0.0,
+            y0: 0.0,
+            x1: 2.0,
+            y1: 1.0,
+        };
+        let image_bbox = BBox {
+            x0: 0.0,
+            y0: 5.0,
+            x1: 2.0,
+            y1: 7.0,
+        };
+        let following_bbox = BBox {
+            x0: 0.0,
+            y0: 7.1,
+            x1: 2.0,
+            y1: 8.1,
+        };
+
+        let elements = vec![
+            create_caption_element(0, 1, "Far caption", preceding_bbox),
+            create_image_element(1, 1, image_bbox),
+            create_caption_element(2, 1, "Close caption", following_bbox),
+        ];
+
+        let blocks = merge_elements_into_blocks(elements, HashMap::new(), &MergeConfig::default())?;
+
+        assert_eq!(blocks.len(), 2);
+        if let BlockType::TextBlock(text) = &blocks[0].kind {
+            assert_eq!(text.text, "Far caption");
+        } else {
+            panic!("Expected TextBlock");
+        }
+        if let BlockType::Image(image) = &blocks[1].kind {
+            assert_eq!(image.caption, Some("Close caption".to_string()));
+        } else {
+            panic!("Expected Image");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge_table_with_preceding_caption() -> anyhow::Result<()> {
+        let caption_bbox = BBox {
+            x0: 0.0,
+            y0: 0.0,
+            x1: 2.0,
+            y1: 2.0,
+        };
+        let table_bbox = BBox {
+            x0: 0.0,
+            y0: 2.1,
+            x1: 2.0,
+            y1: 4.1,
+        };
+
+        let elements = vec![
+            create_caption_element(0, 1, "Table 1. Results overview.", caption_bbox),
+            create_table_element(1, 1, table_bbox),
+        ];
+
+        let blocks = merge_elements_into_blocks(elements, HashMap::new(), &MergeConfig::default())?;
+
+        assert_eq!(blocks.len(), 1);
+        if let BlockType::Table(table) = &blocks[0].kind {
+            assert_eq!(
+                table.caption,
+                Some("Table 1. Results overview.".to_string())
+            );
+        } else {
+            panic!("Expected Table");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge_table_with_following_caption() -> anyhow::Result<()> {
+        let table_bbox = BBox {
+            x0: 0.0,
+            y0: 0.0,
+            x1: 2.0,
+            y1: 2.0,
+        };
+        let caption_bbox = BBox {
+            x0: 0.0,
+            y0: 2.1,
+            x1: 2.0,
+            y1: 4.1,
+        };
+
+        let elements = vec![
+            create_table_element(0, 1, table_bbox),
+            create_caption_element(1, 1, "Table 1. Results overview.", caption_bbox),
+        ];
+
+        let blocks = merge_elements_into_blocks(elements, HashMap::new(), &MergeConfig::default())?;
+
+        assert_eq!(blocks.len(), 1);
+        if let BlockType::Table(table) = &blocks[0].kind {
+            assert_eq!(
+                table.caption,
+                Some("Table 1. Results overview.".to_string())
+            );
+        } else {
+            panic!("Expected Table");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge_config_custom_thresholds_reject_low_overlap_caption() -> anyhow::Result<()> {
+        let image_bbox = BBox {
+            x0: 0.0,
+            y0: 0.0,
+            x1: 10.0,
+            y1: 2.0,
+        };
+        // Only a sliver of horizontal overlap with the image.
+        let caption_bbox = BBox {
+            x0: 9.0,
+            y0: 2.1,
+            x1: 11.0,
+            y1: 3.1,
+        };
+
+        let elements = vec![
+            create_image_element(0, 1, image_bbox),
+            create_caption_element(1, 1, "Off to the side", caption_bbox),
+        ];
+
+        let config = MergeConfig {
+            caption_min_overlap: 0.9,
+            ..MergeConfig::default()
+        };
+        let blocks = merge_elements_into_blocks(elements, HashMap::new(), &config)?;
+
+        assert_eq!(blocks.len(), 2);
+        if let BlockType::Image(image) = &blocks[0].kind {
+            assert_eq!(image.caption, None);
+        } else {
+            panic!("Expected Image");
+        }
+        Ok(())
+    }
+
+    fn bbox(x0: f32, y0: f32, x1: f32, y1: f32) -> BBox {
+        BBox { x0, y0, x1, y1 }
+    }
+
+    fn layout_box(id: i32, bbox: BBox) -> LayoutBBox {
+        LayoutBBox {
+            id,
+            bbox,
+            label: "Text",
+            proba: 1.0,
+        }
+    }
+
+    fn line(text: &str, bbox: BBox) -> Line {
+        Line {
+            text: text.to_owned(),
+            bbox,
+            rotation: 0.0,
+            spans: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn assign_lines_to_layout_refuses_a_backward_jump_onto_a_closer_block() {
+        // block_a sits up and to the left of block_b; two lines settle the sequence into
+        // block_b, then a third line drifts into block_a's territory -- geometrically the
+        // nearest block by far, but reaching it from block_b is a backward jump. The Viterbi
+        // penalty should refuse that jump (leaving the line unassigned) rather than glue it
+        // onto block_a the way a per-line nearest-block match would.
+        let block_a = layout_box(0, bbox(0.0, 0.0, 10.0, 10.0));
+        let block_b = layout_box(1, bbox(5.0, 5.0, 15.0, 15.0));
+        let layout_boxes = vec![block_a, block_b];
+
+        let lines = vec![
+            line("b1", bbox(9.0, 9.0, 11.0, 11.0)),
+            line("b2", bbox(8.5, 8.5, 10.5, 10.5)),
+            line("stray", bbox(1.0, 1.0, 3.0, 3.0)),
+        ];
+
+        let assigned = assign_lines_to_layout(&layout_boxes, &lines);
+        let ids: Vec<_> = assigned.iter().map(|b| b.map(|b| b.id)).collect();
+        assert_eq!(ids, vec![Some(1), Some(1), None]);
+    }
+
+    #[test]
+    fn assign_lines_to_layout_switches_block_when_reading_order_is_consistent() {
+        // A short column followed by lines that clearly belong to a block below it -- a
+        // forward/consistent-order move, so it should take the cheap block-switch cost rather
+        // than sticking to the first block.
+        let top = layout_box(0, bbox(0.0, 0.0, 10.0, 10.0));
+        let bottom = layout_box(1, bbox(0.0, 20.0, 10.0, 30.0));
+        let layout_boxes = vec![top, bottom];
+
+        let lines = vec![
+            line("t1", bbox(0.0, 0.0, 10.0, 5.0)),
+            line("b1", bbox(0.0, 20.0, 10.0, 25.0)),
+            line("b2", bbox(0.0, 26.0, 10.0, 30.0)),
+        ];
+
+        let assigned = assign_lines_to_layout(&layout_boxes, &lines);
+        let ids: Vec<_> = assigned.iter().map(|b| b.map(|b| b.id)).collect();
+        assert_eq!(ids, vec![Some(0), Some(1), Some(1)]);
+    }
+
+    #[test]
+    fn assign_lines_to_layout_prefers_unassigned_over_a_non_intersecting_block() {
+        // The only candidate block is far enough away that it doesn't intersect the line at all,
+        // so the no-intersection penalty should push the line to the "unassigned" state instead.
+        let far_block = layout_box(0, bbox(500.0, 500.0, 510.0, 510.0));
+        let layout_boxes = vec![far_block];
+
+        let lines = vec![line("stray", bbox(0.0, 0.0, 10.0, 5.0))];
+
+        let assigned = assign_lines_to_layout(&layout_boxes, &lines);
+        assert_eq!(assigned, vec![None]);
+    }
 }
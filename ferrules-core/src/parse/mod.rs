@@ -0,0 +1,13 @@
+mod annotations;
+pub mod document;
+mod figures;
+mod footnotes;
+mod images;
+pub(crate) mod merge;
+mod native;
+pub(crate) mod outline;
+mod page;
+pub(crate) mod reading_order;
+mod stitch;
+mod table;
+mod titles;
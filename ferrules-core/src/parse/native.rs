@@ -1,34 +1,72 @@
-use std::{ops::Range, sync::Arc, time::Instant};
+use std::{ops::Range, path::Path, sync::Arc, time::Instant};
 
 use anyhow::Context;
 use image::DynamicImage;
+use memmap2::Mmap;
 use pdfium_render::prelude::{PdfPage, PdfPageTextChar, PdfRenderConfig, Pdfium};
 use tracing::{instrument, Span};
 
 use crate::{
-    entities::{BBox, CharSpan, Line, PageID},
+    cancellation::ParseHandle,
+    entities::{
+        page_rotation_matrix, Annotation, BBox, CharSpan, LigatureConfig, Line, OutlineEntry,
+        PageID,
+    },
     layout::model::ORTLayoutParser,
+    resource::{DefaultProvider, ResourceProvider},
 };
+
+use super::annotations::parse_page_annotations;
+use super::images::{extract_native_images, NativeImage};
+use super::outline::parse_document_outline;
 use tokio::sync::mpsc::{self, Receiver, Sender};
+use tokio_util::sync::CancellationToken;
 
 const MAX_CONCURRENT_NATIVE_REQS: usize = 10;
 
+/// Hard ceiling on how far a page's native render factor is ever pushed past its native
+/// (1 PDF point == 1 pixel) resolution. Guards against a degenerate, near-zero-size MediaBox
+/// driving `required_raster_width` / `required_raster_height`'s rescale factor to an absurd
+/// upscale before the pixel-budget check below even gets a chance to run.
+const MAX_RENDER_SCALE_FACTOR: f32 = 8.0;
+
+/// Refuses to rasterize a page (or decode an image XObject, via [`super::images::extract_native_images`])
+/// whose native (`scale_page_by_factor(1.0)`) pixel count would exceed `max_image_pixels`, before
+/// any render buffer is allocated. A crafted PDF can declare a MediaBox -- or embed an image XObject
+/// -- of essentially unbounded size; without this check, decoding it would try to allocate a
+/// gigapixel bitmap and OOM-kill whatever process is parsing it.
+pub(crate) fn check_image_pixel_budget(
+    width: f32,
+    height: f32,
+    max_image_pixels: u64,
+) -> anyhow::Result<()> {
+    let pixel_count = (width.max(0.0) as u64).saturating_mul(height.max(0.0) as u64);
+    if pixel_count > max_image_pixels {
+        anyhow::bail!(
+            "dimensions {width}x{height} ({pixel_count} px) exceed the configured \
+             max_image_pixels limit of {max_image_pixels}; refusing to decode"
+        )
+    }
+    Ok(())
+}
+
 pub(crate) fn parse_text_spans<'a>(
     chars: impl Iterator<Item = PdfPageTextChar<'a>>,
     page_bbox: &BBox,
+    ligatures: &LigatureConfig,
 ) -> Vec<CharSpan> {
     let mut spans: Vec<CharSpan> = Vec::new();
 
     for char in chars {
         if spans.is_empty() {
-            let span = CharSpan::new_from_char(&char, page_bbox);
+            let span = CharSpan::new_from_char(&char, page_bbox, ligatures);
             spans.push(span);
         } else {
             let span = spans.last_mut().unwrap();
-            match span.append(&char, page_bbox) {
+            match span.append(&char, page_bbox, ligatures) {
                 Some(_) => {}
                 None => {
-                    let span = CharSpan::new_from_char(&char, page_bbox);
+                    let span = CharSpan::new_from_char(&char, page_bbox, ligatures);
                     spans.push(span);
                 }
             };
@@ -38,7 +76,7 @@ pub(crate) fn parse_text_spans<'a>(
     spans
 }
 
-pub(crate) fn parse_text_lines(spans: Vec<CharSpan>) -> Vec<Line> {
+pub(crate) fn parse_text_lines(spans: Vec<CharSpan>, ligatures: &LigatureConfig) -> Vec<Line> {
     let mut lines = Vec::new();
     for span in spans {
         if lines.is_empty() {
@@ -46,7 +84,7 @@ pub(crate) fn parse_text_lines(spans: Vec<CharSpan>) -> Vec<Line> {
             lines.push(line);
         } else {
             let line = lines.last_mut().unwrap();
-            if let Err(span) = line.append(span) {
+            if let Err(span) = line.append(span, ligatures) {
                 let line = Line::new_from_span(span);
                 lines.push(line)
             }
@@ -56,15 +94,49 @@ pub(crate) fn parse_text_lines(spans: Vec<CharSpan>) -> Vec<Line> {
     lines
 }
 
+/// Where a [`ParseNativeRequest`]'s document bytes live. Both variants deref to `&[u8]` so
+/// `handle_parse_native_req` can hand either straight to `pdfium.load_pdf_from_byte_slice`
+/// without caring which one it got.
+#[derive(Clone)]
+pub enum DocSource {
+    /// Bytes already resident on the heap (e.g. from `tokio::fs::read` or a network body).
+    InMemory(Arc<[u8]>),
+    /// A memory-mapped file region. Avoids copying multi-hundred-MB PDFs into `Arc<[u8]>`
+    /// just to hand them to pdfium, which only ever reads lazily from the slice.
+    Mapped(Arc<Mmap>),
+}
+
+impl std::ops::Deref for DocSource {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            DocSource::InMemory(data) => data,
+            DocSource::Mapped(mmap) => mmap,
+        }
+    }
+}
+
 pub struct ParseNativeRequest {
-    pub doc_data: std::sync::Arc<[u8]>,
+    pub doc_data: DocSource,
     pub password: Option<String>,
     pub flatten: bool,
     pub page_range: Option<Range<usize>>,
     pub required_raster_width: u32,
     pub required_raster_height: u32,
+    pub max_image_pixels: u64,
     pub sender_tx: Sender<anyhow::Result<ParseNativePageResult>>,
     pub count_only: bool,
+    /// When set, skips rasterization/text-extraction entirely and returns just the document's
+    /// outline (see [`super::outline::parse_document_outline`]), the same way `count_only` short
+    /// circuits to just the page count.
+    pub outline_only: bool,
+    /// Checked before rasterizing each page; cancelling it stops the request from burning
+    /// any more CPU on pages a disconnected client no longer wants. See [`ParseHandle`].
+    pub cancellation_token: CancellationToken,
+    /// Ligature/glyph-recovery tunables applied while decoding this request's text. See
+    /// [`LigatureConfig`].
+    pub ligatures: LigatureConfig,
 }
 impl ParseNativeRequest {
     pub fn new(
@@ -72,35 +144,103 @@ impl ParseNativeRequest {
         password: Option<&str>,
         flatten: bool,
         page_range: Option<Range<usize>>,
+        max_image_pixels: u64,
         sender_tx: Sender<anyhow::Result<ParseNativePageResult>>,
+        ligatures: LigatureConfig,
     ) -> Self {
         ParseNativeRequest {
-            doc_data: Arc::from(data),
+            doc_data: DocSource::InMemory(Arc::from(data)),
             password: password.map(|p| p.to_string()),
             flatten,
             page_range,
             // TODO: should be global?
             required_raster_width: ORTLayoutParser::REQUIRED_WIDTH,
             required_raster_height: ORTLayoutParser::REQUIRED_HEIGHT,
+            max_image_pixels,
             sender_tx,
             count_only: false,
+            outline_only: false,
+            cancellation_token: CancellationToken::new(),
+            ligatures,
         }
     }
 
+    /// Like [`Self::new`], but memory-maps `path` instead of copying it into an `Arc<[u8]>`.
+    /// The mapping is kept alive for the lifetime of the request (and thus for the whole of
+    /// `handle_parse_native_req`), since pdfium only reads from the slice lazily as it
+    /// rasterizes each page.
+    pub fn from_path(
+        path: impl AsRef<Path>,
+        password: Option<&str>,
+        flatten: bool,
+        page_range: Option<Range<usize>>,
+        max_image_pixels: u64,
+        sender_tx: Sender<anyhow::Result<ParseNativePageResult>>,
+        ligatures: LigatureConfig,
+    ) -> anyhow::Result<Self> {
+        let file = std::fs::File::open(path.as_ref())
+            .with_context(|| format!("can't open {}", path.as_ref().display()))?;
+        // Safe as long as nothing else truncates the file out from under the mapping while
+        // this request is in flight, which holds for the job-scoped input files this is used
+        // for (see `ferrules-api`'s job store).
+        let mmap = unsafe { Mmap::map(&file) }
+            .with_context(|| format!("can't mmap {}", path.as_ref().display()))?;
+
+        Ok(ParseNativeRequest {
+            doc_data: DocSource::Mapped(Arc::new(mmap)),
+            password: password.map(|p| p.to_string()),
+            flatten,
+            page_range,
+            required_raster_width: ORTLayoutParser::REQUIRED_WIDTH,
+            required_raster_height: ORTLayoutParser::REQUIRED_HEIGHT,
+            max_image_pixels,
+            sender_tx,
+            count_only: false,
+            outline_only: false,
+            cancellation_token: CancellationToken::new(),
+            ligatures,
+        })
+    }
+
     pub fn new_count_only(
         data: &[u8],
         password: Option<&str>,
         sender_tx: Sender<anyhow::Result<ParseNativePageResult>>,
     ) -> Self {
         ParseNativeRequest {
-            doc_data: Arc::from(data),
+            doc_data: DocSource::InMemory(Arc::from(data)),
             password: password.map(|p| p.to_string()),
             flatten: false,            // Not needed for counting
             page_range: None,          // Count all pages
             required_raster_width: 0,  // Not needed for counting
             required_raster_height: 0, // Not needed for counting
+            max_image_pixels: crate::parse::document::DEFAULT_MAX_IMAGE_PIXELS,
             sender_tx,
             count_only: true,
+            outline_only: false,
+            cancellation_token: CancellationToken::new(),
+            ligatures: LigatureConfig::default(), // Not needed for counting
+        }
+    }
+
+    pub fn new_outline_only(
+        data: &[u8],
+        password: Option<&str>,
+        sender_tx: Sender<anyhow::Result<ParseNativePageResult>>,
+    ) -> Self {
+        ParseNativeRequest {
+            doc_data: DocSource::InMemory(Arc::from(data)),
+            password: password.map(|p| p.to_string()),
+            flatten: false,            // Not needed to read the outline
+            page_range: None,          // Outline isn't scoped to a page range
+            required_raster_width: 0,  // Not needed to read the outline
+            required_raster_height: 0, // Not needed to read the outline
+            max_image_pixels: crate::parse::document::DEFAULT_MAX_IMAGE_PIXELS,
+            sender_tx,
+            count_only: false,
+            outline_only: true,
+            cancellation_token: CancellationToken::new(),
+            ligatures: LigatureConfig::default(), // Not needed to read the outline
         }
     }
 }
@@ -112,21 +252,27 @@ pub struct ParseNativeMetadata {
 
 #[derive(Debug)]
 pub struct ParseNativePageResult {
-    // TODO: page_native_rotation
     pub page_id: PageID,
     pub text_lines: Vec<Line>,
+    pub annotations: Vec<Annotation>,
+    pub native_images: Vec<NativeImage>,
     pub page_bbox: BBox,
+    /// Clockwise page rotation in degrees (0/90/180/270), as declared by the PDF page itself.
+    pub page_rotation: f32,
     pub page_image: Arc<DynamicImage>,
     pub page_image_scale1: DynamicImage,
     pub downscale_factor: f32,
     pub metadata: ParseNativeMetadata,
     pub is_count_result: bool,
     pub total_page_count: Option<usize>,
+    pub is_outline_result: bool,
+    pub outline: Vec<OutlineEntry>,
 }
 
 #[derive(Debug, Clone)]
 pub struct ParseNativeQueue {
     queue: Sender<(ParseNativeRequest, Span)>,
+    provider: Arc<dyn ResourceProvider>,
 }
 
 impl Default for ParseNativeQueue {
@@ -137,23 +283,70 @@ impl Default for ParseNativeQueue {
 
 impl ParseNativeQueue {
     pub fn new() -> Self {
+        Self::with_provider(Arc::new(DefaultProvider::default()))
+    }
+
+    /// Like [`Self::new`], but resolves `push_uri` requests through a caller-supplied
+    /// provider instead of the default file/HTTP dispatch -- e.g. to pull documents
+    /// straight out of an object store.
+    pub fn with_provider(provider: Arc<dyn ResourceProvider>) -> Self {
         let (queue_sender, queue_receiver) = mpsc::channel(MAX_CONCURRENT_NATIVE_REQS);
 
         tokio::task::spawn_blocking(move || start_native_parser(queue_receiver));
         Self {
             queue: queue_sender,
+            provider,
         }
     }
 
-    pub(crate) async fn push(&self, req: ParseNativeRequest) -> anyhow::Result<()> {
+    pub(crate) async fn push(&self, req: ParseNativeRequest) -> anyhow::Result<ParseHandle> {
         let span = Span::current();
+        let handle = ParseHandle::new(req.cancellation_token.clone());
         self.queue
             .send((req, span))
             .await
-            .context("error sending parse native request")
+            .context("error sending parse native request")?;
+        Ok(handle)
+    }
+
+    /// Resolves `uri` through the configured [`ResourceProvider`].
+    pub(crate) async fn fetch_uri(&self, uri: &str) -> anyhow::Result<bytes::Bytes> {
+        self.provider
+            .fetch(uri)
+            .await
+            .with_context(|| format!("can't resolve document at {uri}"))
+    }
+
+    /// Resolves `uri` through the configured [`ResourceProvider`] on the async side, then
+    /// builds and enqueues a [`ParseNativeRequest`] from the resulting bytes. Lets a caller
+    /// queue a parse straight from an S3/HTTP URL without staging it to disk first; the
+    /// blocking pdfium worker itself is unaware of where the bytes came from.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn push_uri(
+        &self,
+        uri: &str,
+        password: Option<&str>,
+        flatten: bool,
+        page_range: Option<Range<usize>>,
+        max_image_pixels: u64,
+        sender_tx: Sender<anyhow::Result<ParseNativePageResult>>,
+        ligatures: LigatureConfig,
+    ) -> anyhow::Result<ParseHandle> {
+        let data = self.fetch_uri(uri).await?;
+        let req = ParseNativeRequest::new(
+            &data,
+            password,
+            flatten,
+            page_range,
+            max_image_pixels,
+            sender_tx,
+            ligatures,
+        );
+        self.push(req).await
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 #[instrument(skip(page))]
 pub(crate) fn parse_page_native(
     page_id: PageID,
@@ -161,24 +354,38 @@ pub(crate) fn parse_page_native(
     flatten_page: bool,
     required_raster_width: u32,
     required_raster_height: u32,
+    max_image_pixels: u64,
+    ligatures: &LigatureConfig,
 ) -> anyhow::Result<ParseNativePageResult> {
     let start_time = Instant::now();
     if flatten_page {
         page.flatten()?;
     }
-    let rescale_factor = {
-        let scale_w = required_raster_width as f32 / page.width().value;
-        let scale_h = required_raster_height as f32 / page.height().value;
-        f32::min(scale_h, scale_w)
-    };
-    let downscale_factor = 1f32 / rescale_factor;
+    check_image_pixel_budget(page.width().value, page.height().value, max_image_pixels)?;
 
-    let page_bbox = BBox {
+    // Pdfium reports char/annotation/object bounds in the page's raw, un-rotated coordinate
+    // space (just the bottom-left-origin MediaBox flipped to top-left by `page_height`), but
+    // `render_with_config` rasterizes the page upright, honoring its declared `/Rotate` entry.
+    // `rotation_matrix` remaps every bbox built in that raw space into the same upright space
+    // the rendered image -- and therefore `parse_layout_batch`/OCR -- actually operate in.
+    let raw_page_bbox = BBox {
         x0: 0f32,
         y0: 0f32,
         x1: page.width().value,
         y1: page.height().value,
     };
+    let page_rotation = page.rotation().map(|r| r.as_degrees()).unwrap_or(0.0);
+    let rotation_matrix =
+        page_rotation_matrix(page_rotation, raw_page_bbox.width(), raw_page_bbox.height());
+    let page_bbox = raw_page_bbox.transform(&rotation_matrix);
+
+    let rescale_factor = {
+        let scale_w = required_raster_width as f32 / page_bbox.width();
+        let scale_h = required_raster_height as f32 / page_bbox.height();
+        f32::min(scale_h, scale_w).min(MAX_RENDER_SCALE_FACTOR)
+    };
+    let downscale_factor = 1f32 / rescale_factor;
+
     let page_image = page
         .render_with_config(&PdfRenderConfig::default().scale_page_by_factor(rescale_factor))
         .map(|bitmap| bitmap.as_image())?;
@@ -187,20 +394,59 @@ pub(crate) fn parse_page_native(
         .render_with_config(&PdfRenderConfig::default().scale_page_by_factor(1f32))
         .map(|bitmap| bitmap.as_image())?;
 
-    let text_spans = parse_text_spans(page.text()?.chars().iter(), &page_bbox);
+    let text_spans = parse_text_spans(page.text()?.chars().iter(), &raw_page_bbox, ligatures);
+    let raw_text_lines = parse_text_lines(text_spans, ligatures);
+
+    // Annotations/native images are matched against `raw_text_lines`/pdfium object bounds in
+    // the same raw coordinate space they were computed in, then rotated into the upright space
+    // afterward -- rotating first would desync them from each other before the match runs.
+    let annotations =
+        parse_page_annotations(page, raw_page_bbox.height(), &raw_text_lines, page_id)
+            .into_iter()
+            .map(|mut annotation| {
+                annotation.bbox = annotation.bbox.transform(&rotation_matrix);
+                annotation
+            })
+            .collect();
+    let native_images = extract_native_images(
+        page,
+        raw_page_bbox.width(),
+        raw_page_bbox.height(),
+        max_image_pixels,
+    )
+    .into_iter()
+    .map(|mut image| {
+        image.bbox = image.bbox.transform(&rotation_matrix);
+        image
+    })
+    .collect();
 
-    let text_lines = parse_text_lines(text_spans);
+    let text_lines = raw_text_lines
+        .into_iter()
+        .map(|mut line| {
+            line.bbox = line.bbox.transform(&rotation_matrix);
+            for span in &mut line.spans {
+                span.bbox = span.bbox.transform(&rotation_matrix);
+            }
+            line
+        })
+        .collect();
 
-    let parse_native_duration_ms = start_time.elapsed().as_millis();
+    let parse_native_elapsed = start_time.elapsed();
+    let parse_native_duration_ms = parse_native_elapsed.as_millis();
     tracing::debug!(
         "Parsing page {} using pdfium took {}ms",
         page_id,
         parse_native_duration_ms
     );
+    crate::metrics::record_stage_latency("native_parse", parse_native_elapsed);
     Ok(ParseNativePageResult {
         page_id,
         text_lines,
+        annotations,
+        native_images,
         page_bbox,
+        page_rotation,
         page_image: Arc::new(page_image),
         page_image_scale1,
         downscale_factor,
@@ -209,6 +455,8 @@ pub(crate) fn parse_page_native(
         },
         is_count_result: false,
         total_page_count: None,
+        is_outline_result: false,
+        outline: Vec::new(),
     })
 }
 
@@ -226,10 +474,47 @@ fn handle_parse_native_req(
         page_range,
         required_raster_width,
         required_raster_height,
+        max_image_pixels,
         sender_tx,
         count_only,
+        outline_only,
+        cancellation_token,
+        ligatures,
     } = req;
     let mut document = pdfium.load_pdf_from_byte_slice(&doc_data, password.as_deref())?;
+
+    // If only reading the outline, skip page iteration entirely and return it alone.
+    if outline_only {
+        use image::{DynamicImage, ImageBuffer};
+        let dummy_image = DynamicImage::ImageRgb8(ImageBuffer::new(1, 1));
+
+        let outline_result = ParseNativePageResult {
+            page_id: 0,
+            text_lines: Vec::new(),
+            annotations: Vec::new(),
+            native_images: Vec::new(),
+            page_bbox: crate::entities::BBox {
+                x0: 0.0,
+                y0: 0.0,
+                x1: 0.0,
+                y1: 0.0,
+            },
+            page_rotation: 0.0,
+            page_image: Arc::new(dummy_image.clone()),
+            page_image_scale1: dummy_image,
+            downscale_factor: 1.0,
+            metadata: ParseNativeMetadata {
+                parse_native_duration_ms: 0,
+            },
+            is_count_result: false,
+            total_page_count: None,
+            is_outline_result: true,
+            outline: parse_document_outline(&document),
+        };
+        sender_tx.blocking_send(Ok(outline_result))?;
+        return Ok(());
+    }
+
     let mut pages: Vec<_> = document.pages_mut().iter().enumerate().collect();
 
     // If only counting pages, send the count and return early
@@ -241,12 +526,15 @@ fn handle_parse_native_req(
         let count_result = ParseNativePageResult {
             page_id: 0,
             text_lines: Vec::new(),
+            annotations: Vec::new(),
+            native_images: Vec::new(),
             page_bbox: crate::entities::BBox {
                 x0: 0.0,
                 y0: 0.0,
                 x1: 0.0,
                 y1: 0.0,
             },
+            page_rotation: 0.0,
             page_image: Arc::new(dummy_image.clone()),
             page_image_scale1: dummy_image,
             downscale_factor: 1.0,
@@ -255,34 +543,197 @@ fn handle_parse_native_req(
             },
             is_count_result: true,
             total_page_count: Some(total_pages),
+            is_outline_result: false,
+            outline: Vec::new(),
         };
         sender_tx.blocking_send(Ok(count_result))?;
         return Ok(());
     }
 
-    let pages = if let Some(range) = page_range {
-        if range.end > pages.len() {
-            anyhow::bail!(
-                "Page range end ({}) exceeds document length ({})",
-                range.end,
-                pages.len()
-            )
+    let total_pages = pages.len();
+    // `pages`/`document` borrow `pdfium`, which this function's caller reuses across requests --
+    // drop them before fanning the page range out to worker threads, each of which opens its own
+    // `Pdfium` binding below.
+    drop(pages);
+    drop(document);
+
+    let selected_range = match page_range {
+        Some(range) => {
+            if range.end > total_pages {
+                anyhow::bail!(
+                    "Page range end ({}) exceeds document length ({})",
+                    range.end,
+                    total_pages
+                )
+            }
+            range
         }
-        pages.drain(range).collect()
-    } else {
-        pages
+        None => 0..total_pages,
     };
-    for (page_id, mut page) in pages {
-        let parsing_result = parse_page_native(
-            page_id,
-            &mut page,
-            flatten,
-            required_raster_width,
-            required_raster_height,
+
+    let n_workers = std::thread::available_parallelism()
+        .map(|c| c.get())
+        .unwrap_or(4);
+
+    let first_err = std::thread::scope(|scope| {
+        let handles: Vec<_> = doc_chunks(selected_range, n_workers)
+            .into_iter()
+            .map(|chunk| {
+                let doc_data = doc_data.clone();
+                let password = password.clone();
+                let ligatures = ligatures.clone();
+                let sender_tx = sender_tx.clone();
+                let cancellation_token = cancellation_token.clone();
+                let parent_span = parent_span.clone();
+                scope.spawn(move || {
+                    // `parse_page_chunk` itself reports its own failures on `sender_tx` before
+                    // returning them, but the FFI calls it makes into pdfium can still abort via
+                    // `panic!` rather than a normal `Err` -- catch that here so one malformed
+                    // chunk can't take the whole `std::thread::scope` (and therefore this
+                    // dispatcher thread) down with it.
+                    let chunk_for_panic_report = chunk.clone();
+                    let sender_tx_for_panic = sender_tx.clone();
+                    std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                        parse_page_chunk(
+                            &doc_data,
+                            password.as_deref(),
+                            flatten,
+                            required_raster_width,
+                            required_raster_height,
+                            max_image_pixels,
+                            &ligatures,
+                            chunk,
+                            &sender_tx,
+                            &cancellation_token,
+                            parent_span,
+                        )
+                    }))
+                    .unwrap_or_else(|panic_payload| {
+                        let message = panic_message(&panic_payload);
+                        let err = anyhow::anyhow!(
+                            "native parse worker panicked on pages {chunk_for_panic_report:?}: \
+                             {message}"
+                        );
+                        let _ = sender_tx_for_panic.blocking_send(Err(anyhow::anyhow!("{err}")));
+                        Err(err)
+                    })
+                })
+            })
+            .collect();
+
+        let mut first_err = None;
+        for handle in handles {
+            // `catch_unwind` above means `join()` itself should never observe a panic anymore,
+            // but a join failure is treated the same as a worker error rather than re-panicking.
+            let result = handle
+                .join()
+                .unwrap_or_else(|_| Err(anyhow::anyhow!("native parse worker thread panicked")));
+            if let Err(err) = result {
+                first_err.get_or_insert(err);
+            }
+        }
+        first_err
+    });
+
+    match first_err {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+/// Extracts a human-readable message from a `catch_unwind` payload, falling back to a generic
+/// message for panics that didn't pass a `&str`/`String`.
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
+
+/// Parses `page_ids` on the current thread using its own `Pdfium` binding and a fresh load of
+/// `doc_data`. A `PdfDocument`/`PdfPage` isn't `Send`, so this -- rather than sharing the
+/// already-open document `handle_parse_native_req` used to resolve `page_ids` -- is what lets
+/// [`handle_parse_native_req`] split one request's page range across worker threads and process
+/// them concurrently.
+#[allow(clippy::too_many_arguments)]
+fn parse_page_chunk(
+    doc_data: &DocSource,
+    password: Option<&str>,
+    flatten: bool,
+    required_raster_width: u32,
+    required_raster_height: u32,
+    max_image_pixels: u64,
+    ligatures: &LigatureConfig,
+    page_ids: Range<usize>,
+    sender_tx: &Sender<anyhow::Result<ParseNativePageResult>>,
+    cancellation_token: &CancellationToken,
+    parent_span: Span,
+) -> anyhow::Result<()> {
+    let _guard = parent_span.enter();
+    let result = (|| -> anyhow::Result<()> {
+        let pdfium = Pdfium::new(
+            Pdfium::bind_to_statically_linked_library()
+                .map_err(|e| anyhow::anyhow!("can't load pdfium bindings: {e}"))?,
         );
-        sender_tx.blocking_send(parsing_result)?
+        let mut document = pdfium.load_pdf_from_byte_slice(doc_data, password)?;
+        let mut pages: Vec<_> = document.pages_mut().iter().enumerate().collect();
+
+        for (page_id, mut page) in pages.drain(page_ids.clone()) {
+            if cancellation_token.is_cancelled() {
+                tracing::debug!(
+                    "Native parse request cancelled before page {page_id}; stopping early"
+                );
+                break;
+            }
+            let parsing_result = parse_page_native(
+                page_id,
+                &mut page,
+                flatten,
+                required_raster_width,
+                required_raster_height,
+                max_image_pixels,
+                ligatures,
+            );
+            sender_tx.blocking_send(parsing_result)?
+        }
+        Ok(())
+    })();
+
+    // A failure setting up this chunk (binding pdfium, loading the document) happens before any
+    // per-page `Err` would otherwise reach `sender_tx` -- report it there too, not just as this
+    // function's return value, so a caller draining `sender_tx` sees why pages from this chunk
+    // never arrived instead of the chunk just silently going missing.
+    if let Err(ref err) = result {
+        let _ = sender_tx.blocking_send(Err(anyhow::anyhow!(
+            "native parse chunk {page_ids:?} failed: {err}"
+        )));
     }
-    Ok(())
+    result
+}
+
+/// Splits `page_range` into up to `n_workers` contiguous, roughly-equal sub-ranges, so
+/// [`handle_parse_native_req`] can hand each one to its own worker thread. Never returns more
+/// chunks than `page_range` has pages -- an empty or single-page range always comes back as one
+/// chunk.
+fn doc_chunks(page_range: Range<usize>, n_workers: usize) -> Vec<Range<usize>> {
+    let total = page_range.len();
+    if total == 0 || n_workers <= 1 {
+        return vec![page_range];
+    }
+
+    let n_workers = n_workers.min(total);
+    let chunk_size = total.div_ceil(n_workers);
+    let mut chunks = Vec::with_capacity(n_workers);
+    let mut start = page_range.start;
+    while start < page_range.end {
+        let end = (start + chunk_size).min(page_range.end);
+        chunks.push(start..end);
+        start = end;
+    }
+    chunks
 }
 
 pub fn start_native_parser(mut input_rx: Receiver<(ParseNativeRequest, Span)>) {
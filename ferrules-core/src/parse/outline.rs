@@ -0,0 +1,67 @@
+use pdfium_render::prelude::{PdfAction, PdfBookmark, PdfDestination, PdfDocument};
+
+use crate::entities::{BBox, OutlineEntry, PageID};
+
+/// Resolves a bookmark's action (if any) to the `PdfDestination` it points at. Pdfium already
+/// walks the catalog's name tree to turn a named destination into a direct one, so this is just
+/// unwrapping the action variant rather than any tree traversal of our own.
+fn bookmark_destination(bookmark: &PdfBookmark) -> Option<PdfDestination> {
+    if let Some(destination) = bookmark.destination() {
+        return Some(destination);
+    }
+    match bookmark.action()? {
+        PdfAction::GoToDestination(action) => Some(action.destination()),
+        _ => None,
+    }
+}
+
+/// Converts a `destination`'s page index and (optional) target rect into this crate's
+/// `(PageID, BBox)` convention, in the page's own coordinate space -- the same upright, top-left
+/// PDF-point space every other native geometry in this crate is normalized into before rotation
+/// is applied (see `parse::native::parse_page_native`).
+fn resolve_destination(destination: &PdfDestination) -> (Option<PageID>, Option<BBox>) {
+    let page_index = destination.page_index();
+    let page_id = Some(page_index as PageID);
+    let target_bbox = destination.view_rect().map(|rect| BBox {
+        x0: rect.left().value,
+        y0: rect.top().value,
+        x1: rect.right().value,
+        y1: rect.bottom().value,
+    });
+    (page_id, target_bbox)
+}
+
+/// Recursively converts `bookmark` and its chain of next-siblings into [`OutlineEntry`] nodes.
+fn collect_siblings(bookmark: PdfBookmark) -> Vec<OutlineEntry> {
+    let mut entries = Vec::new();
+    let mut next = Some(bookmark);
+    while let Some(bookmark) = next {
+        next = bookmark.next_sibling();
+        let (page_id, target_bbox) = bookmark_destination(&bookmark)
+            .as_ref()
+            .map(resolve_destination)
+            .unwrap_or((None, None));
+        let children = bookmark
+            .first_child()
+            .map(collect_siblings)
+            .unwrap_or_default();
+        entries.push(OutlineEntry {
+            title: bookmark.title().unwrap_or_default(),
+            page_id,
+            target_bbox,
+            children,
+        });
+    }
+    entries
+}
+
+/// Walks `document`'s outline (bookmarks) tree into a forest of [`OutlineEntry`] nodes, one per
+/// top-level bookmark, so consumers get a real table of contents instead of a flat block list.
+/// Returns an empty `Vec` for a document with no outline.
+pub(crate) fn parse_document_outline(document: &PdfDocument) -> Vec<OutlineEntry> {
+    document
+        .bookmarks()
+        .root()
+        .map(collect_siblings)
+        .unwrap_or_default()
+}
@@ -11,18 +11,26 @@ use tracing::instrument;
 
 use crate::{
     draw::{draw_blocks, draw_layout_bboxes, draw_text_lines},
-    entities::{Element, Line, PageID, StructuredPage},
+    entities::{ColumnLayout, Element, ElementType, Line, PageID, StructuredPage},
     layout::{
-        model::LayoutBBox, Metadata, ParseLayoutQueue, ParseLayoutRequest, ParseLayoutResponse,
+        model::LayoutBBox, LayoutPriority, Metadata, ParseLayoutQueue, ParseLayoutRequest,
+        ParseLayoutResponse,
     },
-    ocr::parse_image_ocr,
+    ocr::{parse_image_ocr, OcrConfig},
 };
 
 use super::{
-    merge::{merge_elements_into_blocks, merge_lines_layout, merge_remaining},
+    images::{best_match, NativeImage},
+    merge::{merge_elements_into_blocks, merge_lines_layout, merge_remaining, MergeConfig},
     native::ParseNativePageResult,
+    reading_order::{column_major_order, ReadingOrderConfig},
+    table::infer_table_grid,
 };
 
+/// Minimum fraction of a text line's area that must fall inside a `Table` layout box for
+/// the line to be treated as a cell of that table when reconstructing its grid.
+const MIN_TABLE_LINE_COVERAGE: f32 = 0.5;
+
 /// This constant defines the minimum ratio between the area of text lines identified
 /// by the pdfium2 and the area of text regions detected through layout analysis.
 /// If this ratio falls below the threshold of 0.5 (or 50%), it indicates that the page
@@ -45,8 +53,11 @@ fn page_needs_ocr(text_boxes: &[&LayoutBBox], text_lines: &[Line]) -> bool {
 fn build_page_elements(
     page_layout: &[LayoutBBox],
     text_lines: &[Line],
+    native_images: &[NativeImage],
     page_idx: PageID,
-) -> anyhow::Result<Vec<Element>> {
+    page_width: f32,
+    reading_order: &ReadingOrderConfig,
+) -> anyhow::Result<(Vec<Element>, ColumnLayout)> {
     let mut elements = merge_lines_layout(page_layout, text_lines, page_idx)?;
     let merged_layout_blocks_ids = elements
         .iter()
@@ -58,7 +69,57 @@ fn build_page_elements(
         .collect();
 
     merge_remaining(&mut elements, &unmerged_layout_boxes, page_idx);
-    Ok(elements)
+    attach_table_grids(&mut elements, page_layout, text_lines);
+    attach_native_images(&mut elements, native_images);
+
+    // Every bbox reaching this function -- layout boxes, text lines, native images -- is
+    // already in the page's upright, rendered-image coordinate space (see
+    // `parse::native::parse_page_native`'s `rotation_matrix`), so `column_major_order` can
+    // reason about columns directly without a separate rotated copy just for ordering.
+    let (order, columns) = column_major_order(&elements, page_width, reading_order);
+    let mut elements: Vec<Element> = order.into_iter().map(|i| elements[i].clone()).collect();
+    for (idx, element) in elements.iter_mut().enumerate() {
+        element.order = idx;
+    }
+    Ok((elements, columns))
+}
+
+/// Reconstructs the row/column grid of every `Table` element from the text lines that fall
+/// inside its layout box, so `BlockType::Table` carries structured cells instead of an
+/// undifferentiated blob of concatenated text.
+fn attach_table_grids(elements: &mut [Element], page_layout: &[LayoutBBox], text_lines: &[Line]) {
+    for element in elements.iter_mut() {
+        if !matches!(element.kind, ElementType::Table) {
+            continue;
+        }
+        let Some(table_box) = page_layout.iter().find(|b| b.id == element.layout_block_id) else {
+            continue;
+        };
+        let table_lines: Vec<&Line> = text_lines
+            .iter()
+            .filter(|l| {
+                table_box.bbox.intersection(&l.bbox) / l.bbox.area().max(1.0)
+                    > MIN_TABLE_LINE_COVERAGE
+            })
+            .collect();
+        element.table_rows = Some(infer_table_grid(&table_lines));
+    }
+}
+
+/// Attaches each `Image` element's best-matching native-resolution bitmap (see
+/// [`crate::parse::images`]), so exporters can emit the PDF's own embedded image instead of an
+/// upscaled crop of the whole-page raster. An element with no overlapping XObject (e.g. a figure
+/// that's itself a composite of vector drawing, not a single embedded image) is left untouched
+/// and falls back to the page-crop path at save time.
+fn attach_native_images(elements: &mut [Element], native_images: &[NativeImage]) {
+    for element in elements.iter_mut() {
+        if !matches!(element.kind, ElementType::Image) {
+            continue;
+        }
+        if let Some(native_image) = best_match(&element.bbox, native_images) {
+            element.native_image = Some(native_image.image.clone());
+        }
+    }
 }
 
 #[instrument(skip_all)]
@@ -67,13 +128,17 @@ fn parse_page_text(
     page_layout: &[LayoutBBox],
     page_image: &DynamicImage,
     downscale_factor: f32,
+    ocr_config: &OcrConfig,
 ) -> anyhow::Result<(Vec<Line>, bool)> {
     let text_layout_box: Vec<&LayoutBBox> =
         page_layout.iter().filter(|b| b.is_text_block()).collect();
-    let need_ocr = page_needs_ocr(&text_layout_box, &native_text_lines);
+    let need_ocr = ocr_config.enabled && page_needs_ocr(&text_layout_box, &native_text_lines);
 
     let ocr_result = if need_ocr {
-        parse_image_ocr(page_image, downscale_factor).ok()
+        let start = Instant::now();
+        let result = parse_image_ocr(page_image, downscale_factor, ocr_config).ok();
+        crate::metrics::record_stage_latency("ocr", start.elapsed());
+        result
     } else {
         None
     };
@@ -104,18 +169,26 @@ pub async fn parse_page_full(
     parse_native_result: ParseNativePageResult,
     debug_dir: Option<PathBuf>,
     layout_queue: ParseLayoutQueue,
+    ocr_config: &OcrConfig,
+    reading_order: &ReadingOrderConfig,
+    #[cfg(feature = "lua")] script: Option<&crate::script::ScriptEngine>,
 ) -> anyhow::Result<StructuredPage> {
     let span = tracing::Span::current();
     let ParseNativePageResult {
         page_id,
         text_lines,
         page_bbox,
+        page_rotation,
         page_image,
         page_image_scale1,
         downscale_factor,
         metadata: parse_native_metadata,
+        annotations,
+        native_images,
         is_count_result: _,
         total_page_count: _,
+        is_outline_result: _,
+        outline: _,
     } = parse_native_result;
     let (layout_tx, layout_rx) = tokio::sync::oneshot::channel();
 
@@ -127,6 +200,8 @@ pub async fn parse_page_full(
             response_tx: layout_tx,
             queue_time: Instant::now(),
         },
+        cancellation_token: tokio_util::sync::CancellationToken::new(),
+        priority: LayoutPriority::default(),
     };
     layout_queue.push(layout_req).await?;
 
@@ -140,11 +215,34 @@ pub async fn parse_page_full(
         .context("error receiving layout on oneshot channel")?
         .context("error parsing page")?;
 
-    let (text_lines, need_ocr) =
-        parse_page_text(text_lines, &page_layout, &page_image, downscale_factor)?;
+    let (mut text_lines, need_ocr) = parse_page_text(
+        text_lines,
+        &page_layout,
+        &page_image,
+        downscale_factor,
+        ocr_config,
+    )?;
+
+    // Runs the user's `normalize_text` Lua hook, if any, before this page's lines are merged
+    // into elements -- nothing has read `Line::text` yet at this point, so this has the same
+    // effect as normalizing at `CharSpan`/`Line` construction time without reaching back into
+    // the native-parsing queue worker that builds them.
+    #[cfg(feature = "lua")]
+    if let Some(script) = script {
+        for line in &mut text_lines {
+            line.normalize(script);
+        }
+    }
 
     // Merging elements with layout
-    let elements = build_page_elements(&page_layout, &text_lines, page_id)?;
+    let (elements, columns) = build_page_elements(
+        &page_layout,
+        &text_lines,
+        &native_images,
+        page_id,
+        page_bbox.width(),
+        reading_order,
+    )?;
     if let Some(tmp_dir) = debug_dir {
         debug_page(
             &tmp_dir,
@@ -154,6 +252,8 @@ pub async fn parse_page_full(
             need_ocr,
             &page_layout,
             &elements,
+            #[cfg(feature = "lua")]
+            script,
         )?
     };
 
@@ -161,9 +261,12 @@ pub async fn parse_page_full(
         id: page_id,
         width: page_bbox.width(),
         height: page_bbox.height(),
+        rotation: page_rotation,
         image: page_image_scale1,
         elements,
         need_ocr,
+        columns,
+        annotations,
     };
 
     span.record(
@@ -192,6 +295,7 @@ fn debug_page(
     need_ocr: bool,
     page_layout: &[LayoutBBox],
     elements: &[Element],
+    #[cfg(feature = "lua")] script: Option<&crate::script::ScriptEngine>,
 ) -> anyhow::Result<()> {
     let output_file = tmp_dir.join(format!("page_{}.png", page_idx));
     let final_output_file = tmp_dir.join(format!("page_blocks_{}.png", page_idx));
@@ -199,7 +303,13 @@ fn debug_page(
     let out_img = draw_layout_bboxes(page_layout, &out_img.into())?;
     // Draw the final prediction -
     // TODO: Implement titles hashmap for titles in the page
-    let blocks = merge_elements_into_blocks(elements.to_vec(), HashMap::new())?;
+    let blocks = merge_elements_into_blocks(
+        elements.to_vec(),
+        HashMap::new(),
+        &MergeConfig::default(),
+        #[cfg(feature = "lua")]
+        script,
+    )?;
     let final_img = draw_blocks(&blocks, page_image)?;
     out_img.save(output_file)?;
 
@@ -0,0 +1,250 @@
+use crate::entities::{BBox, ColumnLayout, Element, ElementType};
+
+/// How much wider than the median element height an empty band along an axis must be
+/// before it's treated as a column/row separator rather than normal line spacing. See
+/// [`ReadingOrderConfig::gap_height_factor`].
+const DEFAULT_GAP_HEIGHT_FACTOR: f32 = 1.5;
+
+/// An element whose bbox covers at least this fraction of the node's width can't be split
+/// by a vertical cut (e.g. a full-width header or footer), so it's pulled out beforehand.
+const SPANNING_WIDTH_RATIO: f32 = 0.85;
+
+/// Minimum horizontal gap between successive elements' x-centers, as a fraction of page width,
+/// before it's treated as a column boundary rather than ordinary spacing within one column.
+const MIN_COLUMN_GAP_WIDTH_RATIO: f32 = 0.04;
+
+/// Tunables for the recursive XY-cut reading-order reconstruction.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReadingOrderConfig {
+    /// The empty-band-width threshold used by [`xy_cut_order`]/[`column_major_order`] is this
+    /// factor times the page's median element height. Raising it requires a wider gap before a
+    /// column/row split is recognized, which helps on pages with generous line spacing that
+    /// would otherwise get cut into spurious single-line columns.
+    pub gap_height_factor: f32,
+}
+
+impl Default for ReadingOrderConfig {
+    fn default() -> Self {
+        Self {
+            gap_height_factor: DEFAULT_GAP_HEIGHT_FACTOR,
+        }
+    }
+}
+
+/// Recovers a left-to-right, top-to-bottom reading order across a page's elements using a
+/// recursive XY-cut: at each node we look for the widest empty vertical band first (column
+/// separation), then the widest empty horizontal band (row separation), recursing into each
+/// side. When neither axis has a gap wider than the threshold, the remaining elements are a
+/// leaf and are sorted top-to-bottom then left-to-right.
+///
+/// Returns indices into `elements` in reading order.
+pub(crate) fn xy_cut_order(elements: &[Element], config: &ReadingOrderConfig) -> Vec<usize> {
+    let idxs: Vec<usize> = (0..elements.len()).collect();
+    if idxs.len() <= 1 {
+        return idxs;
+    }
+    let min_gap = median_height(elements) * config.gap_height_factor;
+    cut(idxs, elements, min_gap)
+}
+
+/// Detects column bands by gap-based 1-D partitioning on elements' x-centers: start a new
+/// column whenever the horizontal gap between successive centers exceeds
+/// [`MIN_COLUMN_GAP_WIDTH_RATIO`] of `page_width`. `Header`/`Footer` elements are excluded since
+/// they typically span the full page width and would otherwise wash out the column bands.
+pub(crate) fn detect_columns(elements: &[Element], page_width: f32) -> ColumnLayout {
+    let min_gap = page_width * MIN_COLUMN_GAP_WIDTH_RATIO;
+
+    let mut centers: Vec<f32> = elements
+        .iter()
+        .filter(|e| !matches!(e.kind, ElementType::Header | ElementType::Footer))
+        .map(|e| e.bbox.center().0)
+        .collect();
+    if centers.is_empty() {
+        return ColumnLayout::default();
+    }
+    centers.sort_by(|a, b| a.total_cmp(b));
+
+    let mut boundaries = Vec::new();
+    let mut prev = centers[0];
+    for &center in &centers[1..] {
+        if center - prev > min_gap {
+            boundaries.push((prev + center) / 2.0);
+        }
+        prev = center;
+    }
+
+    ColumnLayout {
+        column_count: boundaries.len() + 1,
+        boundaries,
+    }
+}
+
+/// Orders a page's elements column-major: `Header` elements first, then each detected column
+/// left to right (top-to-bottom within a column via [`xy_cut_order`]'s recursive cut), then
+/// `Footer` elements last. Also returns the [`ColumnLayout`] that was detected, so callers can
+/// expose it on the page for exporters that want true reading order rather than detection order.
+///
+/// Returns indices into `elements` in reading order.
+pub(crate) fn column_major_order(
+    elements: &[Element],
+    page_width: f32,
+    config: &ReadingOrderConfig,
+) -> (Vec<usize>, ColumnLayout) {
+    let idxs: Vec<usize> = (0..elements.len()).collect();
+    if idxs.len() <= 1 {
+        return (idxs, ColumnLayout::default());
+    }
+
+    let (mut headers, rest): (Vec<usize>, Vec<usize>) = idxs
+        .into_iter()
+        .partition(|&i| matches!(elements[i].kind, ElementType::Header));
+    let (mut footers, body): (Vec<usize>, Vec<usize>) = rest
+        .into_iter()
+        .partition(|&i| matches!(elements[i].kind, ElementType::Footer));
+    headers.sort_by(|&a, &b| elements[a].bbox.y0.total_cmp(&elements[b].bbox.y0));
+    footers.sort_by(|&a, &b| elements[a].bbox.y0.total_cmp(&elements[b].bbox.y0));
+
+    let columns = detect_columns(elements, page_width);
+
+    let mut by_column: Vec<Vec<usize>> = vec![Vec::new(); columns.column_count];
+    for i in body {
+        let center = elements[i].bbox.center().0;
+        let col = columns.boundaries.iter().filter(|&&b| center > b).count();
+        by_column[col].push(i);
+    }
+
+    let min_gap = median_height(elements) * config.gap_height_factor;
+    let mut ordered = headers;
+    for column in by_column {
+        ordered.extend(cut(column, elements, min_gap));
+    }
+    ordered.extend(footers);
+
+    (ordered, columns)
+}
+
+fn median_height(elements: &[Element]) -> f32 {
+    let mut heights: Vec<f32> = elements.iter().map(|e| e.bbox.height()).collect();
+    heights.sort_by(|a, b| a.total_cmp(b));
+    heights.get(heights.len() / 2).copied().unwrap_or(1.0)
+}
+
+fn bounds(idxs: &[usize], elements: &[Element]) -> BBox {
+    let mut region: Option<BBox> = None;
+    for &i in idxs {
+        match &mut region {
+            Some(r) => r.merge(&elements[i].bbox),
+            None => region = Some(elements[i].bbox.clone()),
+        }
+    }
+    region.unwrap_or_default()
+}
+
+/// Finds the position of the widest empty band strictly inside `[lo, hi]` given the
+/// 1-D spans already occupying that axis, as long as it's wider than `min_gap`.
+fn widest_valley(mut spans: Vec<(f32, f32)>, lo: f32, hi: f32, min_gap: f32) -> Option<f32> {
+    spans.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut cursor = lo;
+    let mut best: Option<(f32, f32)> = None;
+    for (s0, s1) in spans {
+        if s0 > cursor {
+            let gap = s0 - cursor;
+            if gap > min_gap && best.map_or(true, |(w, _)| gap > w) {
+                best = Some((gap, (cursor + s0) / 2.0));
+            }
+        }
+        cursor = cursor.max(s1);
+    }
+    if hi > cursor {
+        let gap = hi - cursor;
+        if gap > min_gap && best.map_or(true, |(w, _)| gap > w) {
+            best = Some((gap, (cursor + hi) / 2.0));
+        }
+    }
+    best.map(|(_, cut_pos)| cut_pos)
+}
+
+fn cut(idxs: Vec<usize>, elements: &[Element], min_gap: f32) -> Vec<usize> {
+    if idxs.len() <= 1 {
+        return idxs;
+    }
+
+    let region = bounds(&idxs, elements);
+    let (spanning, rest): (Vec<usize>, Vec<usize>) = idxs
+        .into_iter()
+        .partition(|&i| elements[i].bbox.width() >= region.width() * SPANNING_WIDTH_RATIO);
+
+    if rest.len() > 1 {
+        let x_spans = rest
+            .iter()
+            .map(|&i| (elements[i].bbox.x0, elements[i].bbox.x1))
+            .collect();
+        if let Some(cut_x) = widest_valley(x_spans, region.x0, region.x1, min_gap) {
+            let (left, right): (Vec<usize>, Vec<usize>) = rest
+                .into_iter()
+                .partition(|&i| elements[i].bbox.center().0 < cut_x);
+            let mut ordered = cut(left, elements, min_gap);
+            ordered.extend(cut(right, elements, min_gap));
+            return interleave_spanning(spanning, ordered, elements);
+        }
+
+        let y_spans = rest
+            .iter()
+            .map(|&i| (elements[i].bbox.y0, elements[i].bbox.y1))
+            .collect();
+        if let Some(cut_y) = widest_valley(y_spans, region.y0, region.y1, min_gap) {
+            let (top, bottom): (Vec<usize>, Vec<usize>) = rest
+                .into_iter()
+                .partition(|&i| elements[i].bbox.center().1 < cut_y);
+            let mut ordered = cut(top, elements, min_gap);
+            ordered.extend(cut(bottom, elements, min_gap));
+            return interleave_spanning(spanning, ordered, elements);
+        }
+
+        return interleave_spanning(spanning, sort_leaf(rest, elements), elements);
+    }
+
+    let mut leaf = rest;
+    leaf.extend(spanning);
+    sort_leaf(leaf, elements)
+}
+
+fn sort_leaf(mut idxs: Vec<usize>, elements: &[Element]) -> Vec<usize> {
+    idxs.sort_by(|&a, &b| {
+        elements[a]
+            .bbox
+            .y0
+            .total_cmp(&elements[b].bbox.y0)
+            .then_with(|| elements[a].bbox.x0.total_cmp(&elements[b].bbox.x0))
+    });
+    idxs
+}
+
+/// Re-inserts boxes that span the whole cut region (headers/footers) at the point in the
+/// ordering matching their vertical position, rather than always leading or trailing.
+fn interleave_spanning(
+    mut spanning: Vec<usize>,
+    ordered: Vec<usize>,
+    elements: &[Element],
+) -> Vec<usize> {
+    if spanning.is_empty() {
+        return ordered;
+    }
+    spanning.sort_by(|&a, &b| elements[a].bbox.y0.total_cmp(&elements[b].bbox.y0));
+
+    let mut result = Vec::with_capacity(spanning.len() + ordered.len());
+    let mut spanning_iter = spanning.into_iter().peekable();
+    for idx in ordered {
+        while let Some(&next_span) = spanning_iter.peek() {
+            if elements[next_span].bbox.y0 <= elements[idx].bbox.y0 {
+                result.push(spanning_iter.next().unwrap());
+            } else {
+                break;
+            }
+        }
+        result.push(idx);
+    }
+    result.extend(spanning_iter);
+    result
+}
@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+
+use crate::{
+    blocks::{Block, BlockType, TextBlock},
+    entities::{append_collapsing_spans, BBox, Page, PageID},
+};
+
+/// Characters that mark a complete sentence/clause; a block whose text doesn't end in one of
+/// these is a candidate for being continued on the next page.
+const SENTENCE_TERMINATORS: [char; 4] = ['.', '!', '?', ':'];
+
+/// Tunables for [`stitch_cross_page_blocks`].
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct StitchConfig {
+    /// Fraction of page height, measured from the bottom of a page / top of the next, that
+    /// still counts as the margin band a block must sit in to be eligible for stitching.
+    pub margin_ratio: f32,
+    /// Minimum horizontal overlap (as a fraction of the narrower block's width) required
+    /// before two blocks are considered to share a column.
+    pub min_column_overlap: f32,
+}
+
+impl Default for StitchConfig {
+    fn default() -> Self {
+        Self {
+            margin_ratio: 0.12,
+            min_column_overlap: 0.5,
+        }
+    }
+}
+
+/// Recovers paragraphs and lists that a page break split in two: whenever a `TextBlock` (or
+/// `ListBlock`) ends at the bottom margin of page N without a sentence terminator, and the next
+/// block in reading order starts at the top margin of page N+1 in the same column, the two are
+/// merged into one block spanning both pages. [`crate::parse::merge::merge_elements_into_blocks`]
+/// can't see this on its own: it only ever looks at one page's elements at a time.
+pub(crate) fn stitch_cross_page_blocks(
+    blocks: Vec<Block>,
+    pages: &[Page],
+    config: &StitchConfig,
+) -> Vec<Block> {
+    let page_height: HashMap<PageID, f32> = pages.iter().map(|p| (p.id, p.height)).collect();
+
+    let mut stitched: Vec<Block> = Vec::with_capacity(blocks.len());
+    for block in blocks {
+        let should_stitch = stitched
+            .last()
+            .is_some_and(|prev| can_stitch(prev, &block, &page_height, config));
+        if should_stitch {
+            let prev = stitched.pop().unwrap();
+            stitched.push(merge_stitched(prev, block));
+        } else {
+            stitched.push(block);
+        }
+    }
+    stitched
+}
+
+fn can_stitch(
+    prev: &Block,
+    next: &Block,
+    page_height: &HashMap<PageID, f32>,
+    config: &StitchConfig,
+) -> bool {
+    if !same_stitchable_kind(prev, next) {
+        return false;
+    }
+
+    let (Some(&prev_page), Some(&next_page)) = (prev.pages_id.last(), next.pages_id.first())
+    else {
+        return false;
+    };
+    if next_page != prev_page + 1 {
+        return false;
+    }
+
+    let (Some(&prev_height), Some(&next_height)) =
+        (page_height.get(&prev_page), page_height.get(&next_page))
+    else {
+        return false;
+    };
+
+    let prev_in_bottom_margin = prev.bbox.y1 >= prev_height * (1.0 - config.margin_ratio);
+    let next_in_top_margin = next.bbox.y0 <= next_height * config.margin_ratio;
+    if !prev_in_bottom_margin || !next_in_top_margin {
+        return false;
+    }
+
+    if horizontal_overlap_ratio(&prev.bbox, &next.bbox) < config.min_column_overlap {
+        return false;
+    }
+
+    block_tail_text(prev).is_some_and(ends_mid_sentence)
+}
+
+fn same_stitchable_kind(a: &Block, b: &Block) -> bool {
+    matches!(
+        (&a.kind, &b.kind),
+        (BlockType::TextBlock(_), BlockType::TextBlock(_))
+            | (BlockType::ListBlock(_), BlockType::ListBlock(_))
+    )
+}
+
+fn block_tail_text(block: &Block) -> Option<&str> {
+    match &block.kind {
+        BlockType::TextBlock(t) => Some(t.text.as_str()),
+        BlockType::ListBlock(l) => l.items.last().map(String::as_str),
+        _ => None,
+    }
+}
+
+/// True when `text` doesn't end with a sentence terminator, including the case where it ends
+/// mid-hyphenation (a hyphen is never a terminator, so this falls out for free).
+fn ends_mid_sentence(text: &str) -> bool {
+    match text.trim_end().chars().last() {
+        Some(c) => !SENTENCE_TERMINATORS.contains(&c),
+        None => false,
+    }
+}
+
+/// Fraction of `a`/`b`'s narrower width that the two bboxes overlap horizontally.
+fn horizontal_overlap_ratio(a: &BBox, b: &BBox) -> f32 {
+    let overlap = f32::max(0.0, f32::min(a.x1, b.x1) - f32::max(a.x0, b.x0));
+    let narrowest_width = a.width().min(b.width());
+    if narrowest_width <= 0.0 {
+        0.0
+    } else {
+        overlap / narrowest_width
+    }
+}
+
+fn merge_stitched(mut prev: Block, next: Block) -> Block {
+    prev.bbox.merge(&next.bbox);
+    for page in &next.pages_id {
+        if !prev.pages_id.contains(page) {
+            prev.pages_id.push(*page);
+        }
+    }
+
+    match (&mut prev.kind, next.kind) {
+        (BlockType::TextBlock(prev_text), BlockType::TextBlock(next_text)) => {
+            merge_text_blocks(prev_text, next_text);
+        }
+        (BlockType::ListBlock(prev_list), BlockType::ListBlock(next_list)) => {
+            let mut next_items = next_list.items.into_iter();
+            if let (Some(last), Some(first_of_next)) = (prev_list.items.last_mut(), next_items.next())
+            {
+                join_dehyphenating(last, &first_of_next);
+            }
+            prev_list.items.extend(next_items);
+        }
+        _ => unreachable!("same_stitchable_kind only matches TextBlock/TextBlock or ListBlock/ListBlock"),
+    }
+
+    prev
+}
+
+fn merge_text_blocks(prev: &mut TextBlock, next: TextBlock) {
+    let hyphenated = prev.text.trim_end().ends_with('-');
+    join_dehyphenating(&mut prev.text, &next.text);
+
+    let mut next_spans = next.spans;
+    if hyphenated {
+        if let Some(last) = prev.spans.last_mut() {
+            strip_trailing_hyphen(&mut last.text);
+        }
+    } else if let Some(first) = next_spans.first_mut() {
+        first.text.insert(0, ' ');
+    }
+    append_collapsing_spans(&mut prev.spans, next_spans);
+}
+
+/// Joins `next` onto `text`, undoing end-of-line hyphenation (`"intro-" + "duction"` ->
+/// `"introduction"`) when `text` ends in a hyphen, or otherwise joining with a single space.
+fn join_dehyphenating(text: &mut String, next: &str) {
+    if text.trim_end().ends_with('-') {
+        strip_trailing_hyphen(text);
+        text.push_str(next.trim_start());
+    } else {
+        text.push(' ');
+        text.push_str(next.trim_start());
+    }
+}
+
+fn strip_trailing_hyphen(text: &mut String) {
+    let trimmed_len = text.trim_end().len();
+    text.truncate(trimmed_len);
+    while text.ends_with('-') {
+        text.pop();
+    }
+}
@@ -0,0 +1,106 @@
+use crate::entities::Line;
+
+/// How much wider than the median line height a vertical empty band between columns of text
+/// must be before it's treated as a column separator rather than normal letter/word spacing.
+const COLUMN_GAP_FACTOR: f32 = 1.0;
+
+/// How much wider than the median line height a vertical empty band between two lines
+/// must be before it's treated as a row separator rather than normal line spacing.
+const ROW_GAP_FACTOR: f32 = 0.5;
+
+fn median_height(lines: &[&Line]) -> f32 {
+    let mut heights: Vec<f32> = lines.iter().map(|l| l.bbox.height()).collect();
+    heights.sort_by(|a, b| a.total_cmp(b));
+    heights.get(heights.len() / 2).copied().unwrap_or(1.0)
+}
+
+/// Finds the x-position of every vertical gap between text lines wide enough to be a
+/// column boundary, scanning left to right across the union of all line bounding boxes.
+fn column_boundaries(lines: &[&Line], min_gap: f32) -> Vec<f32> {
+    let mut spans: Vec<(f32, f32)> = lines.iter().map(|l| (l.bbox.x0, l.bbox.x1)).collect();
+    spans.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut bounds = Vec::new();
+    let mut cursor = spans[0].1;
+    for &(s0, s1) in &spans[1..] {
+        if s0 - cursor > min_gap {
+            bounds.push((cursor + s0) / 2.0);
+        }
+        cursor = cursor.max(s1);
+    }
+    bounds
+}
+
+/// Picks the column band `[x0, x1)` overlaps the most, so a line that straddles a column
+/// boundary (e.g. a wide merged-cell heading) lands in whichever column it mostly covers
+/// instead of wherever its bare center point happens to fall.
+fn column_with_max_overlap(x0: f32, x1: f32, col_bounds: &[f32]) -> usize {
+    let band_start = |col: usize| {
+        col.checked_sub(1)
+            .and_then(|i| col_bounds.get(i))
+            .copied()
+            .unwrap_or(f32::MIN)
+    };
+    let band_end = |col: usize| col_bounds.get(col).copied().unwrap_or(f32::MAX);
+
+    (0..=col_bounds.len())
+        .max_by(|&a, &b| {
+            let overlap = |col: usize| (x1.min(band_end(col)) - x0.max(band_start(col))).max(0.0);
+            overlap(a).total_cmp(&overlap(b))
+        })
+        .unwrap_or(0)
+}
+
+/// Buckets a table row's lines into cells using the shared column boundaries, joining
+/// multiple lines that land in the same cell with a space.
+fn assign_to_columns(mut row: Vec<&Line>, col_bounds: &[f32]) -> Vec<String> {
+    row.sort_by(|a, b| a.bbox.x0.total_cmp(&b.bbox.x0));
+
+    let mut cells = vec![String::new(); col_bounds.len() + 1];
+    for line in row {
+        let col = column_with_max_overlap(line.bbox.x0, line.bbox.x1, col_bounds);
+        if !cells[col].is_empty() {
+            cells[col].push(' ');
+        }
+        cells[col].push_str(line.text.trim());
+    }
+    cells
+}
+
+/// Reconstructs a table's row/column grid from the text lines it contains by finding
+/// separating gaps in the horizontal and vertical projection profiles of their bounding
+/// boxes: lines are first clustered into rows by vertical gaps, then every row is split
+/// into cells using column boundaries shared across the whole table.
+pub(crate) fn infer_table_grid(lines: &[&Line]) -> Vec<Vec<String>> {
+    if lines.is_empty() {
+        return Vec::new();
+    }
+
+    let median_h = median_height(lines);
+
+    let mut by_y: Vec<&Line> = lines.to_vec();
+    by_y.sort_by(|a, b| a.bbox.y0.total_cmp(&b.bbox.y0));
+
+    let row_gap = median_h * ROW_GAP_FACTOR;
+    let mut rows: Vec<Vec<&Line>> = Vec::new();
+    for line in by_y {
+        let starts_new_row = match rows.last() {
+            Some(row) => {
+                let row_bottom = row.iter().map(|l| l.bbox.y1).fold(f32::MIN, f32::max);
+                line.bbox.y0 - row_bottom > row_gap
+            }
+            None => true,
+        };
+        if starts_new_row {
+            rows.push(vec![line]);
+        } else {
+            rows.last_mut().unwrap().push(line);
+        }
+    }
+
+    let col_bounds = column_boundaries(lines, median_h * COLUMN_GAP_FACTOR);
+
+    rows.into_iter()
+        .map(|row| assign_to_columns(row, &col_bounds))
+        .collect()
+}
@@ -1,64 +1,212 @@
-#![allow(incomplete_features)]
 use std::collections::HashMap;
 
-use itertools::Itertools;
-use kmeans::{EuclideanDistance, KMeans, KMeansConfig};
-
 use crate::{
     blocks::TitleLevel,
     entities::{Element, ElementID, PageID},
 };
-/// Minimum gap between headings to consider them in separate buckets
-const TITLE_MERGE_THRESHOLD: f32 = 0.7;
-const LANE_COUNT_SIMD_KMEANS: usize = 4;
 
-pub fn title_levels_kmeans(
+/// Upper bound on the number of heading-level clusters to look for across a document's titles,
+/// clamped down to the number of distinct title font sizes when there are fewer than this.
+const MAX_TITLE_BUCKETS: usize = 6;
+
+/// Maximum number of Lloyd-style assign/update passes before giving up on convergence and
+/// using whatever centers the last pass produced.
+const MAX_KMEANS_ITERATIONS: usize = 50;
+
+/// Assigns a heading [`TitleLevel`] to each `Title`/`Subtitle` element by running 1-D k-means
+/// over the elements' [`Element::font_size`] and ranking the resulting clusters from largest
+/// (level 1) down to smallest.
+///
+/// `k` is picked as the number of distinct font sizes after rounding to 0.5pt bands (capped at
+/// [`MAX_TITLE_BUCKETS`]), so a document whose titles only ever use two sizes gets two levels
+/// instead of being forced into a fixed bucket count.
+pub fn title_levels_kmeans(titles: &[&Element]) -> HashMap<(PageID, ElementID), TitleLevel> {
+    let distinct_bands = {
+        let mut bands: Vec<i32> = titles
+            .iter()
+            .map(|el| (el.font_size() * 2.0).round() as i32)
+            .collect();
+        bands.sort_unstable();
+        bands.dedup();
+        bands.len()
+    };
+    title_levels_kmeans_with_k(titles, distinct_bands.clamp(1, MAX_TITLE_BUCKETS))
+}
+
+fn title_levels_kmeans_with_k(
     titles: &[&Element],
-    title_buckets: usize,
+    k: usize,
 ) -> HashMap<(PageID, ElementID), TitleLevel> {
     let mut title_level = HashMap::new();
+    if titles.is_empty() {
+        return title_level;
+    }
 
-    let samples: Vec<f32> = titles.iter().map(|e| e.bbox.height()).collect();
-    let sample_len = samples.len();
+    let sizes: Vec<f32> = titles.iter().map(|e| e.font_size()).collect();
 
-    // TODO: Check this heuristic
-    if sample_len <= title_buckets {
+    let mut unique_sizes: Vec<f32> = sizes.clone();
+    unique_sizes.sort_by(|a, b| a.total_cmp(b));
+    unique_sizes.dedup_by(|a, b| (*a - *b).abs() < f32::EPSILON);
+
+    // All titles share a single size: there is nothing to cluster, everyone is level 1.
+    if unique_sizes.len() == 1 {
+        for el in titles {
+            title_level.insert((el.page_id, el.id), 1);
+        }
         return title_level;
     }
 
-    let kmean: KMeans<_, LANE_COUNT_SIMD_KMEANS, _> =
-        KMeans::new(samples, sample_len, 1, EuclideanDistance);
+    let k = k.min(unique_sizes.len()).max(1);
+    let mut centers = init_quantile_centers(&unique_sizes, k);
+
+    for _ in 0..MAX_KMEANS_ITERATIONS {
+        let assignments: Vec<usize> = sizes.iter().map(|s| nearest_center(*s, &centers)).collect();
+
+        let mut sums = vec![0f32; k];
+        let mut counts = vec![0usize; k];
+        for (size, cluster) in sizes.iter().zip(assignments.iter()) {
+            sums[*cluster] += size;
+            counts[*cluster] += 1;
+        }
+
+        let new_centers: Vec<f32> = sums
+            .iter()
+            .zip(counts.iter())
+            .zip(centers.iter())
+            .map(|((sum, count), old_center)| {
+                if *count > 0 {
+                    sum / *count as f32
+                } else {
+                    // Empty cluster: keep its previous center rather than collapsing to 0.
+                    *old_center
+                }
+            })
+            .collect();
+
+        let converged = new_centers
+            .iter()
+            .zip(centers.iter())
+            .all(|(a, b)| (a - b).abs() < f32::EPSILON);
+        centers = new_centers;
+        if converged {
+            break;
+        }
+    }
+
+    let mut ranked_centers: Vec<(usize, f32)> = centers.iter().copied().enumerate().collect();
+    ranked_centers.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+
+    let mut level_by_cluster = vec![0u8; k];
+    for (level, (cluster, _)) in ranked_centers.into_iter().enumerate() {
+        level_by_cluster[cluster] = (level + 1) as u8;
+    }
+
+    for (el, size) in titles.iter().zip(sizes.iter()) {
+        let cluster = nearest_center(*size, &centers);
+        title_level.insert((el.page_id, el.id), level_by_cluster[cluster]);
+    }
 
-    let result = kmean.kmeans_lloyd(
-        title_buckets,
-        100,
-        KMeans::init_kmeanplusplus,
-        &KMeansConfig::default(),
-    );
+    title_level
+}
 
-    let centroids_sorted: Vec<_> = result
-        .centroids
+/// Index of the center closest to `size`.
+fn nearest_center(size: f32, centers: &[f32]) -> usize {
+    centers
         .iter()
         .enumerate()
-        .map(|(c_idx, c)| (c_idx, c[0]))
-        .sorted_by(|(_, c1), (_, c2)| c2.total_cmp(c1))
-        .collect();
+        .min_by(|(_, a), (_, b)| (*a - size).abs().total_cmp(&(*b - size).abs()))
+        .map(|(idx, _)| idx)
+        .expect("centers is never empty")
+}
+
+/// Seeds k-means centers at evenly spaced quantiles of the sorted, deduplicated size
+/// distribution so the initial clusters already roughly track the data's spread, rather than
+/// e.g. all starting at the same point.
+fn init_quantile_centers(sorted_unique_sizes: &[f32], k: usize) -> Vec<f32> {
+    (0..k)
+        .map(|i| {
+            let quantile = if k == 1 {
+                0.0
+            } else {
+                i as f32 / (k - 1) as f32
+            };
+            let idx = (quantile * (sorted_unique_sizes.len() - 1) as f32).round() as usize;
+            sorted_unique_sizes[idx]
+        })
+        .collect()
+}
 
-    let mut centroid_mapping = vec![-1i8; centroids_sorted.len()];
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{BBox, ElementText, ElementType};
 
-    let mut prev_centroid = (1, centroids_sorted[0].1);
-    for (c_idx, c_val) in centroids_sorted.iter() {
-        if *c_val < prev_centroid.1 * TITLE_MERGE_THRESHOLD {
-            prev_centroid.0 += 1;
+    fn title_with_font_size(id: usize, page_id: usize, font_size: f32) -> Element {
+        Element {
+            id,
+            layout_block_id: 0,
+            kind: ElementType::Title,
+            text_block: ElementText::default(),
+            page_id,
+            bbox: BBox {
+                x0: 0.0,
+                y0: 0.0,
+                x1: 10.0,
+                y1: 10.0,
+            },
+            table_rows: None,
+            line_font_sizes: vec![font_size],
+            spans: Vec::new(),
+            order: 0,
+            native_image: None,
         }
-        centroid_mapping[*c_idx] = prev_centroid.0;
-        prev_centroid.1 = *c_val;
     }
 
-    for (el, assignment) in titles.iter().zip(result.assignments.iter()) {
-        assert!(centroid_mapping[*assignment] >= 0);
-        title_level.insert((el.page_id, el.id), centroid_mapping[*assignment] as u8);
+    #[test]
+    fn ranks_clusters_largest_font_first() {
+        let titles = [
+            title_with_font_size(0, 0, 24.0),
+            title_with_font_size(1, 0, 24.0),
+            title_with_font_size(2, 0, 16.0),
+            title_with_font_size(3, 0, 16.0),
+            title_with_font_size(4, 0, 10.0),
+        ];
+        let refs: Vec<&Element> = titles.iter().collect();
+
+        let levels = title_levels_kmeans_with_k(&refs, 3);
+
+        assert_eq!(levels[&(0, 0)], 1);
+        assert_eq!(levels[&(0, 1)], 1);
+        assert_eq!(levels[&(0, 2)], 2);
+        assert_eq!(levels[&(0, 3)], 2);
+        assert_eq!(levels[&(0, 4)], 3);
+    }
+
+    #[test]
+    fn uniform_font_size_is_single_level() {
+        let titles = [
+            title_with_font_size(0, 0, 18.0),
+            title_with_font_size(1, 0, 18.0),
+            title_with_font_size(2, 0, 18.0),
+        ];
+        let refs: Vec<&Element> = titles.iter().collect();
+
+        let levels = title_levels_kmeans(&refs);
+
+        assert!(levels.values().all(|level| *level == 1));
     }
 
-    title_level
+    #[test]
+    fn k_is_clamped_to_distinct_sizes() {
+        let titles = [
+            title_with_font_size(0, 0, 20.0),
+            title_with_font_size(1, 0, 12.0),
+        ];
+        let refs: Vec<&Element> = titles.iter().collect();
+
+        let levels = title_levels_kmeans_with_k(&refs, 4);
+
+        assert_eq!(levels[&(0, 0)], 1);
+        assert_eq!(levels[&(0, 1)], 2);
+    }
 }
@@ -0,0 +1,134 @@
+use std::path::PathBuf;
+
+use regex::Regex;
+
+use crate::{
+    blocks::{Block, BlockType},
+    entities::AnnotationKind,
+};
+
+use super::{Render, Renderer};
+
+static LIST_BULLET_PATTERN: &str = r"(^|[\n ]|<[^>]*>)[•●○ഠ ം◦■▪▫–—-]( )";
+static ORDERED_LIST_PATTERN: &str = r"^\s*(\d+)[.)]\s";
+
+/// Renders `Block`s as [Gemtext](https://geminiprotocol.net/docs/gemtext.gmi). Gemtext is a
+/// line-oriented format with no inline markup (no bold/italic, no tables, no inline images), so
+/// unlike [`super::markdown::NativeMarkdownRenderer`] this renderer drops styled spans and
+/// instead leans on its line prefixes (`#`, `* `, `=>`) plus a preformatted-text toggle for
+/// content gemtext has no native shape for.
+#[derive(Debug)]
+pub struct GemtextRenderer {
+    img_src_path: PathBuf,
+    list_regex: Regex,
+    ordered_list_regex: Regex,
+    buf: String,
+}
+
+impl GemtextRenderer {
+    pub(crate) fn new(img_src_path: PathBuf) -> Self {
+        Self {
+            img_src_path,
+            list_regex: Regex::new(LIST_BULLET_PATTERN).unwrap(),
+            ordered_list_regex: Regex::new(ORDERED_LIST_PATTERN).unwrap(),
+            buf: String::new(),
+        }
+    }
+
+    pub fn finalize(self, _page_title: &str) -> String {
+        self.buf
+    }
+
+    fn push_block(&mut self, text: &str) {
+        if !self.buf.is_empty() {
+            self.buf.push('\n');
+        }
+        self.buf.push_str(text);
+        self.buf.push('\n');
+    }
+
+    fn push_line(&mut self, line: &str) {
+        self.buf.push_str(line);
+        self.buf.push('\n');
+    }
+
+    fn render_preformatted(&mut self, rows: &[Vec<String>]) {
+        let mut block = String::from("```\n");
+        for row in rows {
+            block.push_str(&row.join(" | "));
+            block.push('\n');
+        }
+        block.push_str("```");
+        self.push_block(&block);
+    }
+}
+
+impl Renderer for GemtextRenderer {
+    type Ok = ();
+
+    fn render_block(&mut self, block: &Block) -> anyhow::Result<Self::Ok> {
+        match &block.kind {
+            BlockType::Title(title) => {
+                // Gemtext only defines three heading levels.
+                let level = title.level.clamp(1, 3);
+                self.push_block(&format!("{} {}", "#".repeat(level as usize), title.text));
+            }
+            BlockType::Header(text_block) => self.push_block(&text_block.text),
+            BlockType::Footer(text_block) => self.push_block(&text_block.text),
+            BlockType::TextBlock(text_block) => self.push_block(&text_block.text),
+            BlockType::ListBlock(list) => {
+                for item in &list.items {
+                    let clean = self.ordered_list_regex.replace(item, "");
+                    let clean = self.list_regex.replace(&clean, "");
+                    self.push_line(&format!("* {clean}"));
+                }
+                self.buf.push('\n');
+            }
+            BlockType::Image(image_block) => {
+                // Gemtext can't inline an image, so it becomes its own link line. The caption,
+                // which an HTML/Markdown reader would see inline under the figure, is promoted
+                // into the link text here rather than being dropped.
+                let img_src = self.img_src_path.join(image_block.path());
+                let link_text = image_block.caption.as_deref().unwrap_or("image");
+                self.push_block(&format!("=> {} {link_text}", img_src.display()));
+            }
+            BlockType::Table(table) => {
+                self.render_preformatted(&table.rows);
+                if let Some(caption) = &table.caption {
+                    self.push_block(caption);
+                }
+            }
+            BlockType::Annotation(annotation) => match &annotation.kind {
+                AnnotationKind::Link { uri, .. } => {
+                    let uri = uri.as_deref().unwrap_or("");
+                    let link_text = annotation.text.as_deref().unwrap_or(uri);
+                    self.push_block(&format!("=> {uri} {link_text}"));
+                }
+                AnnotationKind::Highlight
+                | AnnotationKind::Underline
+                | AnnotationKind::StrikeOut => {
+                    if let Some(text) = &annotation.text {
+                        self.push_block(text);
+                    }
+                }
+                AnnotationKind::Note { text } => self.push_block(&format!("> {text}")),
+            },
+        }
+        Ok(())
+    }
+}
+
+#[tracing::instrument(skip_all)]
+pub fn to_gemtext<R: Render>(
+    blocks: R,
+    page_title: &str,
+    img_src_path: PathBuf,
+) -> anyhow::Result<String> {
+    let start = std::time::Instant::now();
+    let mut renderer = GemtextRenderer::new(img_src_path);
+    blocks.render(&mut renderer)?;
+    let gemtext = renderer.finalize(page_title);
+    crate::metrics::record_stage_latency("render", start.elapsed());
+    crate::metrics::record_bytes_out(gemtext.len() as u64);
+    Ok(gemtext)
+}
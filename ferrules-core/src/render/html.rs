@@ -1,19 +1,46 @@
 use std::path::PathBuf;
 
-use build_html::{Html, HtmlContainer, HtmlElement, HtmlPage, HtmlTag};
+use build_html::{Html, HtmlContainer, HtmlElement, HtmlPage, HtmlTag, Table as BuildHtmlTable};
 use regex::Regex;
 
-use crate::blocks::{Block, BlockType};
+use crate::{
+    blocks::{AnnotationBlock, Block, BlockType, ImageBlock, List, Table, TextBlock, Title},
+    entities::{AnnotationKind, StyledSpan},
+};
 
 use super::{Render, Renderer};
 
+/// Renders styled runs as inline HTML, wrapping each span in `<strong>`/`<em>` (or both) so
+/// bold/italic survive into the page. Falls back to the plain `text` when there are no spans.
+fn render_styled_text(text: &str, spans: &[StyledSpan]) -> String {
+    if spans.is_empty() {
+        return text.to_owned();
+    }
+    spans
+        .iter()
+        .map(|span| match (span.bold, span.italic) {
+            (true, true) => format!("<strong><em>{}</em></strong>", span.text),
+            (true, false) => format!("<strong>{}</strong>", span.text),
+            (false, true) => format!("<em>{}</em>", span.text),
+            (false, false) => span.text.clone(),
+        })
+        .collect()
+}
+
 static LIST_BULLET_PATTERN: &str = r"(^|[\n ]|<[^>]*>)[•●○ഠ ം◦■▪▫–—-]( )";
+/// Matches a numbered list marker (`1.`, `2)`) at the start of an item, capturing the number so
+/// the rendered `<ol>` can start at the right offset.
+static ORDERED_LIST_PATTERN: &str = r"^\s*(\d+)[.)]\s";
+/// Matches a lettered list marker (`a.`, `b)`) at the start of an item.
+static LETTERED_LIST_PATTERN: &str = r"^\s*[a-z][.)]\s";
 
 #[derive(Debug)]
 pub struct HTMLRenderer {
     root_element: HtmlElement,
     img_src_path: PathBuf,
     list_regex: Regex,
+    ordered_list_regex: Regex,
+    lettered_list_regex: Regex,
 }
 
 impl HTMLRenderer {
@@ -21,11 +48,15 @@ impl HTMLRenderer {
         let root = HtmlElement::new(HtmlTag::Div);
 
         let list_regex = Regex::new(LIST_BULLET_PATTERN).unwrap();
+        let ordered_list_regex = Regex::new(ORDERED_LIST_PATTERN).unwrap();
+        let lettered_list_regex = Regex::new(LETTERED_LIST_PATTERN).unwrap();
 
         Self {
             root_element: root,
             img_src_path,
             list_regex,
+            ordered_list_regex,
+            lettered_list_regex,
         }
     }
     pub fn finalize(self, page_title: &str) -> String {
@@ -36,79 +67,193 @@ impl HTMLRenderer {
     }
 }
 
-impl Renderer for HTMLRenderer {
-    type Ok = ();
+impl HTMLRenderer {
+    /// One method per [`BlockType`] variant, so a caller who only wants to change how one kind
+    /// of block renders (e.g. slugified heading anchors) can reimplement just that method on
+    /// their own [`Renderer`] rather than copying this whole match statement.
+    fn render_title(&mut self, title: &Title) {
+        // Convert title level to appropriate h1-h6 tag
+        let level = title.level.clamp(1, 6);
+        let tag = match level {
+            1 => HtmlTag::Heading1,
+            2 => HtmlTag::Heading2,
+            3 => HtmlTag::Heading3,
+            4 => HtmlTag::Heading4,
+            5 => HtmlTag::Heading5,
+            _ => HtmlTag::Heading6,
+        };
+        let el = HtmlElement::new(tag)
+            .with_child(title.text.as_str().into())
+            .into();
+        self.root_element.add_child(el);
+    }
 
-    fn render_block(&mut self, block: &Block) -> anyhow::Result<Self::Ok> {
-        match &block.kind {
-            BlockType::Title(title) => {
-                // Convert title level to appropriate h1-h6 tag
-                let level = title.level.clamp(1, 6);
-                let tag = match level {
-                    1 => HtmlTag::Heading1,
-                    2 => HtmlTag::Heading2,
-                    3 => HtmlTag::Heading3,
-                    4 => HtmlTag::Heading4,
-                    5 => HtmlTag::Heading5,
-                    _ => HtmlTag::Heading6,
+    fn render_header(&mut self, text_block: &TextBlock) {
+        let rendered_text = render_styled_text(&text_block.text, &text_block.spans);
+        let el = HtmlElement::new(HtmlTag::Header)
+            .with_child(rendered_text.as_str().into())
+            .into();
+        self.root_element.add_child(el);
+    }
+
+    fn render_footer(&mut self, text_block: &TextBlock) {
+        let rendered_text = render_styled_text(&text_block.text, &text_block.spans);
+        let el = HtmlElement::new(HtmlTag::Footer)
+            .with_child(rendered_text.as_str().into())
+            .into();
+        self.root_element.add_child(el);
+    }
+
+    fn render_list(&mut self, list: &List) {
+        let first_item = list.items.first().map(String::as_str).unwrap_or("");
+        let ordered_start = self
+            .ordered_list_regex
+            .captures(first_item)
+            .and_then(|c| c.get(1))
+            .and_then(|m| m.as_str().parse::<u32>().ok());
+        let is_ordered = ordered_start.is_some() || self.lettered_list_regex.is_match(first_item);
+
+        let mut list_el = HtmlElement::new(if is_ordered {
+            HtmlTag::OrderedList
+        } else {
+            HtmlTag::UnorderedList
+        });
+        if let Some(start) = ordered_start.filter(|&n| n != 1) {
+            list_el = list_el.with_attribute("start", &start.to_string());
+        }
+
+        for item in &list.items {
+            let clean_text = if is_ordered {
+                self.ordered_list_regex.replace(item, "").into_owned()
+            } else {
+                self.list_regex.replace(item, "").into_owned()
+            };
+            let li = HtmlElement::new(HtmlTag::ListElement)
+                .with_child(clean_text.as_str().into())
+                .into();
+            list_el.add_child(li);
+        }
+        self.root_element.add_child(list_el.into());
+    }
+
+    fn render_text(&mut self, text_block: &TextBlock) {
+        let styled_text = render_styled_text(&text_block.text, &text_block.spans);
+        let rendered_text = super::math::render_inline_math(&styled_text);
+        let el = HtmlElement::new(HtmlTag::ParagraphText)
+            .with_child(rendered_text.as_str().into())
+            .into();
+        self.root_element.add_child(el);
+    }
+
+    fn render_image(&mut self, image_block: &ImageBlock) {
+        let mut figure = HtmlElement::new(HtmlTag::Figure);
+        let img_src = self
+            .img_src_path
+            .join(image_block.path())
+            .to_str()
+            .unwrap()
+            .to_owned();
+        let alt = image_block.alt_text.as_deref().unwrap_or("");
+        let img = HtmlElement::new(HtmlTag::Image).with_image(img_src, alt);
+        figure.add_child(img.into());
+
+        if let Some(caption) = &image_block.caption {
+            let figcaption = HtmlElement::new(HtmlTag::Figcaption)
+                .with_child(caption.as_str().into())
+                .into();
+            figure.add_child(figcaption);
+        }
+
+        self.root_element.add_child(figure.into());
+    }
+
+    fn render_table(&mut self, table: &Table) {
+        // A "table" with no detected column boundary is really just stacked text
+        // (e.g. a layout model false positive on a list or a caption block), so
+        // render it as paragraphs instead of a degenerate one-column table.
+        if table.rows.iter().all(|row| row.len() <= 1) {
+            for row in &table.rows {
+                let Some(text) = row.first() else {
+                    continue;
                 };
-                let el = HtmlElement::new(tag)
-                    .with_child(title.text.as_str().into())
-                    .into();
-                self.root_element.add_child(el);
-            }
-            BlockType::Header(text_block) => {
-                let el = HtmlElement::new(HtmlTag::Header)
-                    .with_child(text_block.text.as_str().into())
+                let el = HtmlElement::new(HtmlTag::ParagraphText)
+                    .with_child(text.as_str().into())
                     .into();
                 self.root_element.add_child(el);
             }
-            BlockType::Footer(text_block) => {
-                let el = HtmlElement::new(HtmlTag::Footer)
-                    .with_child(text_block.text.as_str().into())
+            if let Some(caption) = &table.caption {
+                let el = HtmlElement::new(HtmlTag::ParagraphText)
+                    .with_child(caption.as_str().into())
                     .into();
                 self.root_element.add_child(el);
             }
-            BlockType::ListBlock(list) => {
-                let mut ul = HtmlElement::new(HtmlTag::UnorderedList);
-                for item in &list.items {
-                    let clean_text = self.list_regex.replace(item, "").into_owned();
-                    let li = HtmlElement::new(HtmlTag::ListElement)
-                        .with_child(clean_text.as_str().into())
-                        .into();
-                    ul.add_child(li);
-                }
-                self.root_element.add_child(ul.into());
+        } else {
+            // The first row is treated as the header (`<thead>`), the rest as `<tbody>`
+            // rows; `Table` only tracks flat cell text, so colspan/rowspan aren't
+            // available to render yet.
+            let mut html_table = BuildHtmlTable::new();
+            let mut rows = table.rows.iter();
+            if let Some(header_row) = rows.next() {
+                html_table.add_header_row(header_row.clone());
             }
-            BlockType::TextBlock(text_block) => {
-                let el = HtmlElement::new(HtmlTag::ParagraphText)
-                    .with_child(text_block.text.as_str().into())
-                    .into();
-                self.root_element.add_child(el);
+            for row in rows {
+                html_table.add_body_row(row.clone());
             }
-            BlockType::Image(image_block) => {
-                let mut figure = HtmlElement::new(HtmlTag::Figure);
-                let img_src = self
-                    .img_src_path
-                    .join(image_block.path())
-                    .to_str()
-                    .unwrap()
-                    .to_owned();
-                let img = HtmlElement::new(HtmlTag::Image).with_image(img_src, "");
-                figure.add_child(img.into());
-
-                if let Some(caption) = &image_block.caption {
-                    let figcaption = HtmlElement::new(HtmlTag::Figcaption)
-                        .with_child(caption.as_str().into())
-                        .into();
-                    figure.add_child(figcaption);
-                }
 
+            if let Some(caption) = &table.caption {
+                let mut figure = HtmlElement::new(HtmlTag::Figure);
+                figure.add_child(html_table.into());
+                let figcaption = HtmlElement::new(HtmlTag::Figcaption)
+                    .with_child(caption.as_str().into())
+                    .into();
+                figure.add_child(figcaption);
                 self.root_element.add_child(figure.into());
+            } else {
+                self.root_element.add_child(html_table.into());
             }
-            _ => {
-                eprintln!("not implemented yet")
-            }
+        }
+    }
+
+    fn render_annotation(&mut self, annotation: &AnnotationBlock) {
+        let text = annotation.text.as_deref().unwrap_or_default();
+        let el = match &annotation.kind {
+            AnnotationKind::Highlight => HtmlElement::new(HtmlTag::Span)
+                .with_attribute("class", "highlight")
+                .with_child(text.into()),
+            AnnotationKind::Underline => HtmlElement::new(HtmlTag::Span)
+                .with_attribute("class", "underline")
+                .with_child(text.into()),
+            AnnotationKind::StrikeOut => HtmlElement::new(HtmlTag::Span)
+                .with_attribute("class", "strikeout")
+                .with_child(text.into()),
+            AnnotationKind::Link { uri, .. } => HtmlElement::new(HtmlTag::Anchor)
+                .with_attribute("href", uri.as_deref().unwrap_or(""))
+                .with_child(if text.is_empty() {
+                    uri.as_deref().unwrap_or("").into()
+                } else {
+                    text.into()
+                }),
+            AnnotationKind::Note { text } => HtmlElement::new(HtmlTag::Span)
+                .with_attribute("class", "note")
+                .with_child(text.as_str().into()),
+        };
+        self.root_element.add_child(el.into());
+    }
+}
+
+impl Renderer for HTMLRenderer {
+    type Ok = ();
+
+    fn render_block(&mut self, block: &Block) -> anyhow::Result<Self::Ok> {
+        match &block.kind {
+            BlockType::Title(title) => self.render_title(title),
+            BlockType::Header(text_block) => self.render_header(text_block),
+            BlockType::Footer(text_block) => self.render_footer(text_block),
+            BlockType::ListBlock(list) => self.render_list(list),
+            BlockType::TextBlock(text_block) => self.render_text(text_block),
+            BlockType::Image(image_block) => self.render_image(image_block),
+            BlockType::Table(table) => self.render_table(table),
+            BlockType::Annotation(annotation) => self.render_annotation(annotation),
         }
         Ok(())
     }
@@ -120,7 +265,11 @@ pub fn to_html<R: Render>(
     page_title: &str,
     img_src_path: PathBuf,
 ) -> anyhow::Result<String> {
+    let start = std::time::Instant::now();
     let mut html_renderer = HTMLRenderer::new(img_src_path);
     blocks.render(&mut html_renderer)?;
-    Ok(html_renderer.finalize(page_title))
+    let html = html_renderer.finalize(page_title);
+    crate::metrics::record_stage_latency("render", start.elapsed());
+    crate::metrics::record_bytes_out(html.len() as u64);
+    Ok(html)
 }
@@ -1,11 +1,64 @@
 use std::path::PathBuf;
 
 use html2md::parse_html;
+use regex::Regex;
 
-use crate::blocks::Block;
+use crate::{
+    blocks::{AnnotationBlock, Block, BlockType, ImageBlock, List, Table, TextBlock, Title},
+    entities::{AnnotationKind, StyledSpan},
+};
 
 use super::{html::HTMLRenderer, Render, Renderer};
 
+/// Renders styled runs as CommonMark emphasis, wrapping each span's trimmed text in `**`/`*`/
+/// `***` (bold/italic/both) while keeping surrounding whitespace outside the markers, since
+/// CommonMark doesn't allow emphasis markers to border whitespace. Falls back to the plain
+/// `text` when there are no spans to render (e.g. an empty/legacy `TextBlock`).
+fn render_styled_text(text: &str, spans: &[StyledSpan]) -> String {
+    if spans.is_empty() {
+        return text.to_owned();
+    }
+    spans
+        .iter()
+        .map(|span| {
+            let marker = match (span.bold, span.italic) {
+                (true, true) => "***",
+                (true, false) => "**",
+                (false, true) => "*",
+                (false, false) => "",
+            };
+            if marker.is_empty() {
+                return span.text.clone();
+            }
+            let trimmed = span.text.trim();
+            if trimmed.is_empty() {
+                return span.text.clone();
+            }
+            let lead = &span.text[..span.text.len() - span.text.trim_start().len()];
+            let trail = &span.text[span.text.trim_end().len()..];
+            format!("{lead}{marker}{trimmed}{marker}{trail}")
+        })
+        .collect()
+}
+
+static LIST_BULLET_PATTERN: &str = r"(^|[\n ]|<[^>]*>)[•●○ഠ ം◦■▪▫–—-]( )";
+static ORDERED_LIST_PATTERN: &str = r"^\s*(\d+)[.)]\s";
+static LETTERED_LIST_PATTERN: &str = r"^\s*[a-z][.)]\s";
+
+/// Which renderer `to_markdown` uses to turn [`Block`]s into CommonMark/GFM.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MarkdownFlavor {
+    /// Render every block to HTML via [`HTMLRenderer`], then run `html2md` over the whole
+    /// page. This is the original path: simple, but structure only survives as well as the
+    /// HTML round-trip allows, and link/image paths can get mangled.
+    #[default]
+    ViaHtml,
+    /// Walk `Block`s and emit CommonMark/GFM directly, with no HTML in between. Higher
+    /// fidelity for headings, lists, and tables at the cost of a second renderer to keep in
+    /// sync with [`HTMLRenderer`].
+    Native,
+}
+
 #[derive(Debug)]
 pub struct MarkdownRender {
     html_renderer: HTMLRenderer,
@@ -30,13 +83,200 @@ impl Renderer for MarkdownRender {
     }
 }
 
+/// Renders `Block`s straight to CommonMark/GFM, skipping the HTML round-trip that
+/// [`MarkdownRender`] goes through.
+#[derive(Debug)]
+pub struct NativeMarkdownRenderer {
+    img_src_path: PathBuf,
+    list_regex: Regex,
+    ordered_list_regex: Regex,
+    lettered_list_regex: Regex,
+    buf: String,
+}
+
+impl NativeMarkdownRenderer {
+    pub(crate) fn new(img_src_path: PathBuf) -> Self {
+        Self {
+            img_src_path,
+            list_regex: Regex::new(LIST_BULLET_PATTERN).unwrap(),
+            ordered_list_regex: Regex::new(ORDERED_LIST_PATTERN).unwrap(),
+            lettered_list_regex: Regex::new(LETTERED_LIST_PATTERN).unwrap(),
+            buf: String::new(),
+        }
+    }
+
+    pub fn finalize(self, _page_title: &str) -> String {
+        self.buf
+    }
+
+    fn push_block(&mut self, text: &str) {
+        if !self.buf.is_empty() {
+            self.buf.push('\n');
+        }
+        self.buf.push_str(text);
+        self.buf.push('\n');
+    }
+
+    fn render_table_rows(&mut self, rows: &[Vec<String>]) {
+        // A one-column "table" is really just stacked text (see HTMLRenderer::render_table),
+        // so fall back to paragraphs rather than emitting a degenerate GFM table.
+        if rows.iter().all(|row| row.len() <= 1) {
+            for row in rows {
+                if let Some(text) = row.first() {
+                    self.push_block(text.trim());
+                }
+            }
+            return;
+        }
+
+        let escape_cell = |cell: &str| cell.replace('|', "\\|");
+        let mut table = String::new();
+        if let Some(header) = rows.first() {
+            table.push_str("| ");
+            table.push_str(
+                &header
+                    .iter()
+                    .map(|c| escape_cell(c))
+                    .collect::<Vec<_>>()
+                    .join(" | "),
+            );
+            table.push_str(" |\n| ");
+            table.push_str(&vec!["---"; header.len()].join(" | "));
+            table.push_str(" |\n");
+        }
+        for row in rows.iter().skip(1) {
+            table.push_str("| ");
+            table.push_str(
+                &row.iter()
+                    .map(|c| escape_cell(c))
+                    .collect::<Vec<_>>()
+                    .join(" | "),
+            );
+            table.push_str(" |\n");
+        }
+        self.push_block(table.trim_end());
+    }
+
+    /// One method per [`BlockType`] variant, so a caller who only wants to change how one kind
+    /// of block renders can reimplement just that method on their own [`Renderer`] rather than
+    /// copying this whole match statement.
+    fn render_title(&mut self, title: &Title) {
+        let level = title.level.clamp(1, 6);
+        self.push_block(&format!("{} {}", "#".repeat(level as usize), title.text));
+    }
+
+    fn render_header(&mut self, text_block: &TextBlock) {
+        let text = render_styled_text(&text_block.text, &text_block.spans);
+        self.push_block(&format!("<!-- header -->\n{text}"));
+    }
+
+    fn render_footer(&mut self, text_block: &TextBlock) {
+        let text = render_styled_text(&text_block.text, &text_block.spans);
+        self.push_block(&format!("<!-- footer -->\n{text}"));
+    }
+
+    fn render_text(&mut self, text_block: &TextBlock) {
+        self.push_block(&render_styled_text(&text_block.text, &text_block.spans));
+    }
+
+    fn render_list(&mut self, list: &List) {
+        let first_item = list.items.first().map(String::as_str).unwrap_or("");
+        let ordered_start = self
+            .ordered_list_regex
+            .captures(first_item)
+            .and_then(|c| c.get(1))
+            .and_then(|m| m.as_str().parse::<u32>().ok());
+        let is_ordered = ordered_start.is_some() || self.lettered_list_regex.is_match(first_item);
+
+        let items = list
+            .items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| {
+                if is_ordered {
+                    let n = ordered_start.unwrap_or(1) + i as u32;
+                    format!("{n}. {}", self.ordered_list_regex.replace(item, ""))
+                } else {
+                    format!("- {}", self.list_regex.replace(item, ""))
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.push_block(&items);
+    }
+
+    fn render_image(&mut self, image_block: &ImageBlock) {
+        let img_src = self.img_src_path.join(image_block.path());
+        let alt = image_block.alt_text.as_deref().unwrap_or("");
+        self.push_block(&format!("![{alt}]({})", img_src.display()));
+        if let Some(caption) = &image_block.caption {
+            self.push_block(&format!("*{caption}*"));
+        }
+    }
+
+    fn render_table(&mut self, table: &Table) {
+        self.render_table_rows(&table.rows);
+        if let Some(caption) = &table.caption {
+            self.push_block(&format!("*{caption}*"));
+        }
+    }
+
+    fn render_annotation(&mut self, annotation: &AnnotationBlock) {
+        let text = annotation.text.as_deref().unwrap_or_default();
+        match &annotation.kind {
+            AnnotationKind::Highlight => self.push_block(&format!("=={text}==")),
+            AnnotationKind::Underline | AnnotationKind::StrikeOut => {
+                self.push_block(&format!("~~{text}~~"))
+            }
+            AnnotationKind::Link { uri, .. } => {
+                let uri = uri.as_deref().unwrap_or("");
+                let label = if text.is_empty() { uri } else { text };
+                self.push_block(&format!("[{label}]({uri})"));
+            }
+            AnnotationKind::Note { text } => self.push_block(&format!("> {text}")),
+        }
+    }
+}
+
+impl Renderer for NativeMarkdownRenderer {
+    type Ok = ();
+
+    fn render_block(&mut self, block: &Block) -> anyhow::Result<Self::Ok> {
+        match &block.kind {
+            BlockType::Title(title) => self.render_title(title),
+            BlockType::Header(text_block) => self.render_header(text_block),
+            BlockType::Footer(text_block) => self.render_footer(text_block),
+            BlockType::TextBlock(text_block) => self.render_text(text_block),
+            BlockType::ListBlock(list) => self.render_list(list),
+            BlockType::Image(image_block) => self.render_image(image_block),
+            BlockType::Table(table) => self.render_table(table),
+            BlockType::Annotation(annotation) => self.render_annotation(annotation),
+        }
+        Ok(())
+    }
+}
+
 #[tracing::instrument(skip_all)]
 pub fn to_markdown<R: Render>(
     blocks: R,
     page_title: &str,
     img_src_path: PathBuf,
+    flavor: MarkdownFlavor,
 ) -> anyhow::Result<String> {
-    let mut html_renderer = MarkdownRender::new(img_src_path);
-    blocks.render(&mut html_renderer)?;
-    Ok(html_renderer.finalize(page_title))
+    let start = std::time::Instant::now();
+    let markdown = match flavor {
+        MarkdownFlavor::ViaHtml => {
+            let mut renderer = MarkdownRender::new(img_src_path);
+            blocks.render(&mut renderer)?;
+            renderer.finalize(page_title)
+        }
+        MarkdownFlavor::Native => {
+            let mut renderer = NativeMarkdownRenderer::new(img_src_path);
+            blocks.render(&mut renderer)?;
+            renderer.finalize(page_title)
+        }
+    };
+    crate::metrics::record_stage_latency("render", start.elapsed());
+    crate::metrics::record_bytes_out(markdown.len() as u64);
+    Ok(markdown)
 }
@@ -0,0 +1,293 @@
+//! A small LaTeX-to-MathML mapper used by [`super::html::HTMLRenderer`] to render inline math
+//! spans (`$...$` / `\(...\)`) found inside `TextBlock` text.
+//!
+//! Only a subset of LaTeX is understood -- superscripts, subscripts, `\frac{a}{b}`, Greek
+//! letters, and a handful of common operators -- which is enough for the equations that show up
+//! inline in running text. Anything outside that subset (or a malformed expression) falls back
+//! to the raw LaTeX inside a `<span class="math-tex">`, so client-side KaTeX/MathJax can still
+//! pick it up.
+//!
+//! There's no display-mode equation support here: the layout model's `Formula` boxes become
+//! plain `Text` elements (see [`crate::entities::Element::from_layout_block`]) and `BlockType`
+//! has no `Equation` variant, so math is only rendered inline and no `<math display="block">`
+//! element is ever emitted.
+
+/// Scans `text` for `$...$` and `\(...\)` delimited spans and replaces each with an inline
+/// `<math>` element, leaving everything else untouched. An escaped `\$` never opens a span, and
+/// a delimiter with no matching close is left as literal text.
+pub(crate) fn render_inline_math(text: &str) -> String {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let len = chars.len();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+
+    while i < len {
+        let (_, ch) = chars[i];
+        let prev_is_backslash = i > 0 && chars[i - 1].1 == '\\';
+
+        if ch == '$' && !prev_is_backslash {
+            if let Some((mathml, next_i)) = parse_span(&chars, text, i + 1, len, SpanKind::Dollar) {
+                out.push_str(&mathml);
+                i = next_i;
+                continue;
+            }
+        } else if ch == '\\' && i + 1 < len && chars[i + 1].1 == '(' {
+            if let Some((mathml, next_i)) = parse_span(&chars, text, i + 2, len, SpanKind::Paren) {
+                out.push_str(&mathml);
+                i = next_i;
+                continue;
+            }
+        }
+        out.push(ch);
+        i += 1;
+    }
+    out
+}
+
+enum SpanKind {
+    /// Closed by a bare `$`.
+    Dollar,
+    /// Closed by `\)`.
+    Paren,
+}
+
+/// Looks for the closing delimiter starting at char index `start`. Returns the rendered
+/// `<math>` element and the index just past the closing delimiter, or `None` if the span is
+/// never closed (the caller then leaves the opening delimiter as literal text).
+fn parse_span(
+    chars: &[(usize, char)],
+    text: &str,
+    start: usize,
+    len: usize,
+    kind: SpanKind,
+) -> Option<(String, usize)> {
+    let mut j = start;
+    while j < len {
+        let closes = match kind {
+            SpanKind::Dollar => chars[j].1 == '$',
+            SpanKind::Paren => chars[j].1 == '\\' && j + 1 < len && chars[j + 1].1 == ')',
+        };
+        if closes {
+            let body_start = chars[start].0;
+            let body_end = chars[j].0;
+            let body = &text[body_start..body_end];
+            let next_i = match kind {
+                SpanKind::Dollar => j + 1,
+                SpanKind::Paren => j + 2,
+            };
+            return Some((format!("<math>{}</math>", latex_to_mathml(body)), next_i));
+        }
+        j += 1;
+    }
+    None
+}
+
+fn latex_to_mathml(latex: &str) -> String {
+    let trimmed = latex.trim();
+    if trimmed.is_empty() {
+        return String::new();
+    }
+    match try_convert(trimmed) {
+        Some(mathml) => mathml,
+        None => format!("<span class=\"math-tex\">{}</span>", html_escape(trimmed)),
+    }
+}
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    /// A `\command` name, without the leading backslash.
+    Command(String),
+    Caret,
+    Underscore,
+    GroupOpen,
+    GroupClose,
+    Char(char),
+}
+
+fn tokenize(latex: &str) -> Option<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = latex.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                let mut cmd = String::new();
+                while let Some(&next) = chars.peek() {
+                    if next.is_ascii_alphabetic() {
+                        cmd.push(next);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                if cmd.is_empty() {
+                    tokens.push(Token::Char(chars.next()?));
+                } else {
+                    tokens.push(Token::Command(cmd));
+                }
+            }
+            '^' => tokens.push(Token::Caret),
+            '_' => tokens.push(Token::Underscore),
+            '{' => tokens.push(Token::GroupOpen),
+            '}' => tokens.push(Token::GroupClose),
+            c if c.is_whitespace() => {}
+            c => tokens.push(Token::Char(c)),
+        }
+    }
+    Some(tokens)
+}
+
+/// Unicode for the Greek letters and common operator commands this mapper understands; `None`
+/// signals an unsupported command, which bubbles up as a fallback to raw LaTeX.
+fn symbol_for(command: &str) -> Option<&'static str> {
+    Some(match command {
+        "alpha" => "α",
+        "beta" => "β",
+        "gamma" => "γ",
+        "delta" => "δ",
+        "epsilon" => "ε",
+        "zeta" => "ζ",
+        "eta" => "η",
+        "theta" => "θ",
+        "iota" => "ι",
+        "kappa" => "κ",
+        "lambda" => "λ",
+        "mu" => "μ",
+        "nu" => "ν",
+        "xi" => "ξ",
+        "pi" => "π",
+        "rho" => "ρ",
+        "sigma" => "σ",
+        "tau" => "τ",
+        "upsilon" => "υ",
+        "phi" => "φ",
+        "chi" => "χ",
+        "psi" => "ψ",
+        "omega" => "ω",
+        "Gamma" => "Γ",
+        "Delta" => "Δ",
+        "Theta" => "Θ",
+        "Lambda" => "Λ",
+        "Sigma" => "Σ",
+        "Phi" => "Φ",
+        "Psi" => "Ψ",
+        "Omega" => "Ω",
+        "times" => "×",
+        "cdot" => "⋅",
+        "div" => "÷",
+        "pm" => "±",
+        "le" | "leq" => "≤",
+        "ge" | "geq" => "≥",
+        "neq" => "≠",
+        "approx" => "≈",
+        "infty" => "∞",
+        "partial" => "∂",
+        "sum" => "∑",
+        "int" => "∫",
+        _ => return None,
+    })
+}
+
+/// Parses the whole token stream as one sequence; fails (falling back to raw LaTeX) if anything
+/// is left over, which also catches mismatched braces.
+fn try_convert(latex: &str) -> Option<String> {
+    let tokens = tokenize(latex)?;
+    let mut pos = 0;
+    let mathml = parse_sequence(&tokens, &mut pos)?;
+    if pos != tokens.len() {
+        return None;
+    }
+    Some(mathml)
+}
+
+/// Parses a run of atoms, attaching a trailing `^`/`_` to the atom immediately before it.
+/// Stops (without consuming) at a `}` so [`parse_atom`]'s `{...}` case can find it.
+fn parse_sequence(tokens: &[Token], pos: &mut usize) -> Option<String> {
+    let mut out = String::new();
+    let mut last_atom: Option<String> = None;
+
+    while *pos < tokens.len() && tokens[*pos] != Token::GroupClose {
+        match tokens[*pos] {
+            Token::Caret | Token::Underscore => {
+                let is_sup = tokens[*pos] == Token::Caret;
+                *pos += 1;
+                let attachment = parse_atom(tokens, pos)?;
+                let base = last_atom.take().unwrap_or_default();
+                let tag = if is_sup { "msup" } else { "msub" };
+                last_atom = Some(format!(
+                    "<{tag}><mrow>{base}</mrow><mrow>{attachment}</mrow></{tag}>"
+                ));
+            }
+            _ => {
+                if let Some(atom) = last_atom.take() {
+                    out.push_str(&atom);
+                }
+                last_atom = Some(parse_atom(tokens, pos)?);
+            }
+        }
+    }
+    if let Some(atom) = last_atom.take() {
+        out.push_str(&atom);
+    }
+    Some(out)
+}
+
+fn parse_atom(tokens: &[Token], pos: &mut usize) -> Option<String> {
+    match tokens.get(*pos)? {
+        Token::GroupOpen => {
+            *pos += 1;
+            let inner = parse_sequence(tokens, pos)?;
+            if tokens.get(*pos) != Some(&Token::GroupClose) {
+                return None;
+            }
+            *pos += 1;
+            Some(inner)
+        }
+        Token::Command(cmd) if cmd == "frac" => {
+            *pos += 1;
+            let num = parse_atom(tokens, pos)?;
+            let den = parse_atom(tokens, pos)?;
+            Some(format!(
+                "<mfrac><mrow>{num}</mrow><mrow>{den}</mrow></mfrac>"
+            ))
+        }
+        Token::Command(cmd) => {
+            let symbol = symbol_for(cmd)?;
+            *pos += 1;
+            Some(format!("<mi>{symbol}</mi>"))
+        }
+        Token::Char(c) if c.is_ascii_digit() => {
+            let mut number = String::new();
+            while let Some(Token::Char(d)) = tokens.get(*pos) {
+                if d.is_ascii_digit() {
+                    number.push(*d);
+                    *pos += 1;
+                } else {
+                    break;
+                }
+            }
+            Some(format!("<mn>{number}</mn>"))
+        }
+        Token::Char(c) if c.is_alphabetic() => {
+            let c = *c;
+            *pos += 1;
+            Some(format!("<mi>{c}</mi>"))
+        }
+        Token::Char(c) if matches!(c, '+' | '-' | '=' | '(' | ')' | ',') => {
+            let c = *c;
+            *pos += 1;
+            Some(format!("<mo>{c}</mo>"))
+        }
+        Token::Char(c) if matches!(c, '<' | '>') => {
+            let c = *c;
+            *pos += 1;
+            Some(format!("<mo>{}</mo>", html_escape(&c.to_string())))
+        }
+        _ => None,
+    }
+}
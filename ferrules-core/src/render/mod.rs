@@ -2,8 +2,11 @@ use anyhow::Context;
 
 use crate::{blocks::Block, entities::ParsedDocument};
 
+pub mod gemtext;
 pub mod html;
 pub mod markdown;
+mod math;
+pub mod writer;
 
 pub trait Render {
     type Output;
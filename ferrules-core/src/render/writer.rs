@@ -0,0 +1,133 @@
+//! Pluggable output serializers for a [`ParsedDocument`], so adding a format is implementing a
+//! trait instead of copy-pasting another `File::create`/`BufWriter` block into
+//! `save_parsed_document`.
+//!
+//! [`JsonWriter`], [`HtmlWriter`] and [`MarkdownWriter`] serialize the whole document before
+//! returning; [`JsonlWriter`] instead writes one block per line straight into `out` as it goes,
+//! so a multi-thousand-page document is never held as a single giant in-memory blob. The trait
+//! is public so downstream crates can register their own (e.g. a Parquet exporter).
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::entities::ParsedDocument;
+
+use super::{
+    html::to_html,
+    markdown::{to_markdown, MarkdownFlavor},
+};
+
+/// Serializes a [`ParsedDocument`] to one output format.
+pub trait DocumentWriter {
+    /// File extension (without the leading dot) this writer's output is conventionally saved
+    /// under, e.g. `"json"`.
+    fn extension(&self) -> &'static str;
+
+    /// Whether `write`'s output is safe to buffer in memory for `save_parsed_document`'s
+    /// incremental "skip if unchanged" comparison. Writers whose whole point is to avoid
+    /// materializing the full output (like [`JsonlWriter`]) should override this to `false`.
+    fn buffered(&self) -> bool {
+        true
+    }
+
+    /// Renders `doc` into `out`. `figures_dir` is the path (relative to the eventual output
+    /// file) that figure images were saved under, for writers that embed image references.
+    fn write(
+        &self,
+        doc: &ParsedDocument,
+        out: &mut dyn Write,
+        figures_dir: Option<&Path>,
+    ) -> anyhow::Result<()>;
+}
+
+/// Full-document JSON, matching [`ParsedDocument`]'s `Serialize` impl.
+pub struct JsonWriter;
+
+impl DocumentWriter for JsonWriter {
+    fn extension(&self) -> &'static str {
+        "json"
+    }
+
+    fn write(
+        &self,
+        doc: &ParsedDocument,
+        out: &mut dyn Write,
+        _figures_dir: Option<&Path>,
+    ) -> anyhow::Result<()> {
+        serde_json::to_writer(out, doc)?;
+        Ok(())
+    }
+}
+
+/// Single self-contained HTML page.
+pub struct HtmlWriter;
+
+impl DocumentWriter for HtmlWriter {
+    fn extension(&self) -> &'static str {
+        "html"
+    }
+
+    fn write(
+        &self,
+        doc: &ParsedDocument,
+        out: &mut dyn Write,
+        figures_dir: Option<&Path>,
+    ) -> anyhow::Result<()> {
+        let img_src_path = figures_dir
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| PathBuf::from("figures"));
+        let html = to_html(doc, &doc.doc_name, img_src_path)?;
+        out.write_all(html.as_bytes())?;
+        Ok(())
+    }
+}
+
+/// Markdown, rendered via the HTML-backed flavor (see [`MarkdownFlavor`]).
+pub struct MarkdownWriter;
+
+impl DocumentWriter for MarkdownWriter {
+    fn extension(&self) -> &'static str {
+        "md"
+    }
+
+    fn write(
+        &self,
+        doc: &ParsedDocument,
+        out: &mut dyn Write,
+        figures_dir: Option<&Path>,
+    ) -> anyhow::Result<()> {
+        let img_src_path = figures_dir
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| PathBuf::from("figures"));
+        let markdown = to_markdown(doc, &doc.doc_name, img_src_path, MarkdownFlavor::ViaHtml)?;
+        out.write_all(markdown.as_bytes())?;
+        Ok(())
+    }
+}
+
+/// One JSON object per block, newline-delimited, written directly into `out` as each block is
+/// serialized rather than building a single JSON array/string for the whole document.
+pub struct JsonlWriter;
+
+impl DocumentWriter for JsonlWriter {
+    fn extension(&self) -> &'static str {
+        "jsonl"
+    }
+
+    fn buffered(&self) -> bool {
+        false
+    }
+
+    fn write(
+        &self,
+        doc: &ParsedDocument,
+        out: &mut dyn Write,
+        _figures_dir: Option<&Path>,
+    ) -> anyhow::Result<()> {
+        for block in &doc.blocks {
+            serde_json::to_writer(&mut *out, block)?;
+            out.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,239 @@
+//! Resolves a document identified by a URI (a local path or an `http(s)://` URL) to bytes, so
+//! a caller can queue a parse without staging the document to disk themselves first.
+
+use std::net::IpAddr;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use bytes::Bytes;
+use tokio_stream::StreamExt;
+
+/// A pluggable source of document bytes, keyed by URI. Implementations are expected to be
+/// cheap to clone/share -- [`crate::parse::native::ParseNativeQueue`] holds one behind an
+/// `Arc` for the lifetime of the queue.
+#[async_trait]
+pub trait ResourceProvider: std::fmt::Debug + Send + Sync {
+    async fn fetch(&self, uri: &str) -> anyhow::Result<Bytes>;
+}
+
+/// Reads a document straight off the local filesystem. Accepts either a bare path or a
+/// `file://` URI.
+#[derive(Debug, Default, Clone)]
+pub struct FileProvider;
+
+#[async_trait]
+impl ResourceProvider for FileProvider {
+    async fn fetch(&self, uri: &str) -> anyhow::Result<Bytes> {
+        let path = uri.strip_prefix("file://").unwrap_or(uri);
+        let data = tokio::fs::read(path)
+            .await
+            .with_context(move || format!("can't read {path}"))?;
+        Ok(Bytes::from(data))
+    }
+}
+
+/// Hard ceiling on how many times a single fetch follows a redirect, so a malicious or
+/// misconfigured server can't bounce a request around indefinitely.
+const MAX_REDIRECTS: usize = 5;
+
+/// Hard ceiling on how many bytes a single fetch will buffer into memory, checked against both
+/// a `Content-Length` response header (when present) and the actual number of bytes streamed
+/// off the wire -- a server can lie about (or omit) `Content-Length`, so the response body is
+/// still read incrementally and aborted the moment this limit is crossed rather than trusting
+/// the header alone.
+const MAX_RESPONSE_BYTES: u64 = 500 * 1024 * 1024;
+
+/// Refuses to fetch a URI whose host resolves to a non-public address: loopback, RFC 1918/4193
+/// private ranges, link-local (which also covers cloud metadata endpoints like
+/// `169.254.169.254`), and other reserved ranges `IpAddr`'s own helpers classify as non-unicast.
+/// [`HttpProvider`] is the library default [`DefaultProvider`] hands any caller a
+/// caller-supplied URI through, so this check runs unconditionally rather than waiting for a
+/// specific endpoint to need it.
+fn is_blocked_ip(ip: &IpAddr) -> bool {
+    // `to_canonical()` unwraps an IPv4-mapped IPv6 address (`::ffff:a.b.c.d`) down to its plain
+    // `IpAddr::V4` form first -- without this, a literal like `::ffff:169.254.169.254` would
+    // skip every V4-only check below and sail straight through as if it were a real V6 address.
+    match ip.to_canonical() {
+        IpAddr::V4(ip) => {
+            ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || ip.is_documentation()
+        }
+        IpAddr::V6(ip) => {
+            ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                || ip.is_unique_local()
+                || ip.is_unicast_link_local()
+        }
+    }
+}
+
+/// Resolves `host`/`port` and refuses to proceed if any resolved address is blocked (see
+/// [`is_blocked_ip`]). Doesn't protect against DNS rebinding between this check and the
+/// connection `self.client` opens right after it, but does close off the common case of a
+/// caller-supplied URL pointing straight at an internal/metadata address.
+async fn ensure_host_is_public(host: &str, port: u16) -> anyhow::Result<()> {
+    let addrs = tokio::net::lookup_host((host, port))
+        .await
+        .with_context(|| format!("can't resolve host {host}"))?;
+    for addr in addrs {
+        if is_blocked_ip(&addr.ip()) {
+            anyhow::bail!("refusing to fetch from non-public address {}", addr.ip());
+        }
+    }
+    Ok(())
+}
+
+/// Fetches a document over HTTP(S), e.g. a presigned S3/GCS URL.
+#[derive(Debug, Clone)]
+pub struct HttpProvider {
+    client: reqwest::Client,
+}
+
+impl Default for HttpProvider {
+    fn default() -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .redirect(reqwest::redirect::Policy::limited(MAX_REDIRECTS))
+                .build()
+                .expect("can't build the default HTTP client"),
+        }
+    }
+}
+
+#[async_trait]
+impl ResourceProvider for HttpProvider {
+    async fn fetch(&self, uri: &str) -> anyhow::Result<Bytes> {
+        let url = reqwest::Url::parse(uri).with_context(|| format!("{uri} isn't a valid URL"))?;
+        if url.scheme() != "http" && url.scheme() != "https" {
+            anyhow::bail!("unsupported URL scheme in {uri}, only http(s) is allowed");
+        }
+        let host = url
+            .host_str()
+            .with_context(|| format!("{uri} has no host"))?;
+        let port = url.port_or_known_default().unwrap_or(443);
+        ensure_host_is_public(host, port).await?;
+
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .with_context(|| format!("request to {uri} failed"))?
+            .error_for_status()
+            .with_context(|| format!("{uri} returned an error status"))?;
+
+        if let Some(len) = response.content_length() {
+            if len > MAX_RESPONSE_BYTES {
+                anyhow::bail!(
+                    "{uri}'s response is {len} bytes, exceeding the {MAX_RESPONSE_BYTES} byte limit"
+                );
+            }
+        }
+
+        let mut body = Vec::new();
+        let mut stream = response.bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.with_context(|| format!("can't read response body from {uri}"))?;
+            if body.len() as u64 + chunk.len() as u64 > MAX_RESPONSE_BYTES {
+                anyhow::bail!(
+                    "{uri}'s response exceeds the {MAX_RESPONSE_BYTES} byte limit while streaming"
+                );
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(Bytes::from(body))
+    }
+}
+
+/// The provider [`crate::parse::native::ParseNativeQueue::new`] configures by default:
+/// dispatches to [`HttpProvider`] for `http://`/`https://` URIs and [`FileProvider`] for
+/// everything else.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct DefaultProvider {
+    file: FileProvider,
+    http: HttpProvider,
+}
+
+#[async_trait]
+impl ResourceProvider for DefaultProvider {
+    async fn fetch(&self, uri: &str) -> anyhow::Result<Bytes> {
+        if uri.starts_with("http://") || uri.starts_with("https://") {
+            self.http.fetch(uri).await
+        } else {
+            self.file.fetch(uri).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blocks_v4_loopback() {
+        assert!(is_blocked_ip(&"127.0.0.1".parse().unwrap()));
+    }
+
+    #[test]
+    fn blocks_v4_private() {
+        assert!(is_blocked_ip(&"10.0.0.1".parse().unwrap()));
+        assert!(is_blocked_ip(&"192.168.1.1".parse().unwrap()));
+    }
+
+    #[test]
+    fn blocks_v4_link_local() {
+        assert!(is_blocked_ip(&"169.254.169.254".parse().unwrap()));
+    }
+
+    #[test]
+    fn blocks_v4_documentation() {
+        assert!(is_blocked_ip(&"192.0.2.1".parse().unwrap()));
+    }
+
+    #[test]
+    fn blocks_v4_multicast() {
+        assert!(is_blocked_ip(&"224.0.0.1".parse().unwrap()));
+    }
+
+    #[test]
+    fn blocks_v6_loopback() {
+        assert!(is_blocked_ip(&"::1".parse().unwrap()));
+    }
+
+    #[test]
+    fn blocks_v6_unique_local() {
+        assert!(is_blocked_ip(&"fc00::1".parse().unwrap()));
+    }
+
+    #[test]
+    fn blocks_v6_link_local() {
+        assert!(is_blocked_ip(&"fe80::1".parse().unwrap()));
+    }
+
+    #[test]
+    fn blocks_v6_multicast() {
+        assert!(is_blocked_ip(&"ff02::1".parse().unwrap()));
+    }
+
+    #[test]
+    fn blocks_ipv4_mapped_v6_link_local() {
+        assert!(is_blocked_ip(&"::ffff:169.254.169.254".parse().unwrap()));
+    }
+
+    #[test]
+    fn blocks_ipv4_mapped_v6_loopback() {
+        assert!(is_blocked_ip(&"::ffff:127.0.0.1".parse().unwrap()));
+    }
+
+    #[test]
+    fn allows_public_v4_and_v6() {
+        assert!(!is_blocked_ip(&"8.8.8.8".parse().unwrap()));
+        assert!(!is_blocked_ip(&"2001:4860:4860::8888".parse().unwrap()));
+    }
+}
@@ -0,0 +1,132 @@
+//! Optional Lua scripting hooks for user-supplied text normalization and element
+//! reclassification, enabled via the `lua` cargo feature (`lua = ["dep:mlua"]`).
+//!
+//! A [`ScriptEngine`] loads a user's Lua chunk once and exposes two optional hooks the parser
+//! calls into:
+//!
+//! - `normalize_text(text, font_name, font_size) -> string`, applied to each
+//!   [`Line`](crate::entities::Line)'s text once its PDF text-layer spans are assembled, letting
+//!   a user fix domain ligatures or strip boilerplate the built-in cleanup doesn't know about.
+//! - `classify_element(kind, text, bbox) -> string`, applied to each
+//!   [`Element`](crate::entities::Element) once its text is fully accumulated (in this crate
+//!   that's after [`merge_lines_layout`](crate::parse::merge::merge_lines_layout) has pushed
+//!   every line into it, not at [`Element::from_layout_block`](crate::entities::Element::from_layout_block)
+//!   time, since the element carries no text yet at construction), letting a user override the
+//!   layout-label-derived [`ElementType`](crate::entities::ElementType) -- e.g. promote a short
+//!   all-caps `Text` block above a length threshold to `Subtitle`. `kind` and the return value
+//!   are the `ElementType` variant name as a string; `bbox` is passed as a table
+//!   `{x0, y0, x1, y1, width, height, area}`.
+//!
+//! Either hook is optional: a script defining only one of the two globals leaves the other at
+//! the parser's default behavior. A hook that errors, or returns a string that isn't a valid
+//! `ElementType` variant, logs a warning and falls through to the original value rather than
+//! failing the whole parse.
+//!
+//! A [`ScriptEngine`] is shared across a document's pages through an `Arc` held by
+//! [`FerrulesParseConfig`](crate::FerrulesParseConfig) and cloned into each page's spawned parse
+//! task, so the `lua` feature must enable `mlua`'s `"send"` feature for the `Lua` VM to cross
+//! that boundary.
+
+use std::path::Path;
+
+use anyhow::Context;
+use mlua::Lua;
+
+use crate::entities::BBox;
+
+/// Loaded user Lua script exposing the `normalize_text`/`classify_element` hooks described at
+/// the module level.
+pub struct ScriptEngine {
+    lua: Lua,
+    has_normalize_text: bool,
+    has_classify_element: bool,
+}
+
+impl ScriptEngine {
+    /// Compiles and runs `path`'s top-level statements (so a script can set up shared state),
+    /// then records which of the two recognized hook globals it defined.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let source = std::fs::read_to_string(path)
+            .with_context(|| format!("can't read script {}", path.display()))?;
+
+        let lua = Lua::new();
+        lua.load(&source)
+            .exec()
+            .with_context(|| format!("can't run script {}", path.display()))?;
+
+        let globals = lua.globals();
+        let has_normalize_text = globals.get::<mlua::Value>("normalize_text")?.is_function();
+        let has_classify_element = globals
+            .get::<mlua::Value>("classify_element")?
+            .is_function();
+
+        Ok(Self {
+            lua,
+            has_normalize_text,
+            has_classify_element,
+        })
+    }
+
+    /// Runs the `normalize_text` hook, if the script defined one. Returns `text` unchanged when
+    /// the hook isn't defined or when it errors (after logging a warning).
+    pub fn normalize_text(&self, text: &str, font_name: &str, font_size: f32) -> String {
+        if !self.has_normalize_text {
+            return text.to_string();
+        }
+
+        let result: mlua::Result<String> = self
+            .lua
+            .globals()
+            .get::<mlua::Function>("normalize_text")
+            .and_then(|f| f.call((text, font_name, font_size)));
+
+        match result {
+            Ok(normalized) => normalized,
+            Err(err) => {
+                tracing::warn!("normalize_text hook failed, using original text: {err}");
+                text.to_string()
+            }
+        }
+    }
+
+    /// Runs the `classify_element` hook, if the script defined one, and parses its return value
+    /// back into an [`ElementType`](crate::entities::ElementType) name. Returns `kind` unchanged
+    /// when the hook isn't defined, errors, or returns a string matching no known variant.
+    pub fn classify_element(&self, kind: &str, text: &str, bbox: &BBox) -> String {
+        if !self.has_classify_element {
+            return kind.to_string();
+        }
+
+        let result: mlua::Result<String> = (|| {
+            let bbox_table = self.lua.create_table()?;
+            bbox_table.set("x0", bbox.x0)?;
+            bbox_table.set("y0", bbox.y0)?;
+            bbox_table.set("x1", bbox.x1)?;
+            bbox_table.set("y1", bbox.y1)?;
+            bbox_table.set("width", bbox.width())?;
+            bbox_table.set("height", bbox.height())?;
+            bbox_table.set("area", bbox.area())?;
+
+            let classify: mlua::Function = self.lua.globals().get("classify_element")?;
+            classify.call::<String>((kind, text, bbox_table))
+        })();
+
+        match result {
+            Ok(reclassified)
+                if crate::entities::ElementType::from_name(&reclassified).is_some() =>
+            {
+                reclassified
+            }
+            Ok(reclassified) => {
+                tracing::warn!(
+                    "classify_element hook returned unknown kind {reclassified:?}, keeping original kind"
+                );
+                kind.to_string()
+            }
+            Err(err) => {
+                tracing::warn!("classify_element hook failed, keeping original kind: {err}");
+                kind.to_string()
+            }
+        }
+    }
+}
@@ -1,29 +1,189 @@
 use crate::{
     blocks,
     entities::ParsedDocument,
-    render::{html::to_html, markdown::to_markdown},
+    render::writer::{DocumentWriter, HtmlWriter, JsonWriter, MarkdownWriter},
 };
 
 const IMAGE_PADDING: u32 = 5;
 use anyhow::Context;
 use colored::*;
 use pdfium_render::prelude::Pdfium;
+use serde::{Deserialize, Serialize};
 use std::{
-    fs::{create_dir, File},
-    io::{BufWriter, Write},
+    collections::HashMap,
+    fs,
+    io::Write,
     ops::Range,
     path::{Path, PathBuf},
     str::FromStr,
+    time::SystemTime,
 };
+use xxhash_rust::xxh3::Xxh3;
+
+/// Name of the per-result-directory manifest tracking, for every file ferrules itself wrote,
+/// the content hash and timestamp of that write -- so a later run can tell "unchanged since we
+/// wrote it" apart from "edited by the user since our last run".
+const MANIFEST_FILE: &str = ".ferrules-manifest";
+
+/// Name of the advisory lock file held for the lifetime of a single run, guarding a result
+/// directory against two ferrules processes writing to it at once.
+const LOCK_FILE: &str = ".ferrules.lock";
+
+/// Advisory, exclusive lock on a result directory, acquired by [`create_dirs`] and held until
+/// the returned value is dropped (normally at the end of [`save_parsed_document`]). Unlocked
+/// automatically when the underlying file handle closes.
+#[cfg(any(windows, target_os = "linux", target_os = "macos"))]
+pub struct ResultDirLock(fs::File);
+
+/// No-op stand-in on targets `fs2` doesn't support advisory locking on; locking degrades
+/// gracefully instead of failing the build.
+#[cfg(not(any(windows, target_os = "linux", target_os = "macos")))]
+pub struct ResultDirLock;
+
+#[cfg(any(windows, target_os = "linux", target_os = "macos"))]
+fn lock_result_dir(res_dir_path: &Path) -> anyhow::Result<ResultDirLock> {
+    use fs2::FileExt;
+
+    let lock_file = fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .open(res_dir_path.join(LOCK_FILE))
+        .context("can't open result directory lock file")?;
+
+    lock_file.try_lock_exclusive().map_err(|_| {
+        anyhow::anyhow!(
+            "{} is already locked by another ferrules run",
+            res_dir_path.display()
+        )
+    })?;
+
+    Ok(ResultDirLock(lock_file))
+}
+
+#[cfg(not(any(windows, target_os = "linux", target_os = "macos")))]
+fn lock_result_dir(_res_dir_path: &Path) -> anyhow::Result<ResultDirLock> {
+    Ok(ResultDirLock)
+}
+
+/// Sibling `<path>.tmp` path used by [`atomic_write`] (and, for writers that stream straight to
+/// disk, [`run_writer`]) to stage a file before it's renamed into place.
+fn tmp_path_for(path: &Path) -> PathBuf {
+    path.with_extension(match path.extension() {
+        Some(ext) => format!("{}.tmp", ext.to_string_lossy()),
+        None => "tmp".to_owned(),
+    })
+}
+
+/// Writes `bytes` to `path` crash-safely: the data lands in a sibling `<path>.tmp` file first,
+/// gets `flush`ed and `sync_all`ed to disk, and only then is renamed into place. A crash or kill
+/// mid-write leaves the stale `.tmp` file (or nothing) rather than a truncated file at `path`
+/// that looks complete to downstream tooling.
+pub(crate) fn atomic_write(path: &Path, bytes: &[u8]) -> anyhow::Result<()> {
+    let tmp_path = tmp_path_for(path);
+    let mut tmp_file = fs::File::create(&tmp_path)?;
+    tmp_file.write_all(bytes)?;
+    tmp_file.flush()?;
+    tmp_file.sync_all()?;
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+fn content_hash(bytes: &[u8]) -> u64 {
+    let mut hasher = Xxh3::new();
+    hasher.update(bytes);
+    hasher.digest()
+}
+
+fn system_time_to_secs(t: SystemTime) -> u64 {
+    t.duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// One file's content hash and write time, as of the last ferrules run that wrote it.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct ManifestEntry {
+    hash: u64,
+    written_at_secs: u64,
+}
+
+/// Tracks, per result file (keyed by its path relative to the result directory), what ferrules
+/// itself last wrote there. Lets [`write_if_changed`] tell an untouched file (safe to skip or
+/// overwrite) apart from one a user has hand-edited since (refuse to clobber).
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct ResultManifest {
+    files: HashMap<String, ManifestEntry>,
+}
+
+impl ResultManifest {
+    fn load(res_dir_path: &Path) -> Self {
+        fs::read(res_dir_path.join(MANIFEST_FILE))
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, res_dir_path: &Path) -> anyhow::Result<()> {
+        let bytes = serde_json::to_vec(self)?;
+        atomic_write(&res_dir_path.join(MANIFEST_FILE), &bytes)
+    }
+}
+
+/// Writes `bytes` to `full_path` unless doing so would be a no-op or would clobber a user edit.
+///
+/// Skips the write outright when the on-disk content already hashes the same as `bytes`. When
+/// the content differs, overwrites only if the manifest has no record of this file or its
+/// on-disk mtime is no newer than the last write ferrules recorded for it; otherwise the file
+/// has been touched since our last run, so the write is refused with a warning instead of
+/// silently destroying the edit.
+fn write_if_changed(
+    full_path: &Path,
+    rel_key: &str,
+    bytes: &[u8],
+    manifest: &mut ResultManifest,
+) -> anyhow::Result<()> {
+    let new_hash = content_hash(bytes);
+
+    if let Ok(existing) = fs::read(full_path) {
+        if content_hash(&existing) == new_hash {
+            return Ok(());
+        }
+
+        if let Some(prev) = manifest.files.get(rel_key) {
+            let on_disk_mtime = system_time_to_secs(fs::metadata(full_path)?.modified()?);
+            if on_disk_mtime > prev.written_at_secs {
+                println!(
+                    "{} {} was edited since the last run, skipping to avoid overwriting it",
+                    "⚠".yellow().bold(),
+                    full_path.display().to_string().yellow().underline()
+                );
+                return Ok(());
+            }
+        }
+    }
+
+    atomic_write(full_path, bytes)?;
+    manifest.files.insert(
+        rel_key.to_owned(),
+        ManifestEntry {
+            hash: new_hash,
+            written_at_secs: system_time_to_secs(SystemTime::now()),
+        },
+    );
+    Ok(())
+}
 
 pub fn get_doc_length<P: AsRef<Path>>(
     path: P,
     password: Option<&str>,
     page_range: Option<Range<usize>>,
 ) -> anyhow::Result<usize> {
-    // TODO : This panic ! should be handlered
-    let pdfium = Pdfium::new(Pdfium::bind_to_statically_linked_library().unwrap());
-    let document = pdfium.load_pdf_from_file(&path, password).unwrap();
+    let pdfium = Pdfium::new(
+        Pdfium::bind_to_statically_linked_library().expect("can't load pdfium bindings"),
+    );
+    let document = pdfium
+        .load_pdf_from_file(&path, password)
+        .context("can't load pdf document (wrong password or corrupt file?)")?;
     let pages: Vec<_> = document.pages().iter().enumerate().collect();
     match page_range {
         Some(range) => {
@@ -55,42 +215,62 @@ fn sanitize_doc_name(doc_name: &str) -> String {
         .collect::<String>()
 }
 
-fn save_doc_images(imgs_dir: &Path, doc: &ParsedDocument) -> anyhow::Result<()> {
+fn save_doc_images(
+    imgs_dir: &Path,
+    doc: &ParsedDocument,
+    manifest: &mut ResultManifest,
+) -> anyhow::Result<()> {
     for block in doc.blocks.iter() {
         match &block.kind {
             blocks::BlockType::Image(img_block) => {
-                let page_id = block.pages_id.first().unwrap();
-                match doc.pages.iter().find(|&p| p.id == *page_id) {
-                    Some(page) => {
-                        assert!(page.height as u32 > 0);
-                        assert!(page.width as u32 > 0);
-
-                        let x = (block.bbox.x0 - IMAGE_PADDING as f32) as u32;
-                        let y = (block.bbox.y0 - IMAGE_PADDING as f32) as u32;
-                        let width = (block.bbox.width().max(1.0) as u32 + 2 * IMAGE_PADDING)
-                            .min(page.width as u32);
-                        let height = (block.bbox.height().max(1.0) as u32 + 2 * IMAGE_PADDING)
-                            .min(page.height as u32);
-
-                        let crop = page.image.clone().crop(x, y, width, height);
-
-                        let output_file = imgs_dir.join(img_block.path());
-                        crop.save(output_file)?;
+                // Prefer the PDF's own native-resolution image XObject (see
+                // `parse::images::extract_native_images`) over cropping the page raster, since
+                // that raster was downscaled/rescaled for layout inference and would otherwise
+                // upscale the figure back out.
+                let image = if let Some(native_image) = &img_block.native_image {
+                    native_image.clone()
+                } else {
+                    let page_id = block.pages_id.first().unwrap();
+                    match doc.pages.iter().find(|&p| p.id == *page_id) {
+                        Some(page) => {
+                            assert!(page.height as u32 > 0);
+                            assert!(page.width as u32 > 0);
+
+                            let x = (block.bbox.x0 - IMAGE_PADDING as f32) as u32;
+                            let y = (block.bbox.y0 - IMAGE_PADDING as f32) as u32;
+                            let width = (block.bbox.width().max(1.0) as u32 + 2 * IMAGE_PADDING)
+                                .min(page.width as u32);
+                            let height = (block.bbox.height().max(1.0) as u32 + 2 * IMAGE_PADDING)
+                                .min(page.height as u32);
+
+                            page.image.clone().crop(x, y, width, height)
+                        }
+                        None => continue,
                     }
-                    None => continue,
-                }
+                };
+
+                let mut bytes = Vec::new();
+                image.write_to(
+                    &mut std::io::Cursor::new(&mut bytes),
+                    image::ImageFormat::Png,
+                )?;
+
+                let rel_key = format!("figures/{}", img_block.path());
+                let output_file = imgs_dir.join(img_block.path());
+                write_if_changed(&output_file, &rel_key, &bytes, manifest)?;
             }
-            blocks::BlockType::Table => todo!(),
             _ => continue,
         }
     }
     Ok(())
 }
-fn recreate_result_dir(result_dir_name: &Path) -> anyhow::Result<PathBuf> {
-    if std::fs::create_dir(result_dir_name).is_err() {
-        std::fs::remove_dir_all(result_dir_name)?;
-        std::fs::create_dir(result_dir_name)?;
-    };
+
+/// Makes sure `result_dir_name` exists without touching anything already inside it -- unlike
+/// the old behavior of `remove_dir_all`-ing the directory on every run, which silently
+/// destroyed hand-edited output. Existing content is left for [`write_if_changed`] to reconcile
+/// file-by-file.
+fn ensure_result_dir(result_dir_name: &Path) -> anyhow::Result<PathBuf> {
+    fs::create_dir_all(result_dir_name)?;
     Ok(result_dir_name.to_owned())
 }
 
@@ -99,31 +279,68 @@ pub fn create_dirs<P: AsRef<Path>>(
     doc_name: &str,
     debug: bool,
     save_imgs: bool,
-) -> anyhow::Result<(PathBuf, Option<PathBuf>)> {
+) -> anyhow::Result<(PathBuf, Option<PathBuf>, ResultDirLock)> {
     let result_dir_name = format!("{}-results", sanitize_doc_name(doc_name));
     let res_dir_path = match output_dir {
         Some(p) => {
             let result_dir_path = p.as_ref().to_owned().join(&result_dir_name);
-            recreate_result_dir(&result_dir_path)?
+            ensure_result_dir(&result_dir_path)?
         }
         None => {
             let res_dir_path = PathBuf::from(format!("./{}", &result_dir_name));
-            recreate_result_dir(&res_dir_path)?
+            ensure_result_dir(&res_dir_path)?
         }
     };
+    let dir_lock = lock_result_dir(&res_dir_path)?;
+
     if save_imgs {
         let debug_path = res_dir_path.join("figures");
-        create_dir(&debug_path).context("cant create debug path")?;
+        fs::create_dir_all(&debug_path).context("cant create debug path")?;
     }
 
     let debug_path = if debug {
         let debug_path = res_dir_path.join("debug");
-        create_dir(&debug_path).context("cant create debug path")?;
+        fs::create_dir_all(&debug_path).context("cant create debug path")?;
         Some(debug_path)
     } else {
         None
     };
-    Ok((res_dir_path, debug_path))
+    Ok((res_dir_path, debug_path, dir_lock))
+}
+
+/// Runs `writer` over `doc` and reconciles its output against `res_dir_path`/`<sanitized_doc_name>.<ext>`.
+///
+/// Buffered writers (the default) render into memory first so [`write_if_changed`] can hash-compare
+/// against the existing file; writers that opt out via [`DocumentWriter::buffered`] (namely
+/// [`crate::render::writer::JsonlWriter`]) stream straight into the destination file instead,
+/// which is the entire reason they exist, at the cost of always rewriting the file.
+fn run_writer(
+    writer: &dyn DocumentWriter,
+    doc: &ParsedDocument,
+    res_dir_path: &Path,
+    sanitized_doc_name: &str,
+    fig_path: &Path,
+    manifest: &mut ResultManifest,
+) -> anyhow::Result<()> {
+    let ext = writer.extension();
+    let file_name = format!("{sanitized_doc_name}.{ext}");
+    let file_out = res_dir_path.join(&file_name);
+
+    if writer.buffered() {
+        let mut bytes = Vec::new();
+        writer.write(doc, &mut bytes, Some(fig_path))?;
+        write_if_changed(&file_out, &file_name, &bytes, manifest)?;
+    } else {
+        let tmp_out = tmp_path_for(&file_out);
+        let file = fs::File::create(&tmp_out)?;
+        let mut out = std::io::BufWriter::new(file);
+        writer.write(doc, &mut out, Some(fig_path))?;
+        out.flush()?;
+        out.get_ref().sync_all()?;
+        fs::rename(&tmp_out, &file_out)?;
+        manifest.files.remove(&file_name);
+    }
+    Ok(())
 }
 
 pub fn save_parsed_document(
@@ -132,19 +349,25 @@ pub fn save_parsed_document(
     save_imgs: bool,
     save_html: bool,
     save_markdown: bool,
+    // Held for the duration of the save so a concurrent run targeting the same result
+    // directory fails fast in `create_dirs` instead of racing us; released on drop.
+    _dir_lock: ResultDirLock,
 ) -> anyhow::Result<()> {
     let sanitized_doc_name = sanitize_doc_name(&doc.doc_name);
-    // Save json
-    let file_out = res_dir_path.join(format!("{}.json", &sanitized_doc_name));
-    let file = File::create(&file_out)?;
-    let mut writer = BufWriter::new(file);
-    let doc_json = serde_json::to_string(&doc)?;
-    writer.write_all(doc_json.as_bytes())?;
-    // TODO: this is shit, refac
+    let mut manifest = ResultManifest::load(&res_dir_path);
     let fig_path = PathBuf::from_str("figures").unwrap();
 
-    if save_imgs {
-        save_doc_images(&res_dir_path.join(&fig_path), doc).context("can't save the doc images")?;
+    let mut writers: Vec<Box<dyn DocumentWriter>> = vec![Box::new(JsonWriter)];
+    if save_html {
+        writers.push(Box::new(HtmlWriter));
+    }
+    if save_markdown {
+        writers.push(Box::new(MarkdownWriter));
+    }
+
+    if save_imgs || save_html || save_markdown {
+        save_doc_images(&res_dir_path.join(&fig_path), doc, &mut manifest)
+            .context("can't save the doc images")?;
     }
 
     if let Some(dbg_path) = &doc.debug_path {
@@ -155,25 +378,21 @@ pub fn save_parsed_document(
         );
     }
 
-    if save_html {
-        if !save_imgs {
-            save_doc_images(&res_dir_path.join(&fig_path), doc)
-                .context("can't save the doc images")?;
-        }
-        let html_content = to_html(doc, &doc.doc_name, Some(fig_path.clone())).unwrap();
-        let html_file_out = res_dir_path.join(format!("{}.html", sanitized_doc_name));
-        let file = File::create(&html_file_out)?;
-        let mut writer = BufWriter::new(file);
-        writer.write_all(html_content.as_bytes())?;
+    for writer in &writers {
+        run_writer(
+            writer.as_ref(),
+            doc,
+            &res_dir_path,
+            &sanitized_doc_name,
+            &fig_path,
+            &mut manifest,
+        )?;
     }
 
-    if save_markdown {
-        let md_content = to_markdown(doc, &doc.doc_name, Some(fig_path.clone())).unwrap();
-        let html_file_out = res_dir_path.join(format!("{}.md", sanitized_doc_name));
-        let file = File::create(&html_file_out)?;
-        let mut writer = BufWriter::new(file);
-        writer.write_all(md_content.as_bytes())?;
-    }
+    manifest
+        .save(&res_dir_path)
+        .context("can't save the result manifest")?;
+
     println!(
         "{} Results saved in: {}",
         "✓".green().bold(),